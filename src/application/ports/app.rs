@@ -2,7 +2,15 @@ use std::{fmt::Debug, sync::Arc};
 
 use crate::application::{
     ports::outbound::{
-        action_repository::{CreateActionRepository, HandleActionRepository},
+        action_repository::{CreateActionRepository, HandleActionRepository, ScheduledActionRepository, UpdateActionRepository},
+        device_group_repository::{
+            CreateDeviceGroupRepository, DeleteDeviceGroupRepository, GetDeviceGroupRepository,
+            UpdateDeviceGroupRepository,
+        },
+        device_model_repository::{
+            CreateDeviceModelRepository, DeleteDeviceModelRepository, GetDeviceModelRepository,
+            UpdateDeviceModelRepository,
+        },
         device_repository::{
             CreateDeviceRepository, DeleteDeviceRepository, GetDeviceRepository,
             UpdateDeviceRepository,
@@ -12,10 +20,16 @@ use crate::application::{
             UpdateDeviceStateRepository,
         },
         event_repository::{CreateEventRepository, GetEventRepository},
+        rule_repository::{
+            CreateRuleRepository, DeleteRuleRepository, GetRuleRepository, UpdateRuleRepository,
+        },
     },
     usecases::{
         manage_action::ManageActionService, manage_device::ManageDeviceService,
+        manage_device_group::ManageDeviceGroupService,
+        manage_device_model::ManageDeviceModelService,
         manage_device_state::ManageDeviceStateService, manage_event::ManageEventService,
+        manage_rule::ManageRuleService,
     },
 };
 
@@ -28,6 +42,29 @@ pub trait AppOutbound: Send + Sync + Clone + Debug {
             impl GetDeviceRepository,
             impl UpdateDeviceRepository,
             impl DeleteDeviceRepository,
+            impl GetDeviceModelRepository,
+        >,
+    >;
+    fn get_device_model_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceModelService<
+            impl CreateDeviceModelRepository,
+            impl GetDeviceModelRepository,
+            impl UpdateDeviceModelRepository,
+            impl DeleteDeviceModelRepository,
+            impl GetDeviceRepository,
+            impl UpdateDeviceRepository,
+        >,
+    >;
+    fn get_device_group_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceGroupService<
+            impl CreateDeviceGroupRepository,
+            impl GetDeviceGroupRepository,
+            impl UpdateDeviceGroupRepository,
+            impl DeleteDeviceGroupRepository,
         >,
     >;
     fn get_device_state_service(
@@ -45,7 +82,17 @@ pub trait AppOutbound: Send + Sync + Clone + Debug {
     ) -> &Arc<ManageEventService<impl CreateEventRepository, impl GetEventRepository>>;
     fn get_action_service(
         &self,
-    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository>>;
+    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository, impl UpdateActionRepository, impl ScheduledActionRepository>>;
+    fn get_rule_service(
+        &self,
+    ) -> &Arc<
+        ManageRuleService<
+            impl CreateRuleRepository,
+            impl GetRuleRepository,
+            impl UpdateRuleRepository,
+            impl DeleteRuleRepository,
+        >,
+    >;
 }
 
 pub trait AppInbound {
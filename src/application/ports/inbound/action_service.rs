@@ -1,13 +1,22 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::domain::{
-    action::{action::Action, action_format::ActionFormat},
+    action::{
+        action::Action,
+        action_data_value::ActionDataValue,
+        action_format::ActionFormat,
+        action_schedule::{ActionSchedule, ScheduleRun},
+        action_status::ActionStatus,
+    },
+    device::Device,
 };
 
 #[derive(Debug)]
 pub enum ActionServiceError {
+    NotFound,
     InvalidInput(String),
     InternalError(String),
 }
@@ -15,6 +24,7 @@ pub enum ActionServiceError {
 impl Display for ActionServiceError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
+            ActionServiceError::NotFound => f.write_str("Action not found"),
             ActionServiceError::InvalidInput(v) => f.write_str(&format!("Invalid input: {}", &v)),
             ActionServiceError::InternalError(v) => f.write_str(&format!("Internal error: {}", &v)),
         }
@@ -22,10 +32,41 @@ impl Display for ActionServiceError {
 }
 
 pub trait ActionService {
+    /// Sends the action, or keeps it until its scheduled time.
     async fn send_action(
         &self,
         event: Action,
         event_format: &ActionFormat,
     ) -> Result<(), ActionServiceError>;
+    /// Builds the action from its values, validated against the action of the device, and sends it.
+    async fn send_device_action(
+        &self,
+        device: &Device,
+        action_name: &str,
+        payload: HashMap<String, ActionDataValue>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Action, ActionServiceError>;
     async fn get_actions(&self, device_id: &str) -> Result<Vec<Action>, ActionServiceError>;
+    async fn get_action(&self, action_id: Uuid) -> Result<Action, ActionServiceError>;
+    /// Moves an action of the device to `status`, fails if its lifecycle does not allow it.
+    async fn update_action_status(
+        &self,
+        device_id: &str,
+        action_id: Uuid,
+        status: ActionStatus,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Action, ActionServiceError>;
+    async fn get_scheduled_actions(&self, device_id: Option<&str>) -> Result<Vec<Action>, ActionServiceError>;
+    /// Removes an action not sent yet, returns it.
+    async fn cancel_scheduled_action(&self, action_id: Uuid) -> Result<Action, ActionServiceError>;
+    /// Stores a recurring schedule, its actions are sent with `action_format`.
+    async fn create_action_schedule(
+        &self,
+        schedule: ActionSchedule,
+        action_format: &ActionFormat,
+    ) -> Result<(), ActionServiceError>;
+    async fn get_action_schedules(&self, device_id: Option<&str>) -> Result<Vec<ActionSchedule>, ActionServiceError>;
+    /// Removes the schedule and its run history, returns it.
+    async fn delete_action_schedule(&self, schedule_id: Uuid) -> Result<ActionSchedule, ActionServiceError>;
+    async fn get_schedule_runs(&self, schedule_id: Uuid) -> Result<Vec<ScheduleRun>, ActionServiceError>;
 }
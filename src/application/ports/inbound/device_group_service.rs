@@ -0,0 +1,47 @@
+use std::{collections::BTreeSet, fmt::Display};
+
+use uuid::Uuid;
+
+use crate::domain::device_group::DeviceGroup;
+
+pub enum DeviceGroupServiceError {
+    NotFound,
+    AlreadyExists,
+    InvalidInput(String),
+    InternalError(String),
+}
+
+impl Display for DeviceGroupServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceGroupServiceError::NotFound => write!(f, "device group not found"),
+            DeviceGroupServiceError::AlreadyExists => write!(f, "device group already exists"),
+            DeviceGroupServiceError::InvalidInput(e) => write!(f, "invalid input provided: {}", e),
+            DeviceGroupServiceError::InternalError(e) => write!(f, "internal error: {}", e),
+        }
+    }
+}
+
+pub trait DeviceGroupService {
+    async fn create_device_group(&self, group: &DeviceGroup) -> Result<DeviceGroup, DeviceGroupServiceError>;
+    async fn get_device_group(&self, id: Uuid) -> Result<Option<DeviceGroup>, DeviceGroupServiceError>;
+    async fn get_device_groups_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<DeviceGroup>, DeviceGroupServiceError>;
+    async fn get_device_group_children(&self, id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupServiceError>;
+    /// Groups with sub-groups cannot be deleted.
+    async fn delete_device_group(&self, id: Uuid) -> Result<(), DeviceGroupServiceError>;
+    /// A `parent_id` of `Some(None)` makes the group a root group, `device_ids` replaces the members.
+    async fn update_device_group(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        parent_id: Option<Option<Uuid>>,
+        device_ids: Option<BTreeSet<Uuid>>,
+    ) -> Result<DeviceGroup, DeviceGroupServiceError>;
+    async fn add_device_to_group(&self, id: Uuid, device_id: Uuid) -> Result<DeviceGroup, DeviceGroupServiceError>;
+    async fn remove_device_from_group(&self, id: Uuid, device_id: Uuid) -> Result<DeviceGroup, DeviceGroupServiceError>;
+    /// Devices of the group and of all its sub-groups.
+    async fn get_device_group_device_ids(&self, id: Uuid) -> Result<BTreeSet<Uuid>, DeviceGroupServiceError>;
+}
@@ -0,0 +1,45 @@
+use std::{collections::HashMap, fmt::Display};
+
+use uuid::Uuid;
+
+use crate::domain::{action::action_emittable::ActionEmittable, device_model::DeviceModel, event::event_emittable::EventEmittable};
+
+pub enum DeviceModelServiceError {
+    NotFound,
+    AlreadyExists,
+    /// Devices still use the model, they are detached from it before it is deleted.
+    InUse,
+    InvalidInput(String),
+    InternalError(String),
+}
+
+impl Display for DeviceModelServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DeviceModelServiceError::NotFound => write!(f, "device model not found"),
+            DeviceModelServiceError::AlreadyExists => write!(f, "device model already exists"),
+            DeviceModelServiceError::InUse => write!(f, "device model is used by devices"),
+            DeviceModelServiceError::InvalidInput(e) => write!(f, "invalid input provided: {}", e),
+            DeviceModelServiceError::InternalError(e) => write!(f, "internal error: {}", e),
+        }
+    }
+}
+
+pub trait DeviceModelService {
+    async fn create_device_model(&self, model: &DeviceModel) -> Result<DeviceModel, DeviceModelServiceError>;
+    async fn get_device_model(&self, id: Uuid) -> Result<Option<DeviceModel>, DeviceModelServiceError>;
+    async fn get_device_models_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<DeviceModel>, DeviceModelServiceError>;
+    /// Fails while devices use the model, their emittables would be lost.
+    async fn delete_device_model(&self, id: Uuid) -> Result<(), DeviceModelServiceError>;
+    /// Changes apply to every device using the model the next time it is loaded.
+    async fn update_device_model(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        events: Option<HashMap<String, EventEmittable>>,
+        actions: Option<HashMap<String, ActionEmittable>>,
+    ) -> Result<DeviceModel, DeviceModelServiceError>;
+}
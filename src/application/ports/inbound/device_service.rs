@@ -1,13 +1,13 @@
-use std::{collections::HashMap, fmt::Display};
+use std::{collections::{BTreeSet, HashMap}, fmt::Display};
 
 use uuid::Uuid;
 
-use crate::domain::{action::action_emittable::ActionEmittable, device::Device, event::event_emittable::EventEmittable};
+use crate::domain::{action::action_emittable::ActionEmittable, device::Device, device_filter::DeviceFilter, device_schema::{DeviceSchema, SchemaDiff}, event::event_emittable::EventEmittable, state::StateMergePolicy, connectivity::Connectivity};
 
 pub enum DeviceServiceError {
     NotFound,
     AlreadyExists,
-    InvalidInput,
+    InvalidInput(String),
     InternalError(String),
 }
 
@@ -16,12 +16,28 @@ impl Display for DeviceServiceError {
         match self {
             DeviceServiceError::NotFound => write!(f, "device not found"),
             DeviceServiceError::AlreadyExists => write!(f, "device already exists"),
-            DeviceServiceError::InvalidInput => write!(f, "invalid input provided"),
+            DeviceServiceError::InvalidInput(e) => write!(f, "invalid input provided: {}", e),
             DeviceServiceError::InternalError(e) => write!(f, "internal error: {}", e),
         }
     }
 }
 
+/// Changes to a device, fields left to `None` are kept as stored. A `model_id` of `Some(None)`
+/// detaches the device from its model, a `heartbeat_interval` of `Some(None)` stops monitoring it.
+#[derive(Debug, Default)]
+pub struct DeviceUpdate {
+    pub physical_id: Option<String>,
+    pub name: Option<String>,
+    pub events: Option<HashMap<String, EventEmittable>>,
+    pub actions: Option<HashMap<String, ActionEmittable>>,
+    pub descriptor_set: Option<Vec<u8>>,
+    pub model_id: Option<Option<Uuid>>,
+    pub labels: Option<HashMap<String, String>>,
+    pub tags: Option<BTreeSet<String>>,
+    pub state_merge_policy: Option<StateMergePolicy>,
+    pub heartbeat_interval: Option<Option<u64>>,
+}
+
 pub trait DeviceService {
     async fn create_device(&self, device: &Device) -> Result<Device, DeviceServiceError>;
     async fn get_device(&self, id: Uuid) -> Result<Option<Device>, DeviceServiceError>;
@@ -33,13 +49,21 @@ pub trait DeviceService {
         &self,
         physical_id: &str,
     ) -> Result<Option<Device>, DeviceServiceError>;
+    async fn get_devices_by_filter(
+        &self,
+        user_id: Uuid,
+        filter: &DeviceFilter,
+    ) -> Result<Vec<Device>, DeviceServiceError>;
+    /// Devices having a heartbeat interval, of every user.
+    async fn get_monitored_devices(&self) -> Result<Vec<Device>, DeviceServiceError>;
     async fn delete_device(&self, id: Uuid) -> Result<(), DeviceServiceError>;
-    async fn update_device(
+    async fn update_device(&self, id: Uuid, update: DeviceUpdate) -> Result<Device, DeviceServiceError>;
+    async fn set_connectivity(&self, id: Uuid, connectivity: Connectivity) -> Result<Device, DeviceServiceError>;
+    async fn get_device_schemas(&self, id: Uuid) -> Result<Vec<DeviceSchema>, DeviceServiceError>;
+    async fn diff_device_schemas(
         &self,
         id: Uuid,
-        physical_id: Option<String>,
-        name: Option<String>,
-        event: Option<HashMap<String, EventEmittable>>,
-        actions: Option<HashMap<String, ActionEmittable>>
-    ) -> Result<Device, DeviceServiceError>;
+        from: u32,
+        to: u32,
+    ) -> Result<SchemaDiff, DeviceServiceError>;
 }
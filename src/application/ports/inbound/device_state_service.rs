@@ -2,13 +2,13 @@ use std::collections::HashMap;
 
 use uuid::Uuid;
 
-use crate::domain::{event::event_data_value::EventDataValue, state::DeviceState};
+use crate::domain::{action::action::Action, device::Device, event::event_data_value::EventDataValue, state::{DeviceState, StateMergePolicy, StateValue}};
 
 pub enum DeviceStateServiceError {
     DeviceNotFound,
     DeviceStateNotFound,
     AlreadyExists,
-    InvalidInput,
+    InvalidInput(String),
     InternalError(String),
 }
 
@@ -18,7 +18,7 @@ impl std::fmt::Display for DeviceStateServiceError {
             DeviceStateServiceError::DeviceNotFound => write!(f, "Device not found"),
             DeviceStateServiceError::DeviceStateNotFound => write!(f, "Device state not found"),
             DeviceStateServiceError::AlreadyExists => write!(f, "Device state already exists"),
-            DeviceStateServiceError::InvalidInput => write!(f, "Invalid input provided"),
+            DeviceStateServiceError::InvalidInput(s) => write!(f, "Invalid input: {}", s),
             DeviceStateServiceError::InternalError(s) => write!(f, "Internal error: {}", s),
         }
     }
@@ -28,16 +28,25 @@ pub trait DeviceStateService {
     async fn create_device_state(
         &self,
         device_id: Uuid,
-        values: HashMap<String, EventDataValue>,
+        values: HashMap<String, StateValue>,
     ) -> Result<DeviceState, DeviceStateServiceError>;
     async fn get_device_state(
         &self,
         id: Uuid,
     ) -> Result<Option<DeviceState>, DeviceStateServiceError>;
     async fn delete_device_state(&self, id: Uuid) -> Result<(), DeviceStateServiceError>;
+    /// Merges the given keys following `policy`, the others keep their value and source.
     async fn update_device_state(
         &self,
         id: Uuid,
-        values: HashMap<String, EventDataValue>,
+        values: HashMap<String, StateValue>,
+        policy: StateMergePolicy,
     ) -> Result<DeviceState, DeviceStateServiceError>;
+    /// Replaces the desired values of the device, returns its state and the actions to send
+    /// for the reported values to converge.
+    async fn set_desired_state(
+        &self,
+        device: &Device,
+        desired: HashMap<String, EventDataValue>,
+    ) -> Result<(DeviceState, Vec<Action>), DeviceStateServiceError>;
 }
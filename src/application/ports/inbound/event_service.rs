@@ -1,5 +1,7 @@
 use std::fmt::Display;
 
+use chrono::{DateTime, Utc};
+
 use crate::domain::event::{event::Event, event_format::EventFormat};
 
 #[derive(Debug)]
@@ -24,4 +26,5 @@ pub trait EventService {
         event_format: &EventFormat,
     ) -> Result<(), EventServiceError>;
     async fn get_events(&self, device_physical_id: &str) -> Result<Vec<Event>, EventServiceError>;
+    async fn get_last_event_time(&self, device_physical_id: &str) -> Result<Option<DateTime<Utc>>, EventServiceError>;
 }
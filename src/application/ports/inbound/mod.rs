@@ -1,4 +1,7 @@
 pub mod action_service;
+pub mod device_group_service;
+pub mod device_model_service;
 pub mod device_service;
 pub mod device_state_service;
-pub mod event_service;
\ No newline at end of file
+pub mod event_service;
+pub mod rule_service;
\ No newline at end of file
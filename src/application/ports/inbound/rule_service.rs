@@ -0,0 +1,60 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::domain::{
+    event::event::Event,
+    rule::{Rule, RuleAction, RuleCondition},
+    state::DeviceState,
+};
+
+pub enum RuleServiceError {
+    NotFound,
+    AlreadyExists,
+    InvalidInput(String),
+    InternalError(String),
+}
+
+impl Display for RuleServiceError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RuleServiceError::NotFound => write!(f, "rule not found"),
+            RuleServiceError::AlreadyExists => write!(f, "rule already exists"),
+            RuleServiceError::InvalidInput(e) => write!(f, "invalid input provided: {}", e),
+            RuleServiceError::InternalError(e) => write!(f, "internal error: {}", e),
+        }
+    }
+}
+
+/// Changes to a rule, fields left to `None` are kept as stored. An `event_name` of `Some(None)`
+/// evaluates every event of the device.
+#[derive(Debug, Default)]
+pub struct RuleUpdate {
+    pub name: Option<String>,
+    pub event_name: Option<Option<String>>,
+    pub conditions: Option<Vec<RuleCondition>>,
+    pub action: Option<RuleAction>,
+    pub debounce: Option<u64>,
+    pub cooldown: Option<u64>,
+    pub enabled: Option<bool>,
+}
+
+pub trait RuleService {
+    async fn create_rule(&self, rule: &Rule) -> Result<Rule, RuleServiceError>;
+    async fn get_rule(&self, id: Uuid) -> Result<Option<Rule>, RuleServiceError>;
+    async fn get_rules_by_user_id(&self, user_id: Uuid) -> Result<Vec<Rule>, RuleServiceError>;
+    async fn get_rules_by_device_id(&self, device_id: Uuid) -> Result<Vec<Rule>, RuleServiceError>;
+    async fn delete_rule(&self, id: Uuid) -> Result<(), RuleServiceError>;
+    /// Changing the event name or the conditions restarts the debounce.
+    async fn update_rule(&self, id: Uuid, update: RuleUpdate) -> Result<Rule, RuleServiceError>;
+    /// Evaluates the rules of the device on one of its events and the state it led to,
+    /// returns the rules firing.
+    async fn evaluate_rules(
+        &self,
+        device_id: Uuid,
+        event: &Event,
+        state: Option<&DeviceState>,
+        now: &DateTime<Utc>,
+    ) -> Result<Vec<Rule>, RuleServiceError>;
+}
@@ -1,11 +1,14 @@
+use chrono::{DateTime, Utc};
 use uuid::Uuid;
 
 use crate::domain::action::{
     action::Action,
     action_format::{ActionFormat, ActionFormatError},
+    action_schedule::{ActionSchedule, ScheduleRun},
 };
 
 pub enum ActionRepositoryError {
+    NotFound,
     RepositoryError(String),
     ValidationError(String),
 }
@@ -15,7 +18,15 @@ pub trait HandleActionRepository: Send + Sync {
         &mut self,
         device_id: &str,
     ) -> impl Future<Output = Result<Vec<Action>, ActionRepositoryError>> + Send;
-
+    fn get_action(
+        &mut self,
+        action_id: Uuid,
+    ) -> impl Future<Output = Result<Option<Action>, ActionRepositoryError>> + Send;
+    /// Actions past their deadline the devices did not acknowledge yet.
+    fn get_expired_actions(
+        &mut self,
+        now: &DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<Action>, ActionRepositoryError>> + Send;
 }
 
 pub trait CreateActionRepository: Send + Sync {
@@ -26,12 +37,89 @@ pub trait CreateActionRepository: Send + Sync {
     ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
 }
 
+pub trait UpdateActionRepository: Send + Sync {
+    /// Stores the status of the action and its transitions.
+    fn update_action_status(
+        &self,
+        action: &Action,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+}
+
+/// Actions waiting for their scheduled time and recurring schedules, with the format
+/// their actions are sent with.
+pub trait ScheduledActionRepository: Send + Sync {
+    fn schedule_action(
+        &self,
+        action: Action,
+        action_format: &ActionFormat,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+    /// Scheduled actions of the device, of every device without one.
+    fn get_scheduled_actions(
+        &self,
+        device_id: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<Action>, ActionRepositoryError>> + Send;
+    /// Due actions, claimed for a while when several processes share the repository so that
+    /// only one of them gets each.
+    fn get_due_actions(
+        &self,
+        now: &DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<(Action, ActionFormat)>, ActionRepositoryError>> + Send;
+    fn delete_scheduled_action(
+        &self,
+        action_id: Uuid,
+    ) -> impl Future<Output = Result<Action, ActionRepositoryError>> + Send;
+    /// Stores a scheduled action that will not be sent, expired or failed, with the other
+    /// actions and removes it from the scheduled ones. Nothing is published to the device.
+    fn retire_scheduled_action(
+        &self,
+        action: &Action,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+    fn create_action_schedule(
+        &self,
+        schedule: ActionSchedule,
+        action_format: &ActionFormat,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+    /// Schedules of the device, of every device without one.
+    fn get_action_schedules(
+        &self,
+        device_id: Option<&str>,
+    ) -> impl Future<Output = Result<Vec<ActionSchedule>, ActionRepositoryError>> + Send;
+    /// Due schedules, claimed for a while when several processes share the repository so that
+    /// only one of them gets each.
+    fn get_due_action_schedules(
+        &self,
+        now: &DateTime<Utc>,
+    ) -> impl Future<Output = Result<Vec<(ActionSchedule, ActionFormat)>, ActionRepositoryError>> + Send;
+    fn update_action_schedule_next_run(
+        &self,
+        schedule_id: Uuid,
+        next_run_at: Option<DateTime<Utc>>,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+    /// Removes the schedule and its runs, returns it.
+    fn delete_action_schedule(
+        &self,
+        schedule_id: Uuid,
+    ) -> impl Future<Output = Result<ActionSchedule, ActionRepositoryError>> + Send;
+    fn add_schedule_run(
+        &self,
+        run: ScheduleRun,
+    ) -> impl Future<Output = Result<(), ActionRepositoryError>> + Send;
+    /// Runs of the schedule, latest first, fails if the schedule does not exist.
+    fn get_schedule_runs(
+        &self,
+        schedule_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<ScheduleRun>, ActionRepositoryError>> + Send;
+}
+
 impl From<ActionFormatError> for ActionRepositoryError {
     fn from(value: ActionFormatError) -> Self {
         match value {
             ActionFormatError::UnsupportedFormat(e) => {
                 ActionRepositoryError::RepositoryError(format!("Unsupported format: {}", e))
             }
+            ActionFormatError::ConstraintViolation(e) => {
+                ActionRepositoryError::ValidationError(format!("Constraint violation: {}", e))
+            }
         }
     }
 }
@@ -0,0 +1,47 @@
+use uuid::Uuid;
+
+use crate::domain::device_group::DeviceGroup;
+
+#[derive(Debug, Clone)]
+pub enum DeviceGroupRepositoryError {
+    NotFound,
+    Conflict,
+    InternalError(String),
+}
+
+pub trait GetDeviceGroupRepository: Send + Sync {
+    fn get_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<DeviceGroup>, DeviceGroupRepositoryError>> + Send;
+    fn get_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<DeviceGroup>, DeviceGroupRepositoryError>> + Send;
+    /// Groups directly nested in the given group.
+    fn get_children(
+        &self,
+        parent_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<DeviceGroup>, DeviceGroupRepositoryError>> + Send;
+}
+
+pub trait CreateDeviceGroupRepository: Send + Sync {
+    fn create(
+        &self,
+        group: &DeviceGroup,
+    ) -> impl Future<Output = Result<(), DeviceGroupRepositoryError>> + Send;
+}
+
+pub trait DeleteDeviceGroupRepository: Send + Sync {
+    fn delete_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), DeviceGroupRepositoryError>> + Send;
+}
+
+pub trait UpdateDeviceGroupRepository: Send + Sync {
+    fn update(
+        &self,
+        group: &DeviceGroup,
+    ) -> impl Future<Output = Result<(), DeviceGroupRepositoryError>> + Send;
+}
@@ -0,0 +1,42 @@
+use uuid::Uuid;
+
+use crate::domain::device_model::DeviceModel;
+
+#[derive(Debug, Clone)]
+pub enum DeviceModelRepositoryError {
+    NotFound,
+    Conflict,
+    InternalError(String),
+}
+
+pub trait GetDeviceModelRepository: Send + Sync {
+    fn get_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<DeviceModel>, DeviceModelRepositoryError>> + Send;
+    fn get_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<DeviceModel>, DeviceModelRepositoryError>> + Send;
+}
+
+pub trait CreateDeviceModelRepository: Send + Sync {
+    fn create(
+        &self,
+        model: &DeviceModel,
+    ) -> impl Future<Output = Result<(), DeviceModelRepositoryError>> + Send;
+}
+
+pub trait DeleteDeviceModelRepository: Send + Sync {
+    fn delete_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), DeviceModelRepositoryError>> + Send;
+}
+
+pub trait UpdateDeviceModelRepository: Send + Sync {
+    fn update(
+        &self,
+        model: &DeviceModel,
+    ) -> impl Future<Output = Result<(), DeviceModelRepositoryError>> + Send;
+}
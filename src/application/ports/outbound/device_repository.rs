@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::domain::device::Device;
+use crate::domain::{connectivity::Connectivity, device::Device, device_filter::DeviceFilter, device_schema::DeviceSchema};
 
 #[derive(Debug, Clone)]
 pub enum DeviceRepositoryError {
@@ -37,6 +37,27 @@ pub trait GetDeviceRepository: Send + Sync {
         &self,
         physical_id: &str,
     ) -> impl Future<Output = Result<Option<Device>, DeviceRepositoryError>> + Send;
+    /// Devices of a user having all the labels and tags of the filter.
+    fn get_by_filter(
+        &self,
+        user_id: Uuid,
+        filter: &DeviceFilter,
+    ) -> impl Future<Output = Result<Vec<Device>, DeviceRepositoryError>> + Send;
+    /// Devices having a heartbeat interval, of every user.
+    fn get_monitored(
+        &self,
+    ) -> impl Future<Output = Result<Vec<Device>, DeviceRepositoryError>> + Send;
+    /// Devices using a model, of every user.
+    fn get_by_model_id(
+        &self,
+        model_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<Device>, DeviceRepositoryError>> + Send;
+    /// Schema versions of a device, oldest first. Versions are recorded when a device is
+    /// created or updated with a schema version not stored yet.
+    fn get_schemas(
+        &self,
+        device_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<DeviceSchema>, DeviceRepositoryError>> + Send;
 }
 
 pub trait CreateDeviceRepository: Send + Sync {
@@ -58,4 +79,10 @@ pub trait UpdateDeviceRepository: Send + Sync {
         &self,
         device: &Device,
     ) -> impl Future<Output = Result<(), DeviceRepositoryError>> + Send;
+    /// Changes the connectivity only, leaving the rest of the device as stored.
+    fn update_connectivity(
+        &self,
+        id: Uuid,
+        connectivity: Connectivity,
+    ) -> impl Future<Output = Result<(), DeviceRepositoryError>> + Send;
 }
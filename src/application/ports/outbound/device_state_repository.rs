@@ -1,6 +1,6 @@
 use uuid::Uuid;
 
-use crate::domain::state::DeviceState;
+use crate::domain::state::{DeviceState, StateMergePolicy};
 
 pub enum DeviceStateRepositoryError {
     DeviceNotFound,
@@ -30,8 +30,19 @@ pub trait DeleteDeviceStateRepository: Send + Sync {
 }
 
 pub trait UpdateDeviceStateRepository: Send + Sync {
-    fn update(
+    /// Stores the desired values of a state and their convergence, reported values are left
+    /// as stored. The state is created if missing, returns the stored state.
+    fn set_desired(
         &self,
-        device: &DeviceState,
-    ) -> impl Future<Output = Result<(), DeviceStateRepositoryError>> + Send;
+        device_state: &DeviceState,
+    ) -> impl Future<Output = Result<DeviceState, DeviceStateRepositoryError>> + Send;
+    /// Merges the `keys` of a merged state into the stored one following `policy`, in one
+    /// step so concurrent reports do not roll values back. The state is created if missing,
+    /// returns the stored state.
+    fn merge_values(
+        &self,
+        device_state: &DeviceState,
+        keys: &[String],
+        policy: StateMergePolicy,
+    ) -> impl Future<Output = Result<DeviceState, DeviceStateRepositoryError>> + Send;
 }
@@ -1,3 +1,5 @@
+use chrono::{DateTime, Utc};
+
 use crate::domain::event::{
     event::Event,
     event_format::{EventFormat, EventFormatError},
@@ -13,6 +15,11 @@ pub trait GetEventRepository: Send + Sync {
         &self,
         device_physical_id: &str,
     ) -> impl Future<Output = Result<Vec<Event>, EventRepositoryError>> + Send;
+    /// Timestamp of the latest event of a device, none if nothing was received from it.
+    fn get_last_event_time(
+        &self,
+        device_physical_id: &str,
+    ) -> impl Future<Output = Result<Option<DateTime<Utc>>, EventRepositoryError>> + Send;
 }
 
 pub trait CreateEventRepository: Send + Sync {
@@ -29,6 +36,9 @@ impl From<EventFormatError> for EventRepositoryError {
             EventFormatError::UnsupportedFormat(e) => {
                 EventRepositoryError::RepositoryError(format!("Unsupported format: {}", e))
             }
+            EventFormatError::ConstraintViolation(e) => {
+                EventRepositoryError::ValidationError(format!("Constraint violation: {}", e))
+            }
         }
     }
 }
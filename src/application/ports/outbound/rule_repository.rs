@@ -0,0 +1,48 @@
+use uuid::Uuid;
+
+use crate::domain::rule::Rule;
+
+#[derive(Debug, Clone)]
+pub enum RuleRepositoryError {
+    NotFound,
+    Conflict,
+    InternalError(String),
+}
+
+pub trait GetRuleRepository: Send + Sync {
+    fn get_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<Option<Rule>, RuleRepositoryError>> + Send;
+    fn get_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<Rule>, RuleRepositoryError>> + Send;
+    /// Rules evaluated on the events of the device.
+    fn get_by_device_id(
+        &self,
+        device_id: Uuid,
+    ) -> impl Future<Output = Result<Vec<Rule>, RuleRepositoryError>> + Send;
+}
+
+pub trait CreateRuleRepository: Send + Sync {
+    fn create(
+        &self,
+        rule: &Rule,
+    ) -> impl Future<Output = Result<(), RuleRepositoryError>> + Send;
+}
+
+pub trait DeleteRuleRepository: Send + Sync {
+    fn delete_by_id(
+        &self,
+        id: Uuid,
+    ) -> impl Future<Output = Result<(), RuleRepositoryError>> + Send;
+}
+
+pub trait UpdateRuleRepository: Send + Sync {
+    /// Stores the definition of the rule and the state of its evaluation.
+    fn update(
+        &self,
+        rule: &Rule,
+    ) -> impl Future<Output = Result<(), RuleRepositoryError>> + Send;
+}
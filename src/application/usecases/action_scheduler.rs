@@ -0,0 +1,184 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    application::{
+        ports::{
+            inbound::{action_service::{ActionService, ActionServiceError}, device_service::DeviceService},
+            outbound::action_repository::{
+                CreateActionRepository, HandleActionRepository, ScheduledActionRepository,
+                UpdateActionRepository,
+            },
+        },
+        usecases::manage_action::{ManageActionService, from_repository_error},
+    },
+    domain::action::{action::Action, action_format::ActionFormat, action_schedule::ScheduleRun, action_status::ActionStatus},
+};
+
+/// Hands the scheduled actions to the create repository once due, sends the actions
+/// of the due recurring schedules, and expires the actions devices did not acknowledge
+/// before their deadline. Devices give back the protobuf descriptors stored formats lack.
+pub struct ActionScheduler<C: CreateActionRepository, H: HandleActionRepository, U: UpdateActionRepository, S: ScheduledActionRepository, D: DeviceService> {
+    service: Arc<ManageActionService<C, H, U, S>>,
+    devices: Arc<D>,
+}
+
+impl<C: CreateActionRepository, H: HandleActionRepository, U: UpdateActionRepository, S: ScheduledActionRepository, D: DeviceService> ActionScheduler<C, H, U, S, D> {
+    pub fn new(service: Arc<ManageActionService<C, H, U, S>>, devices: Arc<D>) -> Self {
+        Self { service, devices }
+    }
+
+    /// Sends the due actions, returns a result per action. Actions whose deadline passed while
+    /// scheduled are stored as expired instead of sent, those whose format can't be resolved
+    /// as failed. An action failing to be handed over is left for the next run and does not
+    /// hold back the others.
+    pub async fn dispatch_due_actions(&self, now: &DateTime<Utc>) -> Result<Vec<Result<Action, (Uuid, ActionServiceError)>>, ActionServiceError> {
+        // repositories may share a lock, each one is released before the next is taken
+        let due = self
+            .service
+            .scheduled_repo
+            .lock()
+            .await
+            .get_due_actions(now)
+            .await
+            .map_err(from_repository_error)?;
+        let mut dispatched = Vec::new();
+        for (action, format) in due {
+            let action_id = action.id;
+            let result = match self.resolve_format(&action.device_id, &action.action_name, format).await {
+                Ok(format) => self.dispatch_action(action, &format, now).await,
+                // it would fail the same way on every run
+                Err(e) => self
+                    .retire_action(action, ActionStatus::Failed, now)
+                    .await
+                    .and(Err(ActionServiceError::InvalidInput(e))),
+            };
+            dispatched.push(result.map_err(|e| (action_id, e)));
+        }
+        Ok(dispatched)
+    }
+
+    async fn dispatch_action(&self, action: Action, format: &ActionFormat, now: &DateTime<Utc>) -> Result<Action, ActionServiceError> {
+        if action.is_expired(now) {
+            // kept with its history like the actions expiring once sent, the device never gets it
+            return self.retire_action(action, ActionStatus::Expired, now).await;
+        }
+        self.service
+            .create_repo
+            .lock()
+            .await
+            .create_action(action.clone(), format)
+            .await
+            .map_err(from_repository_error)?;
+        // removed once handed over, a failure above leaves it for the next run
+        self.service
+            .scheduled_repo
+            .lock()
+            .await
+            .delete_scheduled_action(action.id)
+            .await
+            .map_err(from_repository_error)?;
+        Ok(action)
+    }
+
+    /// Format of a stored action, a protobuf message takes its descriptor back from the
+    /// action definition of the device.
+    async fn resolve_format(&self, device_id: &str, action_name: &str, format: ActionFormat) -> Result<ActionFormat, String> {
+        if format.is_resolved() {
+            return Ok(format);
+        }
+        let id = Uuid::parse_str(device_id).map_err(|_| format!("Invalid device ID {}", device_id))?;
+        let device = self
+            .devices
+            .get_device(id)
+            .await
+            .map_err(|e| e.to_string())?
+            .ok_or_else(|| format!("Device {} not found", device_id))?;
+        match device.action(action_name).map(|action| action.format()) {
+            Some(resolved) if resolved.is_resolved() && resolved.to_string() == format.to_string() => Ok(resolved.clone()),
+            _ => Err(format!("No descriptor for the {} format of action '{}'", format, action_name)),
+        }
+    }
+
+    /// Stores a scheduled action that will not be sent with the given status.
+    async fn retire_action(&self, mut action: Action, status: ActionStatus, now: &DateTime<Utc>) -> Result<Action, ActionServiceError> {
+        action
+            .transition(status, now)
+            .map_err(ActionServiceError::InternalError)?;
+        self.service
+            .scheduled_repo
+            .lock()
+            .await
+            .retire_scheduled_action(&action)
+            .await
+            .map_err(from_repository_error)?;
+        Ok(action)
+    }
+
+    /// Sends an action for each due schedule and records the run, returns the runs.
+    pub async fn run_due_schedules(&self, now: &DateTime<Utc>) -> Result<Vec<ScheduleRun>, ActionServiceError> {
+        let due = self
+            .service
+            .scheduled_repo
+            .lock()
+            .await
+            .get_due_action_schedules(now)
+            .await
+            .map_err(from_repository_error)?;
+        let mut runs = Vec::new();
+        for (schedule, format) in due {
+            let action = schedule.action(now);
+            let action_id = action.id;
+            let result = match self.resolve_format(&schedule.device_id, &schedule.action_name, format).await {
+                Ok(format) => self.service.send_action(action, &format).await,
+                Err(e) => Err(ActionServiceError::InvalidInput(e)),
+            };
+            let run = ScheduleRun {
+                schedule_id: schedule.id,
+                scheduled_for: schedule.next_run_at.unwrap_or(*now),
+                ran_at: *now,
+                action_id: result.is_ok().then_some(action_id),
+                error: result.err().map(|e| e.to_string()),
+            };
+            // runs missed while the application was stopped are not caught up
+            let repo = self.service.scheduled_repo.lock().await;
+            repo.update_action_schedule_next_run(schedule.id, schedule.next_run_after(now))
+                .await
+                .map_err(from_repository_error)?;
+            repo.add_schedule_run(run.clone())
+                .await
+                .map_err(from_repository_error)?;
+            runs.push(run);
+        }
+        Ok(runs)
+    }
+
+    /// Expires the sent actions past their deadline, returns them.
+    pub async fn expire_actions(&self, now: &DateTime<Utc>) -> Result<Vec<Action>, ActionServiceError> {
+        let expired = self
+            .service
+            .get_repo
+            .lock()
+            .await
+            .get_expired_actions(now)
+            .await
+            .map_err(from_repository_error)?;
+        let mut result = Vec::new();
+        for mut action in expired {
+            action
+                .transition(ActionStatus::Expired, now)
+                .map_err(ActionServiceError::InternalError)?;
+            self.service
+                .update_repo
+                .lock()
+                .await
+                .update_action_status(&action)
+                .await
+                .map_err(from_repository_error)?;
+            result.push(action);
+        }
+        Ok(result)
+    }
+}
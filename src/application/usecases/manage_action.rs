@@ -1,4 +1,5 @@
-use std::{collections::HashSet, sync::Arc};
+use chrono::{DateTime, Utc};
+use std::{collections::{HashMap, HashSet}, sync::Arc};
 use tokio::sync::Mutex;
 use uuid::Uuid;
 
@@ -7,40 +8,72 @@ use crate::{
         inbound::action_service::{ActionService, ActionServiceError},
         outbound::action_repository::{
             ActionRepositoryError, CreateActionRepository, HandleActionRepository,
+            ScheduledActionRepository, UpdateActionRepository,
         },
     },
-    domain::action::{action::Action, action_format::ActionFormat},
+    domain::{
+        action::{
+            action::Action,
+            action_data_value::ActionDataValue,
+            action_format::ActionFormat,
+            action_schedule::{ActionSchedule, ScheduleRun},
+            action_status::ActionStatus,
+        },
+        device::Device,
+    },
 };
 
 #[derive(Debug)]
-pub struct ManageActionService<C: CreateActionRepository, H: HandleActionRepository> {
+pub struct ManageActionService<C: CreateActionRepository, H: HandleActionRepository, U: UpdateActionRepository, S: ScheduledActionRepository> {
     pub create_repo: Arc<Mutex<C>>,
     pub get_repo: Arc<Mutex<H>>,
+    pub update_repo: Arc<Mutex<U>>,
+    pub scheduled_repo: Arc<Mutex<S>>,
+}
+
+pub(crate) fn from_repository_error(err: ActionRepositoryError) -> ActionServiceError {
+    match err {
+        ActionRepositoryError::NotFound => ActionServiceError::NotFound,
+        ActionRepositoryError::RepositoryError(msg) => ActionServiceError::InternalError(msg),
+        ActionRepositoryError::ValidationError(msg) => ActionServiceError::InvalidInput(msg),
+    }
 }
 
-impl<C: CreateActionRepository, H: HandleActionRepository> ActionService
-    for ManageActionService<C, H>
+impl<C: CreateActionRepository, H: HandleActionRepository, U: UpdateActionRepository, S: ScheduledActionRepository> ActionService
+    for ManageActionService<C, H, U, S>
 {
     async fn send_action(
         &self,
         action: Action,
         event_format: &ActionFormat,
     ) -> Result<(), ActionServiceError> {
+        if action.scheduled_at.is_some_and(|scheduled_at| scheduled_at > Utc::now()) {
+            let repo = self.scheduled_repo.lock().await;
+            return repo
+                .schedule_action(action, event_format)
+                .await
+                .map_err(from_repository_error);
+        }
         let repo = self.create_repo.lock().await;
-        if let Err(e) = repo
-            .create_action(action.clone(), event_format)
+        repo.create_action(action.clone(), event_format)
             .await
-        {
-            return Err(match e {
-                ActionRepositoryError::RepositoryError(msg) => {
-                    ActionServiceError::InternalError(msg)
-                }
-                ActionRepositoryError::ValidationError(msg) => {
-                    ActionServiceError::InvalidInput(msg)
-                }
-            });
-        }
-        Ok(())
+            .map_err(from_repository_error)
+    }
+
+    async fn send_device_action(
+        &self,
+        device: &Device,
+        action_name: &str,
+        payload: HashMap<String, ActionDataValue>,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Action, ActionServiceError> {
+        let action_concerned = device
+            .action(action_name)
+            .ok_or_else(|| ActionServiceError::InvalidInput(format!("Action '{}' not found for the device", action_name)))?;
+        let action = Action::from_values(device, timestamp, action_name, payload)
+            .map_err(|e| ActionServiceError::InvalidInput(e.to_string()))?;
+        self.send_action(action.clone(), action_concerned.format()).await?;
+        Ok(action)
     }
 
     async fn get_actions(
@@ -51,19 +84,95 @@ impl<C: CreateActionRepository, H: HandleActionRepository> ActionService
         let mut repo = self.get_repo.lock().await;
         match repo.get_actions(device_id).await {
             Ok(actions) => result.push(actions),
-            Err(ActionRepositoryError::RepositoryError(msg)) => {
-                return Err(ActionServiceError::InternalError(msg));
-            }
-            Err(ActionRepositoryError::ValidationError(msg)) => {
-                return Err(ActionServiceError::InvalidInput(msg));
-            }
+            Err(e) => return Err(from_repository_error(e)),
         }
-        let mut actions = HashSet::new();
+        // payloads hold floats, actions are told apart by ID
+        let mut seen = HashSet::new();
+        let mut actions = Vec::new();
         for repo_actions in result {
             for action in repo_actions {
-                actions.insert(action);
+                if seen.insert(action.id) {
+                    actions.push(action);
+                }
             }
         }
-        Ok(actions.into_iter().collect())
+        Ok(actions)
+    }
+
+    async fn get_action(&self, action_id: Uuid) -> Result<Action, ActionServiceError> {
+        let mut repo = self.get_repo.lock().await;
+        match repo.get_action(action_id).await {
+            Ok(Some(action)) => Ok(action),
+            Ok(None) => Err(ActionServiceError::NotFound),
+            Err(e) => Err(from_repository_error(e)),
+        }
+    }
+
+    async fn update_action_status(
+        &self,
+        device_id: &str,
+        action_id: Uuid,
+        status: ActionStatus,
+        timestamp: &DateTime<Utc>,
+    ) -> Result<Action, ActionServiceError> {
+        let mut action = self.get_action(action_id).await?;
+        // devices only see their own actions
+        if action.device_id != device_id {
+            return Err(ActionServiceError::NotFound);
+        }
+        action
+            .transition(status, timestamp)
+            .map_err(ActionServiceError::InvalidInput)?;
+        let repo = self.update_repo.lock().await;
+        repo.update_action_status(&action)
+            .await
+            .map_err(from_repository_error)?;
+        Ok(action)
+    }
+
+    async fn get_scheduled_actions(&self, device_id: Option<&str>) -> Result<Vec<Action>, ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.get_scheduled_actions(device_id)
+            .await
+            .map_err(from_repository_error)
+    }
+
+    async fn cancel_scheduled_action(&self, action_id: Uuid) -> Result<Action, ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.delete_scheduled_action(action_id)
+            .await
+            .map_err(from_repository_error)
+    }
+
+    async fn create_action_schedule(
+        &self,
+        schedule: ActionSchedule,
+        action_format: &ActionFormat,
+    ) -> Result<(), ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.create_action_schedule(schedule, action_format)
+            .await
+            .map_err(from_repository_error)
+    }
+
+    async fn get_action_schedules(&self, device_id: Option<&str>) -> Result<Vec<ActionSchedule>, ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.get_action_schedules(device_id)
+            .await
+            .map_err(from_repository_error)
+    }
+
+    async fn delete_action_schedule(&self, schedule_id: Uuid) -> Result<ActionSchedule, ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.delete_action_schedule(schedule_id)
+            .await
+            .map_err(from_repository_error)
+    }
+
+    async fn get_schedule_runs(&self, schedule_id: Uuid) -> Result<Vec<ScheduleRun>, ActionServiceError> {
+        let repo = self.scheduled_repo.lock().await;
+        repo.get_schedule_runs(schedule_id)
+            .await
+            .map_err(from_repository_error)
     }
 }
@@ -1,16 +1,20 @@
-use std::{ collections::HashMap, sync::Arc};
+use std::sync::Arc;
 
+use chrono::Utc;
 use uuid::Uuid;
 
 use crate::{
     application::ports::{
-        inbound::device_service::{DeviceService, DeviceServiceError},
-        outbound::device_repository::{
-            CreateDeviceRepository, DeleteDeviceRepository, DeviceRepositoryError,
-            GetDeviceRepository, UpdateDeviceRepository,
+        inbound::device_service::{DeviceService, DeviceServiceError, DeviceUpdate},
+        outbound::{
+            device_model_repository::{DeviceModelRepositoryError, GetDeviceModelRepository},
+            device_repository::{
+                CreateDeviceRepository, DeleteDeviceRepository, DeviceRepositoryError,
+                GetDeviceRepository, UpdateDeviceRepository,
+            },
         },
     },
-    domain::{action::action_emittable::ActionEmittable, device::Device, event::event_emittable::EventEmittable},
+    domain::{device::Device, device_filter::DeviceFilter, device_schema::{DeviceSchema, SchemaDiff}, connectivity::Connectivity},
 };
 
 #[derive(Debug)]
@@ -19,11 +23,14 @@ pub struct ManageDeviceService<
     G: GetDeviceRepository,
     U: UpdateDeviceRepository,
     D: DeleteDeviceRepository,
+    M: GetDeviceModelRepository,
 > {
     pub create_repo: Arc<C>,
     pub get_repo: Arc<G>,
     pub update_repo: Arc<U>,
     pub delete_repo: Arc<D>,
+    /// Models are read to resolve the effective events and actions of devices.
+    pub model_repo: Arc<M>,
 }
 
 impl<
@@ -31,11 +38,64 @@ impl<
     G: GetDeviceRepository,
     U: UpdateDeviceRepository,
     D: DeleteDeviceRepository,
-> DeviceService for ManageDeviceService<C, G, U, D>
+    M: GetDeviceModelRepository,
+> ManageDeviceService<C, G, U, D, M>
+{
+    /// Applies the model of the device if it has one, then resolves its protobuf messages.
+    /// A device whose model was deleted keeps its own events and actions only.
+    async fn with_model(&self, mut device: Device) -> Result<Device, DeviceServiceError> {
+        if let Some(model_id) = device.model_id().cloned() {
+            match self.model_repo.get_by_id(model_id).await {
+                Ok(Some(model)) => device.apply_model(&model),
+                Ok(None) | Err(DeviceModelRepositoryError::NotFound) => {}
+                Err(DeviceModelRepositoryError::InternalError(v)) => return Err(DeviceServiceError::InternalError(v)),
+                Err(DeviceModelRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError("Unexpected conflict error while getting device model".to_string())),
+            }
+        }
+        device
+            .resolve_protobuf_messages()
+            .map_err(DeviceServiceError::InternalError)?;
+        Ok(device)
+    }
+
+    /// Same as `with_model` but the model must exist, for devices being created or updated.
+    async fn with_existing_model(&self, mut device: Device) -> Result<Device, DeviceServiceError> {
+        if let Some(model_id) = device.model_id().cloned() {
+            match self.model_repo.get_by_id(model_id).await {
+                Ok(Some(model)) => device.apply_model(&model),
+                Ok(None) | Err(DeviceModelRepositoryError::NotFound) => return Err(DeviceServiceError::InvalidInput(format!("device model {} not found", model_id))),
+                Err(DeviceModelRepositoryError::InternalError(v)) => return Err(DeviceServiceError::InternalError(v)),
+                Err(DeviceModelRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError("Unexpected conflict error while getting device model".to_string())),
+            }
+        }
+        device
+            .resolve_protobuf_messages()
+            .map_err(DeviceServiceError::InvalidInput)?;
+        device
+            .validate_computed_fields()
+            .map_err(DeviceServiceError::InvalidInput)?;
+        device
+            .validate_binary_layouts()
+            .map_err(DeviceServiceError::InvalidInput)?;
+        device
+            .validate_heartbeat_interval()
+            .map_err(DeviceServiceError::InvalidInput)?;
+        Ok(device)
+    }
+}
+
+impl<
+    C: CreateDeviceRepository,
+    G: GetDeviceRepository,
+    U: UpdateDeviceRepository,
+    D: DeleteDeviceRepository,
+    M: GetDeviceModelRepository,
+> DeviceService for ManageDeviceService<C, G, U, D, M>
 {
     async fn create_device(&self, device: &Device) -> Result<Device, DeviceServiceError> {
+        let device = self.with_existing_model(device.clone()).await?;
         match self.create_repo.create(&device).await {
-            Ok(_) => Ok(device.clone()),
+            Ok(_) => Ok(device),
             Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::AlreadyExists),
             Err(DeviceRepositoryError::NotFound) => Err(DeviceServiceError::InternalError(format!("Unexpected not found error while creating device"))),
             Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
@@ -44,7 +104,7 @@ impl<
 
     async fn get_device(&self, id: Uuid) -> Result<Option<Device>, DeviceServiceError> {
         match self.get_repo.get_by_id(id).await {
-            Ok(Some(device)) => Ok(Some(device)),
+            Ok(Some(device)) => Ok(Some(self.with_model(device).await?)),
             Ok(None) => Err(DeviceServiceError::NotFound),
             Err(DeviceRepositoryError::NotFound) => Err(DeviceServiceError::NotFound),
             Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
@@ -54,13 +114,47 @@ impl<
 
     async fn get_devices_by_user_id(&self, user_id: Uuid) -> Result<Vec<Device>, DeviceServiceError> {
         match self.get_repo.get_by_user_id(user_id).await {
-            Ok(devices) => Ok(devices),
+            Ok(devices) => {
+                let mut resolved = Vec::with_capacity(devices.len());
+                for device in devices {
+                    resolved.push(self.with_model(device).await?);
+                }
+                Ok(resolved)
+            }
             Err(DeviceRepositoryError::NotFound) => Err(DeviceServiceError::NotFound),
             Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
             Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::InternalError(format!("Unexpected conflict error while getting from user device"))), // Catch-all for any other errors
         }
     }
 
+    async fn get_devices_by_filter(&self, user_id: Uuid, filter: &DeviceFilter) -> Result<Vec<Device>, DeviceServiceError> {
+        match self.get_repo.get_by_filter(user_id, filter).await {
+            Ok(devices) => {
+                let mut resolved = Vec::with_capacity(devices.len());
+                for device in devices {
+                    resolved.push(self.with_model(device).await?);
+                }
+                Ok(resolved)
+            }
+            Err(DeviceRepositoryError::NotFound) => Err(DeviceServiceError::NotFound),
+            Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::InternalError("Unexpected conflict error while filtering devices".to_string())),
+        }
+    }
+    async fn get_monitored_devices(&self) -> Result<Vec<Device>, DeviceServiceError> {
+        match self.get_repo.get_monitored().await {
+            Ok(devices) => {
+                let mut resolved = Vec::with_capacity(devices.len());
+                for device in devices {
+                    resolved.push(self.with_model(device).await?);
+                }
+                Ok(resolved)
+            }
+            Err(DeviceRepositoryError::NotFound) => Ok(Vec::new()),
+            Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::InternalError("Unexpected conflict error while getting monitored devices".to_string())),
+        }
+    }
     async fn delete_device(&self, id: Uuid) -> Result<(), DeviceServiceError> {
         match self.delete_repo.delete_by_id(id).await {
             Ok(_) => Ok(()),
@@ -70,14 +164,7 @@ impl<
         }
     }
 
-    async fn update_device(
-        &self,
-        id: Uuid,
-        physical_id: Option<String>,
-        name: Option<String>,
-        opt_events: Option<HashMap<String, EventEmittable>>,
-        opt_actions: Option<HashMap<String, ActionEmittable>>
-    ) -> Result<Device, DeviceServiceError> {
+    async fn update_device(&self, id: Uuid, update: DeviceUpdate) -> Result<Device, DeviceServiceError> {
         let mut device = match self.get_repo.get_by_id(id).await {
             Ok(Some(device)) => device,
             Ok(None) => return Err(DeviceServiceError::NotFound),
@@ -87,19 +174,44 @@ impl<
             }
             Err(DeviceRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError(format!("Unexpected conflict error while getting device"))), // Catch-all for any other errors
         };
+        let now = Utc::now();
+        let previous_schema = self.with_model(device.clone()).await?.schema(&now);
 
-        if let Some(physical_id) = physical_id {
+        if let Some(physical_id) = update.physical_id {
             device.set_physical_id(&physical_id)
         }
-        if let Some(name) = name {
+        if let Some(name) = update.name {
             device.set_name(&name);
         }
-        if let Some(events) = opt_events {
+        if let Some(events) = update.events {
             device.set_events(events.into_iter().collect());
         }
-        if let Some(actions) = opt_actions {
+        if let Some(actions) = update.actions {
             device.set_actions(actions.into_iter().collect());
         }
+        if let Some(descriptor_set) = update.descriptor_set {
+            device.set_descriptor_set(Some(descriptor_set));
+        }
+        if let Some(model_id) = update.model_id {
+            device.set_model_id(model_id);
+        }
+        if let Some(labels) = update.labels {
+            device.set_labels(labels);
+        }
+        if let Some(tags) = update.tags {
+            device.set_tags(tags);
+        }
+        if let Some(state_merge_policy) = update.state_merge_policy {
+            device.set_state_merge_policy(state_merge_policy);
+        }
+        if let Some(heartbeat_interval) = update.heartbeat_interval {
+            device.set_heartbeat_interval(heartbeat_interval);
+        }
+        let mut device = self.with_existing_model(device).await?;
+        // changing the emittables, its own or its model's, starts a new schema version, previous events keep theirs
+        if !previous_schema.diff(&device.schema(&now)).is_empty() {
+            device.set_schema_version(device.schema_version() + 1);
+        }
         match self.update_repo.update(&device).await {
             Ok(_) => Ok(device),
             Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::AlreadyExists),
@@ -107,14 +219,62 @@ impl<
             Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
         }
     }
-    
+
+    async fn set_connectivity(&self, id: Uuid, connectivity: Connectivity) -> Result<Device, DeviceServiceError> {
+        match self.update_repo.update_connectivity(id, connectivity).await {
+            Ok(_) => {}
+            Err(DeviceRepositoryError::NotFound) => return Err(DeviceServiceError::NotFound),
+            Err(DeviceRepositoryError::InternalError(v)) => return Err(DeviceServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError("Unexpected conflict error while updating connectivity".to_string())),
+        }
+        let mut device = match self.get_repo.get_by_id(id).await {
+            Ok(Some(device)) => device,
+            Ok(None) | Err(DeviceRepositoryError::NotFound) => return Err(DeviceServiceError::NotFound),
+            Err(DeviceRepositoryError::InternalError(v)) => return Err(DeviceServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError("Unexpected conflict error while getting device".to_string())),
+        };
+        // the change may only be forwarded by the repository, the returned device reflects it
+        device.set_connectivity(connectivity);
+        self.with_model(device).await
+    }
+
     async fn get_device_by_physical_id(&self, physical_id: &str) -> Result<Option<Device>, DeviceServiceError> {
         match self.get_repo.get_by_physical_id(physical_id).await {
-            Ok(Some(device)) => Ok(Some(device)),
+            Ok(Some(device)) => Ok(Some(self.with_model(device).await?)),
             Ok(None) => Err(DeviceServiceError::NotFound),
             Err(DeviceRepositoryError::NotFound) => Err(DeviceServiceError::NotFound),
             Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceServiceError::InternalError(v)),
             Err(DeviceRepositoryError::Conflict) => Err(DeviceServiceError::InternalError(format!("Unexpected conflict error while getting device by physical ID"))), // Catch-all for any other errors
         }
     }
+
+    async fn get_device_schemas(&self, id: Uuid) -> Result<Vec<DeviceSchema>, DeviceServiceError> {
+        let device = match self.get_device(id).await? {
+            Some(device) => device,
+            None => return Err(DeviceServiceError::NotFound),
+        };
+        let mut schemas = match self.get_repo.get_schemas(id).await {
+            Ok(schemas) => schemas,
+            Err(DeviceRepositoryError::NotFound) => Vec::new(),
+            Err(DeviceRepositoryError::InternalError(v)) => return Err(DeviceServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => return Err(DeviceServiceError::InternalError("Unexpected conflict error while getting device schemas".to_string())),
+        };
+        // devices stored before schemas were versioned have no history yet
+        if !schemas.iter().any(|s| s.version() == device.schema_version()) {
+            schemas.push(device.schema(&Utc::now()));
+        }
+        schemas.sort_by_key(|s| s.version());
+        Ok(schemas)
+    }
+
+    async fn diff_device_schemas(&self, id: Uuid, from: u32, to: u32) -> Result<SchemaDiff, DeviceServiceError> {
+        let schemas = self.get_device_schemas(id).await?;
+        let find = |version: u32| {
+            schemas
+                .iter()
+                .find(|s| s.version() == version)
+                .ok_or_else(|| DeviceServiceError::InvalidInput(format!("schema version {} not found", version)))
+        };
+        Ok(find(from)?.diff(find(to)?))
+    }
 }
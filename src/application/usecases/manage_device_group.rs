@@ -0,0 +1,192 @@
+use std::{
+    collections::{BTreeSet, HashSet},
+    sync::Arc,
+};
+
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        inbound::device_group_service::{DeviceGroupService, DeviceGroupServiceError},
+        outbound::device_group_repository::{
+            CreateDeviceGroupRepository, DeleteDeviceGroupRepository, DeviceGroupRepositoryError,
+            GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+        },
+    },
+    domain::device_group::DeviceGroup,
+};
+
+#[derive(Debug)]
+pub struct ManageDeviceGroupService<
+    C: CreateDeviceGroupRepository,
+    G: GetDeviceGroupRepository,
+    U: UpdateDeviceGroupRepository,
+    D: DeleteDeviceGroupRepository,
+> {
+    pub create_repo: Arc<C>,
+    pub get_repo: Arc<G>,
+    pub update_repo: Arc<U>,
+    pub delete_repo: Arc<D>,
+}
+
+fn from_repository_error(err: DeviceGroupRepositoryError, context: &str) -> DeviceGroupServiceError {
+    match err {
+        DeviceGroupRepositoryError::NotFound => DeviceGroupServiceError::NotFound,
+        DeviceGroupRepositoryError::InternalError(v) => DeviceGroupServiceError::InternalError(v),
+        DeviceGroupRepositoryError::Conflict => DeviceGroupServiceError::InternalError(format!("Unexpected conflict error while {}", context)),
+    }
+}
+
+impl<
+    C: CreateDeviceGroupRepository,
+    G: GetDeviceGroupRepository,
+    U: UpdateDeviceGroupRepository,
+    D: DeleteDeviceGroupRepository,
+> ManageDeviceGroupService<C, G, U, D>
+{
+    async fn get_existing(&self, id: Uuid) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        match self.get_repo.get_by_id(id).await {
+            Ok(Some(group)) => Ok(group),
+            Ok(None) => Err(DeviceGroupServiceError::NotFound),
+            Err(e) => Err(from_repository_error(e, "getting device group")),
+        }
+    }
+
+    /// Checks `parent_id` can be the parent of group `id`: it exists, belongs to the same user and
+    /// is not the group itself or one of its descendants.
+    async fn check_parent(&self, id: &Uuid, user_id: &Uuid, parent_id: &Uuid) -> Result<(), DeviceGroupServiceError> {
+        let mut current = Some(*parent_id);
+        while let Some(ancestor_id) = current {
+            if &ancestor_id == id {
+                return Err(DeviceGroupServiceError::InvalidInput("a group cannot be nested in itself".to_string()));
+            }
+            let ancestor = match self.get_repo.get_by_id(ancestor_id).await {
+                Ok(Some(group)) => group,
+                Ok(None) | Err(DeviceGroupRepositoryError::NotFound) => {
+                    return Err(DeviceGroupServiceError::InvalidInput(format!("parent group {} not found", ancestor_id)));
+                }
+                Err(e) => return Err(from_repository_error(e, "getting parent group")),
+            };
+            if ancestor.user_id() != user_id {
+                return Err(DeviceGroupServiceError::InvalidInput(format!("parent group {} not found", ancestor_id)));
+            }
+            current = ancestor.parent_id().cloned();
+        }
+        Ok(())
+    }
+
+    async fn save(&self, group: DeviceGroup) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        match self.update_repo.update(&group).await {
+            Ok(_) => Ok(group),
+            Err(DeviceGroupRepositoryError::Conflict) => Err(DeviceGroupServiceError::AlreadyExists),
+            Err(e) => Err(from_repository_error(e, "updating device group")),
+        }
+    }
+}
+
+impl<
+    C: CreateDeviceGroupRepository,
+    G: GetDeviceGroupRepository,
+    U: UpdateDeviceGroupRepository,
+    D: DeleteDeviceGroupRepository,
+> DeviceGroupService for ManageDeviceGroupService<C, G, U, D>
+{
+    async fn create_device_group(&self, group: &DeviceGroup) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        if let Some(parent_id) = group.parent_id() {
+            self.check_parent(group.id(), group.user_id(), parent_id).await?;
+        }
+        match self.create_repo.create(group).await {
+            Ok(_) => Ok(group.clone()),
+            Err(DeviceGroupRepositoryError::Conflict) => Err(DeviceGroupServiceError::AlreadyExists),
+            Err(DeviceGroupRepositoryError::NotFound) => Err(DeviceGroupServiceError::InternalError("Unexpected not found error while creating device group".to_string())),
+            Err(DeviceGroupRepositoryError::InternalError(v)) => Err(DeviceGroupServiceError::InternalError(v)),
+        }
+    }
+
+    async fn get_device_group(&self, id: Uuid) -> Result<Option<DeviceGroup>, DeviceGroupServiceError> {
+        self.get_existing(id).await.map(Some)
+    }
+
+    async fn get_device_groups_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupServiceError> {
+        self.get_repo
+            .get_by_user_id(user_id)
+            .await
+            .map_err(|e| from_repository_error(e, "getting device groups of user"))
+    }
+
+    async fn get_device_group_children(&self, id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupServiceError> {
+        self.get_existing(id).await?;
+        self.get_repo
+            .get_children(id)
+            .await
+            .map_err(|e| from_repository_error(e, "getting sub-groups"))
+    }
+
+    async fn delete_device_group(&self, id: Uuid) -> Result<(), DeviceGroupServiceError> {
+        let children = self.get_device_group_children(id).await?;
+        if !children.is_empty() {
+            return Err(DeviceGroupServiceError::InvalidInput("the group still has sub-groups".to_string()));
+        }
+        self.delete_repo
+            .delete_by_id(id)
+            .await
+            .map_err(|e| from_repository_error(e, "deleting device group"))
+    }
+
+    async fn update_device_group(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        opt_parent_id: Option<Option<Uuid>>,
+        opt_device_ids: Option<BTreeSet<Uuid>>,
+    ) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        let mut group = self.get_existing(id).await?;
+        if let Some(name) = name {
+            group.set_name(&name);
+        }
+        if let Some(parent_id) = opt_parent_id {
+            if let Some(parent_id) = &parent_id {
+                self.check_parent(group.id(), group.user_id(), parent_id).await?;
+            }
+            group.set_parent_id(parent_id);
+        }
+        if let Some(device_ids) = opt_device_ids {
+            group.set_device_ids(device_ids);
+        }
+        self.save(group).await
+    }
+
+    async fn add_device_to_group(&self, id: Uuid, device_id: Uuid) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        let mut group = self.get_existing(id).await?;
+        group.add_device(device_id);
+        self.save(group).await
+    }
+
+    async fn remove_device_from_group(&self, id: Uuid, device_id: Uuid) -> Result<DeviceGroup, DeviceGroupServiceError> {
+        let mut group = self.get_existing(id).await?;
+        if !group.remove_device(&device_id) {
+            return Err(DeviceGroupServiceError::InvalidInput(format!("device {} is not in the group", device_id)));
+        }
+        self.save(group).await
+    }
+
+    async fn get_device_group_device_ids(&self, id: Uuid) -> Result<BTreeSet<Uuid>, DeviceGroupServiceError> {
+        let root = self.get_existing(id).await?;
+        let mut device_ids = BTreeSet::new();
+        let mut visited = HashSet::new();
+        let mut to_visit = vec![root];
+        while let Some(group) = to_visit.pop() {
+            if !visited.insert(*group.id()) {
+                continue;
+            }
+            device_ids.extend(group.device_ids().iter().cloned());
+            let children = self
+                .get_repo
+                .get_children(*group.id())
+                .await
+                .map_err(|e| from_repository_error(e, "getting sub-groups"))?;
+            to_visit.extend(children);
+        }
+        Ok(device_ids)
+    }
+}
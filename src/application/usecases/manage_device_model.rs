@@ -0,0 +1,169 @@
+use std::{collections::HashMap, sync::Arc};
+
+use chrono::Utc;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        inbound::device_model_service::{DeviceModelService, DeviceModelServiceError},
+        outbound::{
+            device_model_repository::{
+                CreateDeviceModelRepository, DeleteDeviceModelRepository, DeviceModelRepositoryError,
+                GetDeviceModelRepository, UpdateDeviceModelRepository,
+            },
+            device_repository::{DeviceRepositoryError, GetDeviceRepository, UpdateDeviceRepository},
+        },
+    },
+    domain::{action::action_emittable::ActionEmittable, device::Device, device_model::DeviceModel, event::event_emittable::EventEmittable},
+};
+
+#[derive(Debug)]
+pub struct ManageDeviceModelService<
+    C: CreateDeviceModelRepository,
+    G: GetDeviceModelRepository,
+    U: UpdateDeviceModelRepository,
+    D: DeleteDeviceModelRepository,
+    DG: GetDeviceRepository,
+    DU: UpdateDeviceRepository,
+> {
+    pub create_repo: Arc<C>,
+    pub get_repo: Arc<G>,
+    pub update_repo: Arc<U>,
+    pub delete_repo: Arc<D>,
+    /// Devices using an updated model are stored with a new schema version.
+    pub device_get_repo: Arc<DG>,
+    pub device_update_repo: Arc<DU>,
+}
+
+impl<
+    C: CreateDeviceModelRepository,
+    G: GetDeviceModelRepository,
+    U: UpdateDeviceModelRepository,
+    D: DeleteDeviceModelRepository,
+    DG: GetDeviceRepository,
+    DU: UpdateDeviceRepository,
+> ManageDeviceModelService<C, G, U, D, DG, DU>
+{
+    /// Starts a new schema version for the devices whose emittables changed with the model.
+    async fn get_devices_of_model(&self, model_id: Uuid) -> Result<Vec<Device>, DeviceModelServiceError> {
+        match self.device_get_repo.get_by_model_id(model_id).await {
+            Ok(devices) => Ok(devices),
+            Err(DeviceRepositoryError::NotFound) => Ok(Vec::new()),
+            Err(DeviceRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+            Err(DeviceRepositoryError::Conflict) => Err(DeviceModelServiceError::InternalError("Unexpected conflict error while getting devices of model".to_string())),
+        }
+    }
+
+    async fn version_devices(&self, previous: &DeviceModel, model: &DeviceModel) -> Result<(), DeviceModelServiceError> {
+        let devices = self.get_devices_of_model(*model.id()).await?;
+        let now = Utc::now();
+        for mut device in devices {
+            device.apply_model(previous);
+            let previous_schema = device.schema(&now);
+            device.apply_model(model);
+            if previous_schema.diff(&device.schema(&now)).is_empty() {
+                continue;
+            }
+            device.set_schema_version(device.schema_version() + 1);
+            match self.device_update_repo.update(&device).await {
+                Ok(_) | Err(DeviceRepositoryError::NotFound) => {}
+                Err(DeviceRepositoryError::InternalError(v)) => return Err(DeviceModelServiceError::InternalError(v)),
+                Err(DeviceRepositoryError::Conflict) => return Err(DeviceModelServiceError::InternalError("Unexpected conflict error while updating device of model".to_string())),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<
+    C: CreateDeviceModelRepository,
+    G: GetDeviceModelRepository,
+    U: UpdateDeviceModelRepository,
+    D: DeleteDeviceModelRepository,
+    DG: GetDeviceRepository,
+    DU: UpdateDeviceRepository,
+> DeviceModelService for ManageDeviceModelService<C, G, U, D, DG, DU>
+{
+    async fn create_device_model(&self, model: &DeviceModel) -> Result<DeviceModel, DeviceModelServiceError> {
+        validate_computed(model.events())?;
+        match self.create_repo.create(model).await {
+            Ok(_) => Ok(model.clone()),
+            Err(DeviceModelRepositoryError::Conflict) => Err(DeviceModelServiceError::AlreadyExists),
+            Err(DeviceModelRepositoryError::NotFound) => Err(DeviceModelServiceError::InternalError("Unexpected not found error while creating device model".to_string())),
+            Err(DeviceModelRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+        }
+    }
+
+    async fn get_device_model(&self, id: Uuid) -> Result<Option<DeviceModel>, DeviceModelServiceError> {
+        match self.get_repo.get_by_id(id).await {
+            Ok(Some(model)) => Ok(Some(model)),
+            Ok(None) => Err(DeviceModelServiceError::NotFound),
+            Err(DeviceModelRepositoryError::NotFound) => Err(DeviceModelServiceError::NotFound),
+            Err(DeviceModelRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+            Err(DeviceModelRepositoryError::Conflict) => Err(DeviceModelServiceError::InternalError("Unexpected conflict error while getting device model".to_string())),
+        }
+    }
+
+    async fn get_device_models_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceModel>, DeviceModelServiceError> {
+        match self.get_repo.get_by_user_id(user_id).await {
+            Ok(models) => Ok(models),
+            Err(DeviceModelRepositoryError::NotFound) => Err(DeviceModelServiceError::NotFound),
+            Err(DeviceModelRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+            Err(DeviceModelRepositoryError::Conflict) => Err(DeviceModelServiceError::InternalError("Unexpected conflict error while getting device models of user".to_string())),
+        }
+    }
+
+    async fn delete_device_model(&self, id: Uuid) -> Result<(), DeviceModelServiceError> {
+        if !self.get_devices_of_model(id).await?.is_empty() {
+            return Err(DeviceModelServiceError::InUse);
+        }
+        match self.delete_repo.delete_by_id(id).await {
+            Ok(_) => Ok(()),
+            Err(DeviceModelRepositoryError::NotFound) => Err(DeviceModelServiceError::NotFound),
+            Err(DeviceModelRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+            Err(DeviceModelRepositoryError::Conflict) => Err(DeviceModelServiceError::InternalError("Unexpected conflict error while deleting device model".to_string())),
+        }
+    }
+
+    async fn update_device_model(
+        &self,
+        id: Uuid,
+        name: Option<String>,
+        opt_events: Option<HashMap<String, EventEmittable>>,
+        opt_actions: Option<HashMap<String, ActionEmittable>>,
+    ) -> Result<DeviceModel, DeviceModelServiceError> {
+        let mut model = match self.get_device_model(id).await? {
+            Some(model) => model,
+            None => return Err(DeviceModelServiceError::NotFound),
+        };
+        let previous = model.clone();
+        if let Some(name) = name {
+            model.set_name(&name);
+        }
+        if let Some(events) = opt_events {
+            validate_computed(&events)?;
+            model.set_events(events);
+        }
+        if let Some(actions) = opt_actions {
+            model.set_actions(actions);
+        }
+        match self.update_repo.update(&model).await {
+            Ok(_) => {
+                self.version_devices(&previous, &model).await?;
+                Ok(model)
+            }
+            Err(DeviceModelRepositoryError::Conflict) => Err(DeviceModelServiceError::AlreadyExists),
+            Err(DeviceModelRepositoryError::NotFound) => Err(DeviceModelServiceError::NotFound),
+            Err(DeviceModelRepositoryError::InternalError(v)) => Err(DeviceModelServiceError::InternalError(v)),
+        }
+    }
+}
+
+fn validate_computed(events: &HashMap<String, EventEmittable>) -> Result<(), DeviceModelServiceError> {
+    for (name, event) in events {
+        event
+            .validate_computed()
+            .map_err(|e| DeviceModelServiceError::InvalidInput(format!("Event '{}': {}", name, e)))?;
+    }
+    Ok(())
+}
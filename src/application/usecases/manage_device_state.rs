@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Arc};
 
 use uuid::Uuid;
 
-use crate::{application::ports::{inbound::device_state_service::{DeviceStateService, DeviceStateServiceError}, outbound::device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError, GetDeviceStateRepository, UpdateDeviceStateRepository}}, domain::{event::event_data_value::EventDataValue, state::DeviceState}};
+use crate::{application::ports::{inbound::device_state_service::{DeviceStateService, DeviceStateServiceError}, outbound::device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError, GetDeviceStateRepository, UpdateDeviceStateRepository}}, domain::{action::action::Action, device::Device, device_shadow, event::event_data_value::EventDataValue, state::{DeviceState, StateMergePolicy, StateValue}}};
 
 #[derive(Debug)]
 pub struct ManageDeviceStateService<C: CreateDeviceStateRepository, G: GetDeviceStateRepository, U: UpdateDeviceStateRepository, D: DeleteDeviceStateRepository> {
@@ -13,11 +13,14 @@ pub struct ManageDeviceStateService<C: CreateDeviceStateRepository, G: GetDevice
 }
 
 impl<C: CreateDeviceStateRepository, G: GetDeviceStateRepository, U: UpdateDeviceStateRepository, D: DeleteDeviceStateRepository> DeviceStateService for ManageDeviceStateService<C, G, U, D> {
-    async fn create_device_state(&self, device_id: Uuid, values: HashMap<String, EventDataValue>) -> Result<DeviceState, DeviceStateServiceError> {
+    async fn create_device_state(&self, device_id: Uuid, values: HashMap<String, StateValue>) -> Result<DeviceState, DeviceStateServiceError> {
         let device_state = DeviceState {
             device_id,
             last_update: chrono::Utc::now(),
             values,
+            desired: HashMap::new(),
+            desired_update: None,
+            converged_at: None,
         };
 
         match self.create_repo.create(&device_state).await {
@@ -47,23 +50,55 @@ impl<C: CreateDeviceStateRepository, G: GetDeviceStateRepository, U: UpdateDevic
         }
     }
     
-    async fn update_device_state(&self, id: Uuid, values: HashMap<String, EventDataValue>) -> Result<DeviceState, DeviceStateServiceError> {
+    async fn update_device_state(&self, id: Uuid, values: HashMap<String, StateValue>, policy: StateMergePolicy) -> Result<DeviceState, DeviceStateServiceError> {
+        let now = chrono::Utc::now();
         let mut device_state = match self.get_repo.get_by_id(id).await {
             Ok(Some(state)) => state,
-            Ok(None) => return self.create_device_state(id, values).await,
+            Ok(None) => DeviceState::from_parts(id, now, HashMap::new(), HashMap::new()),
             Err(DeviceStateRepositoryError::DeviceNotFound) => return Err(DeviceStateServiceError::DeviceNotFound),
             Err(DeviceStateRepositoryError::InternalError(s)) => return Err(DeviceStateServiceError::InternalError(s)),
             Err(DeviceStateRepositoryError::Conflict) => return Err(DeviceStateServiceError::AlreadyExists),
         };
 
-        device_state.values.extend(values);
-        device_state.last_update = chrono::Utc::now();
+        // late values are still stored as events, the state is left untouched
+        let updated = device_state.merge(values, policy);
+        if updated.is_empty() {
+            return Ok(device_state);
+        }
+        device_state.last_update = now;
+        device_state.refresh_convergence(&now);
 
-        match self.update_repo.update(&device_state).await {
-            Ok(_) => Ok(device_state),
+        // the state read may be stale, the repository merges the keys again on the stored one
+        match self.update_repo.merge_values(&device_state, &updated, policy).await {
+            Ok(stored) => Ok(stored),
             Err(DeviceStateRepositoryError::Conflict) => Err(DeviceStateServiceError::AlreadyExists),
             Err(DeviceStateRepositoryError::InternalError(s)) => Err(DeviceStateServiceError::InternalError(s)),
             Err(DeviceStateRepositoryError::DeviceNotFound) => Err(DeviceStateServiceError::DeviceNotFound),
         }
     }
-}
\ No newline at end of file
+
+    async fn set_desired_state(&self, device: &Device, desired: HashMap<String, EventDataValue>) -> Result<(DeviceState, Vec<Action>), DeviceStateServiceError> {
+        device_shadow::check_desired(device, &desired).map_err(DeviceStateServiceError::InvalidInput)?;
+        let now = chrono::Utc::now();
+        let mut device_state = match self.get_repo.get_by_id(*device.id()).await {
+            Ok(Some(state)) => state,
+            Ok(None) => DeviceState::from_parts(*device.id(), now, HashMap::new(), HashMap::new()),
+            Err(DeviceStateRepositoryError::DeviceNotFound) => return Err(DeviceStateServiceError::DeviceNotFound),
+            Err(DeviceStateRepositoryError::InternalError(s)) => return Err(DeviceStateServiceError::InternalError(s)),
+            Err(DeviceStateRepositoryError::Conflict) => return Err(DeviceStateServiceError::AlreadyExists),
+        };
+        device_state.set_desired(desired, &now);
+        device_shadow::converging_actions(device, &device_state, &now)
+            .map_err(DeviceStateServiceError::InvalidInput)?;
+        let stored = match self.update_repo.set_desired(&device_state).await {
+            Ok(stored) => stored,
+            Err(DeviceStateRepositoryError::Conflict) => return Err(DeviceStateServiceError::AlreadyExists),
+            Err(DeviceStateRepositoryError::InternalError(s)) => return Err(DeviceStateServiceError::InternalError(s)),
+            Err(DeviceStateRepositoryError::DeviceNotFound) => return Err(DeviceStateServiceError::DeviceNotFound),
+        };
+        // reported values may have changed since the read, actions follow the stored ones
+        let actions = device_shadow::converging_actions(device, &stored, &now)
+            .map_err(DeviceStateServiceError::InvalidInput)?;
+        Ok((stored, actions))
+    }
+}
@@ -1,5 +1,7 @@
 use std::{collections::HashSet, sync::Arc};
 
+use chrono::{DateTime, Utc};
+
 use crate::{
     application::ports::{
         inbound::event_service::{EventService, EventServiceError},
@@ -35,12 +37,24 @@ impl<C: CreateEventRepository, G: GetEventRepository> EventService for ManageEve
                 return Err(EventServiceError::InvalidInput(msg));
             }
         }
-        let mut events = HashSet::new();
+        // payloads hold floats, events are told apart by ID
+        let mut seen = HashSet::new();
+        let mut events = Vec::new();
         for repo_events in result {
             for event in repo_events {
-                events.insert(event);
+                if seen.insert(event.id) {
+                    events.push(event);
+                }
             }
         }
-        Ok(events.into_iter().collect())
+        Ok(events)
+    }
+
+    async fn get_last_event_time(&self, device_physical_id: &str) -> Result<Option<DateTime<Utc>>, EventServiceError> {
+        match self.get_repo.get_last_event_time(device_physical_id).await {
+            Ok(timestamp) => Ok(timestamp),
+            Err(EventRepositoryError::RepositoryError(msg)) => Err(EventServiceError::InternalError(msg)),
+            Err(EventRepositoryError::ValidationError(msg)) => Err(EventServiceError::InvalidInput(msg)),
+        }
     }
 }
@@ -0,0 +1,157 @@
+use std::sync::Arc;
+
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        inbound::rule_service::{RuleService, RuleServiceError, RuleUpdate},
+        outbound::rule_repository::{
+            CreateRuleRepository, DeleteRuleRepository, GetRuleRepository, RuleRepositoryError,
+            UpdateRuleRepository,
+        },
+    },
+    domain::{
+        event::event::Event,
+        rule::Rule,
+        state::DeviceState,
+    },
+};
+
+#[derive(Debug)]
+pub struct ManageRuleService<
+    C: CreateRuleRepository,
+    G: GetRuleRepository,
+    U: UpdateRuleRepository,
+    D: DeleteRuleRepository,
+> {
+    pub create_repo: Arc<C>,
+    pub get_repo: Arc<G>,
+    pub update_repo: Arc<U>,
+    pub delete_repo: Arc<D>,
+}
+
+fn from_repository_error(err: RuleRepositoryError, context: &str) -> RuleServiceError {
+    match err {
+        RuleRepositoryError::NotFound => RuleServiceError::NotFound,
+        RuleRepositoryError::InternalError(v) => RuleServiceError::InternalError(v),
+        RuleRepositoryError::Conflict => RuleServiceError::InternalError(format!("Unexpected conflict error while {}", context)),
+    }
+}
+
+impl<
+    C: CreateRuleRepository,
+    G: GetRuleRepository,
+    U: UpdateRuleRepository,
+    D: DeleteRuleRepository,
+> ManageRuleService<C, G, U, D>
+{
+    async fn get_existing(&self, id: Uuid) -> Result<Rule, RuleServiceError> {
+        match self.get_repo.get_by_id(id).await {
+            Ok(Some(rule)) => Ok(rule),
+            Ok(None) => Err(RuleServiceError::NotFound),
+            Err(e) => Err(from_repository_error(e, "getting rule")),
+        }
+    }
+}
+
+impl<
+    C: CreateRuleRepository,
+    G: GetRuleRepository,
+    U: UpdateRuleRepository,
+    D: DeleteRuleRepository,
+> RuleService for ManageRuleService<C, G, U, D>
+{
+    async fn create_rule(&self, rule: &Rule) -> Result<Rule, RuleServiceError> {
+        rule.validate().map_err(RuleServiceError::InvalidInput)?;
+        match self.create_repo.create(rule).await {
+            Ok(_) => Ok(rule.clone()),
+            Err(RuleRepositoryError::Conflict) => Err(RuleServiceError::AlreadyExists),
+            Err(e) => Err(from_repository_error(e, "creating rule")),
+        }
+    }
+
+    async fn get_rule(&self, id: Uuid) -> Result<Option<Rule>, RuleServiceError> {
+        self.get_existing(id).await.map(Some)
+    }
+
+    async fn get_rules_by_user_id(&self, user_id: Uuid) -> Result<Vec<Rule>, RuleServiceError> {
+        self.get_repo
+            .get_by_user_id(user_id)
+            .await
+            .map_err(|e| from_repository_error(e, "getting rules of user"))
+    }
+
+    async fn get_rules_by_device_id(&self, device_id: Uuid) -> Result<Vec<Rule>, RuleServiceError> {
+        self.get_repo
+            .get_by_device_id(device_id)
+            .await
+            .map_err(|e| from_repository_error(e, "getting rules of device"))
+    }
+
+    async fn delete_rule(&self, id: Uuid) -> Result<(), RuleServiceError> {
+        self.delete_repo
+            .delete_by_id(id)
+            .await
+            .map_err(|e| from_repository_error(e, "deleting rule"))
+    }
+
+    async fn update_rule(&self, id: Uuid, update: RuleUpdate) -> Result<Rule, RuleServiceError> {
+        let mut rule = self.get_existing(id).await?;
+        if let Some(name) = update.name {
+            rule.name = name;
+        }
+        if let Some(event_name) = update.event_name.filter(|event_name| *event_name != rule.event_name) {
+            rule.event_name = event_name;
+            rule.matching_since = None;
+        }
+        if let Some(conditions) = update.conditions.filter(|conditions| *conditions != rule.conditions) {
+            rule.conditions = conditions;
+            rule.matching_since = None;
+        }
+        if let Some(action) = update.action {
+            rule.action = action;
+        }
+        if let Some(debounce) = update.debounce {
+            rule.debounce = debounce;
+        }
+        if let Some(cooldown) = update.cooldown {
+            rule.cooldown = cooldown;
+        }
+        if let Some(enabled) = update.enabled {
+            rule.enabled = enabled;
+        }
+        rule.validate().map_err(RuleServiceError::InvalidInput)?;
+        self.update_repo
+            .update(&rule)
+            .await
+            .map_err(|e| from_repository_error(e, "updating rule"))?;
+        Ok(rule)
+    }
+
+    async fn evaluate_rules(
+        &self,
+        device_id: Uuid,
+        event: &Event,
+        state: Option<&DeviceState>,
+        now: &DateTime<Utc>,
+    ) -> Result<Vec<Rule>, RuleServiceError> {
+        let rules = self.get_rules_by_device_id(device_id).await?;
+        let mut fired = Vec::new();
+        for mut rule in rules {
+            let before = rule.clone();
+            let fires = rule.evaluate(event, state, now);
+            // only the evaluation state moves, rules left as is are not written back
+            if rule != before {
+                self.update_repo
+                    .update(&rule)
+                    .await
+                    .map_err(|e| from_repository_error(e, "updating rule"))?;
+            }
+            if fires {
+                fired.push(rule);
+            }
+        }
+        Ok(fired)
+    }
+}
@@ -1,4 +1,7 @@
 pub mod manage_device;
+pub mod manage_device_group;
+pub mod manage_device_model;
 pub mod manage_device_state;
 pub mod manage_event;
-pub mod manage_action;
\ No newline at end of file
+pub mod manage_rule;
+pub mod manage_action;pub mod action_scheduler;
@@ -2,25 +2,25 @@ use std::collections::HashMap;
 
 use chrono::{DateTime, Utc};
 use uuid::Uuid;
-use std::hash::Hash;
 
-use crate::domain::{action::{action_data_type::ActionDataType, action_data_value::ActionDataValue, action_format::ActionFormatError}, device::Device};
+use crate::domain::{action::{action_data_type::ActionDataType, action_data_value::ActionDataValue, action_format::ActionFormatError, action_status::{ActionStatus, ActionTransition}}, device::Device, field_constraints::UnknownKeyPolicy};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Action {
     pub id: Uuid,
     pub device_id: String,
     pub action_name: String,
     pub timestamp: DateTime<Utc>,
     pub payload: HashMap<String, ActionDataValue>,
-}
-
-impl Hash for Action {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.id.hash(state);
-        self.action_name.hash(state);
-        self.timestamp.hash(state);
-    }
+    /// Version of the device schema the action was validated against.
+    pub schema_version: Option<u32>,
+    pub status: ActionStatus,
+    /// Statuses reached so far, oldest first.
+    pub transitions: Vec<ActionTransition>,
+    /// Time the action is handed to the device, right away when none.
+    pub scheduled_at: Option<DateTime<Utc>>,
+    /// Deadline after which the action expires if the device did not acknowledge it.
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl Action {
@@ -31,25 +31,78 @@ impl Action {
             action_name: action_name.to_string(),
             timestamp: *timestamp,
             payload,
+            schema_version: None,
+            status: ActionStatus::Pending,
+            transitions: vec![ActionTransition { status: ActionStatus::Pending, timestamp: *timestamp }],
+            scheduled_at: None,
+            expires_at: None,
         };
     }
     pub fn new_checked(device: &Device, timestamp: &DateTime<Utc>, action_name: &str, payload: &[u8]) -> Result<Self, ActionFormatError> {
-        let action_concerned = match device.actions().get(action_name) {
+        let action_concerned = match device.action(action_name) {
             Some(evt) => evt,
             None => return Err(ActionFormatError::UnsupportedFormat(format!("Action '{action_name}' not found in device events")))
         };
-        let payload_received = action_concerned.format().decode_action(&payload)?;
+        let payload_received = action_concerned.format().decode_action(payload)?;
+        Self::from_values(device, timestamp, action_name, payload_received)
+    }
+    /// Same as `new_checked` for an already decoded payload.
+    pub fn from_values(device: &Device, timestamp: &DateTime<Utc>, action_name: &str, mut payload_received: HashMap<String, ActionDataValue>) -> Result<Self, ActionFormatError> {
+        let action_concerned = match device.action(action_name) {
+            Some(evt) => evt,
+            None => return Err(ActionFormatError::UnsupportedFormat(format!("Action '{action_name}' not found in device events")))
+        };
+        let unknown_keys: Vec<String> = payload_received
+            .keys()
+            .filter(|k| !action_concerned.payload().contains_key(*k))
+            .cloned()
+            .collect();
+        match action_concerned.unknown_keys() {
+            UnknownKeyPolicy::Accept => {}
+            UnknownKeyPolicy::Drop => payload_received.retain(|k, _| !unknown_keys.contains(k)),
+            UnknownKeyPolicy::Reject => {
+                if let Some(key) = unknown_keys.first() {
+                    return Err(ActionFormatError::UnsupportedFormat(format!("Key '{}' is not declared for action '{}'", key, action_name)));
+                }
+            }
+        }
         // iterate over the device's event_data to ensure all keys in payload are valid
         for (key, data_type) in action_concerned.payload().clone().into_iter() {
+            if !payload_received.contains_key(&key) {
+                match action_concerned.constraints().get(&key) {
+                    Some(constraints) if !constraints.is_required() => {
+                        let Some(default) = &constraints.default else {
+                            continue;
+                        };
+                        let default = ActionDataValue::try_from(default.clone())
+                            .map_err(|_| ActionFormatError::UnsupportedFormat(format!("Invalid default value for key {}", key)))?;
+                        payload_received.insert(key.clone(), default);
+                    }
+                    _ => return Err(ActionFormatError::UnsupportedFormat(format!("Key '{}' not found in payload", key)))
+                }
+            }
             let is_valid = match payload_received.get(&key) {
                 Some(ActionDataValue::String(_)) => data_type == ActionDataType::String,
-                Some(ActionDataValue::Number(_)) => data_type == ActionDataType::Number,
+                Some(ActionDataValue::Integer(_)) => matches!(data_type, ActionDataType::Integer | ActionDataType::Float | ActionDataType::Number),
+                Some(ActionDataValue::Float(_)) => matches!(data_type, ActionDataType::Float | ActionDataType::Number),
                 Some(ActionDataValue::Boolean(_)) => data_type == ActionDataType::Boolean,
                 None => return Err(ActionFormatError::UnsupportedFormat(format!("Key '{}' not found in payload", key)))
             };
             if !is_valid {
                 return Err(ActionFormatError::UnsupportedFormat(format!("Invalid value for key {}, {} expected", &key, &data_type.to_string())));
             }
+            // integers sent for a float field are stored as floats
+            if data_type == ActionDataType::Float
+                && let Some(ActionDataValue::Integer(n)) = payload_received.get(&key)
+            {
+                let n = *n as f64;
+                payload_received.insert(key.clone(), ActionDataValue::Float(n));
+            }
+            if let (Some(constraints), Some(value)) = (action_concerned.constraints().get(&key), payload_received.get(&key)) {
+                constraints
+                    .check(&key, &value.clone().into())
+                    .map_err(ActionFormatError::ConstraintViolation)?;
+            }
         }
         return Ok(Self {
             id: Uuid::new_v4(),
@@ -57,7 +110,41 @@ impl Action {
             action_name: action_name.to_string(),
             timestamp: *timestamp,
             payload: payload_received,
+            schema_version: Some(device.schema_version()),
+            status: ActionStatus::Pending,
+            transitions: vec![ActionTransition { status: ActionStatus::Pending, timestamp: *timestamp }],
+            scheduled_at: None,
+            expires_at: None,
         });
     }
+    /// Sets when the action is sent and its deadline, a past `scheduled_at` sends it right away.
+    pub fn schedule(&mut self, scheduled_at: Option<DateTime<Utc>>, expires_at: Option<DateTime<Utc>>, now: &DateTime<Utc>) -> Result<(), String> {
+        let scheduled_at = scheduled_at.filter(|scheduled_at| scheduled_at > now);
+        let sent_at = scheduled_at.unwrap_or(*now);
+        if expires_at.is_some_and(|expires_at| expires_at <= sent_at) {
+            return Err(format!("Action {} would expire before being sent", self.id));
+        }
+        self.scheduled_at = scheduled_at;
+        self.expires_at = expires_at;
+        Ok(())
+    }
+    /// Whether the deadline of the action passed.
+    pub fn is_expired(&self, now: &DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= *now)
+    }
+    /// Whether the deadline passed before the device acknowledged the action.
+    #[cfg(any(feature = "in_memory", feature = "mqtt_client_outbound"))]
+    pub fn is_overdue(&self, now: &DateTime<Utc>) -> bool {
+        matches!(self.status, ActionStatus::Pending | ActionStatus::Sent) && self.is_expired(now)
+    }
+    /// Moves the action to `status`, fails if the lifecycle does not allow it.
+    pub fn transition(&mut self, status: ActionStatus, timestamp: &DateTime<Utc>) -> Result<(), String> {
+        if !self.status.can_become(status) {
+            return Err(format!("Action {} cannot go from {} to {}", self.id, self.status, status));
+        }
+        self.status = status;
+        self.transitions.push(ActionTransition { status, timestamp: *timestamp });
+        Ok(())
+    }
 }
 
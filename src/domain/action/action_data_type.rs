@@ -6,6 +6,8 @@ use serde::{Deserialize, Serialize};
 pub enum ActionDataType {
     String,
     Number,
+    Integer,
+    Float,
     Boolean,
 }
 
@@ -14,6 +16,8 @@ impl ActionDataType {
         match s.to_lowercase().as_str() {
             "string" => Ok(ActionDataType::String),
             "number" => Ok(ActionDataType::Number),
+            "integer" => Ok(ActionDataType::Integer),
+            "float" => Ok(ActionDataType::Float),
             "boolean" => Ok(ActionDataType::Boolean),
             _ => Err(format!("Unsupported event data type: {}", s)),
         }
@@ -25,6 +29,8 @@ impl Display for ActionDataType {
         match self {
             ActionDataType::String => write!(f, "string"),
             ActionDataType::Number => write!(f, "number"),
+            ActionDataType::Integer => write!(f, "integer"),
+            ActionDataType::Float => write!(f, "float"),
             ActionDataType::Boolean => write!(f, "boolean"),
         }
     }
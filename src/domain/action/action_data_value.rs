@@ -2,10 +2,11 @@ use serde_json::Value;
 
 use crate::domain::action::action_data_type::ActionDataType;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum ActionDataValue {
     String(String),
-    Number(u64),
+    Integer(i64),
+    Float(f64),
     Boolean(bool),
 }
 
@@ -16,11 +17,24 @@ impl ActionDataValue {
     ) -> Result<ActionDataValue, EventDataValueError> {
         match data_type {
             ActionDataType::String => Ok(ActionDataValue::String(value.to_string())),
-            ActionDataType::Number => {
+            ActionDataType::Integer => {
                 let num = value
-                    .parse::<u64>()
+                    .parse::<i64>()
                     .map_err(|_| EventDataValueError::InvalidNumber(value.to_owned()))?;
-                Ok(ActionDataValue::Number(num))
+                Ok(ActionDataValue::Integer(num))
+            }
+            ActionDataType::Float => {
+                let num = parse_finite_float(value)
+                    .ok_or_else(|| EventDataValueError::InvalidNumber(value.to_owned()))?;
+                Ok(ActionDataValue::Float(num))
+            }
+            ActionDataType::Number => {
+                if let Ok(num) = value.parse::<i64>() {
+                    return Ok(ActionDataValue::Integer(num));
+                }
+                let num = parse_finite_float(value)
+                    .ok_or_else(|| EventDataValueError::InvalidNumber(value.to_owned()))?;
+                Ok(ActionDataValue::Float(num))
             }
             ActionDataType::Boolean => match value.to_lowercase().as_str() {
                 "true" | "1" => Ok(ActionDataValue::Boolean(true)),
@@ -31,11 +45,16 @@ impl ActionDataValue {
     }
 }
 
+fn parse_finite_float(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
 impl From<ActionDataValue> for Value {
     fn from(value: ActionDataValue) -> Self {
         match value {
             ActionDataValue::String(s) => Value::from(s.to_owned()),
-            ActionDataValue::Number(n) => Value::from(n.to_owned()),
+            ActionDataValue::Integer(n) => Value::from(n.to_owned()),
+            ActionDataValue::Float(n) => Value::from(n.to_owned()),
             ActionDataValue::Boolean(b) => Value::from(b.to_owned()),
         }
     }
@@ -46,9 +65,16 @@ impl TryFrom<Value> for ActionDataValue {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Bool(b) => Ok(ActionDataValue::Boolean(b)),
-            Value::Number(n) => Ok(ActionDataValue::Number(
-                n.as_u64().expect("Should be a valid u64"),
-            )),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    return Ok(ActionDataValue::Integer(i));
+                }
+                // u64 values above i64::MAX and fractional values end up here
+                match n.as_f64() {
+                    Some(f) => Ok(ActionDataValue::Float(f)),
+                    None => Err(EventDataValueError::InvalidNumber(n.to_string())),
+                }
+            }
             Value::String(s) => Ok(ActionDataValue::String(s)),
             _ => return Err(EventDataValueError::InvalidType),
         }
@@ -62,9 +88,12 @@ impl TryFrom<&str> for ActionDataValue {
         if value.is_empty() {
             return Err(EventDataValueError::InvalidType);
         }
-        // Attempt to parse as a number first
-        if let Ok(num) = value.parse::<u64>() {
-            return Ok(ActionDataValue::Number(num));
+        // Attempt to parse as an integer first, then as a float
+        if let Ok(num) = value.parse::<i64>() {
+            return Ok(ActionDataValue::Integer(num));
+        }
+        if let Some(num) = parse_finite_float(value) {
+            return Ok(ActionDataValue::Float(num));
         }
         // Attempt to parse as a boolean
         if let Ok(boolean) = value.parse::<bool>() {
@@ -1,23 +1,45 @@
 use std::collections::HashMap;
 
-use crate::domain::action::{action_data_type::ActionDataType, action_format::ActionFormat};
+use crate::domain::{action::{action_data_type::ActionDataType, action_format::ActionFormat}, field_constraints::{FieldConstraints, UnknownKeyPolicy}};
 
 #[derive(Debug, Clone)]
 pub struct ActionEmittable {
     format: ActionFormat,
     payload: HashMap<String, ActionDataType>,
+    constraints: HashMap<String, FieldConstraints>,
+    unknown_keys: UnknownKeyPolicy,
 }
 
 impl ActionEmittable {
     pub fn new(format: ActionFormat, payload: HashMap<String, ActionDataType>) -> Self {
-        Self { format, payload }
+        Self { format, payload, constraints: HashMap::new(), unknown_keys: UnknownKeyPolicy::default() }
     }
 
     pub fn format(&self) -> &ActionFormat {
         &self.format
     }
 
+    pub(crate) fn format_mut(&mut self) -> &mut ActionFormat {
+        &mut self.format
+    }
+
     pub fn payload(&self) -> &HashMap<String, ActionDataType> {
         &self.payload
     }
+
+    pub fn constraints(&self) -> &HashMap<String, FieldConstraints> {
+        &self.constraints
+    }
+
+    pub fn set_constraints(&mut self, constraints: HashMap<String, FieldConstraints>) {
+        self.constraints = constraints;
+    }
+
+    pub fn unknown_keys(&self) -> &UnknownKeyPolicy {
+        &self.unknown_keys
+    }
+
+    pub fn set_unknown_keys(&mut self, unknown_keys: UnknownKeyPolicy) {
+        self.unknown_keys = unknown_keys;
+    }
 }
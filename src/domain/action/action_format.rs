@@ -2,55 +2,111 @@ use std::{collections::HashMap, fmt::Display};
 
 use serde_json::Value;
 
-use crate::domain::action::action_data_value::ActionDataValue;
+use crate::domain::{binary_layout::BinaryLayout, protobuf::ProtobufMessage, action::action_data_value::ActionDataValue};
 
 #[derive(Debug, Clone)]
 pub enum ActionFormat {
     Json,
+    Cbor,
+    MessagePack,
+    /// Packed struct, the layout gives the position of each field.
+    Binary(BinaryLayout),
+    /// Protobuf message, written `protobuf:<message type>` in device definitions.
+    Protobuf(ProtobufMessage),
 }
 
 impl ActionFormat {
+    /// Binary formats can't be carried as is in text envelopes (MQTT messages, HTTP JSON bodies).
+    #[cfg(any(feature = "mqtt", feature = "reqwest"))]
+    pub fn is_binary(&self) -> bool {
+        match self {
+            ActionFormat::Json => false,
+            ActionFormat::Cbor | ActionFormat::MessagePack | ActionFormat::Binary(_) | ActionFormat::Protobuf(_) => true,
+        }
+    }
+    /// Builds a format from its name, the binary format requires a layout.
+    pub fn from_parts(format: &str, binary_layout: Option<BinaryLayout>) -> Result<Self, String> {
+        match (ActionFormat::try_from(format)?, binary_layout) {
+            (ActionFormat::Binary(_), Some(layout)) => Ok(ActionFormat::Binary(layout)),
+            (ActionFormat::Binary(_), None) => Err(format!("Missing binary layout for format {}", format)),
+            (format, _) => Ok(format),
+        }
+    }
+    /// Protobuf formats built from their name lack the descriptor of their message until resolved.
+    pub fn is_resolved(&self) -> bool {
+        match self {
+            ActionFormat::Protobuf(message) => message.is_resolved(),
+            _ => true,
+        }
+    }
+    pub fn binary_layout(&self) -> Option<&BinaryLayout> {
+        match self {
+            ActionFormat::Binary(layout) => Some(layout),
+            _ => None,
+        }
+    }
     pub fn decode_action(
         &self,
         payload: &[u8],
     ) -> Result<HashMap<String, ActionDataValue>, ActionFormatError> {
-        match self {
+        let action_raw: HashMap<String, Value> = match self {
             ActionFormat::Json => {
-                // For simplicity, we assume the payload is a JSON string that can be parsed into a HashMap
-                // In a real application, you would use a JSON library to parse the payload
                 let json_str = String::from_utf8_lossy(payload);
-                let mut payload = HashMap::new();
-                let event_raw: HashMap<String, Value> = serde_json::from_str(&json_str)
-                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))?;
-                // iterate over the device's event_data to ensure all keys in payload are valid
-                for (key, value) in event_raw.into_iter() {
-                    let value = ActionDataValue::try_from(value)
-                        .map_err(|_| ActionFormatError::UnsupportedFormat(key.clone()))?;
-                    payload.insert(key, value);
-                }
-                return Ok(payload);
+                serde_json::from_str(&json_str)
+                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))?
             }
+            ActionFormat::Cbor => ciborium::from_reader(payload)
+                .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))?,
+            ActionFormat::MessagePack => rmp_serde::from_slice(payload)
+                .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))?,
+            ActionFormat::Binary(layout) => layout
+                .decode(payload)
+                .map_err(ActionFormatError::UnsupportedFormat)?,
+            ActionFormat::Protobuf(message) => message
+                .decode(payload)
+                .map_err(ActionFormatError::UnsupportedFormat)?,
+        };
+        let mut payload = HashMap::new();
+        for (key, value) in action_raw.into_iter() {
+            let value = ActionDataValue::try_from(value)
+                .map_err(|_| ActionFormatError::UnsupportedFormat(key.clone()))?;
+            payload.insert(key, value);
         }
+        Ok(payload)
     }
+    #[cfg(feature = "mqtt_outbound")]
     pub fn encode_event(
         &self,
         event_payload: HashMap<String, ActionDataValue>,
-    ) -> Result<String, ActionFormatError> {
+    ) -> Result<Vec<u8>, ActionFormatError> {
+        let payload_converted: HashMap<String, Value> = event_payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::from(v.to_owned())))
+            .collect();
         match self {
             ActionFormat::Json => {
-                let payload_converted: HashMap<String, Value> = event_payload
-                    .iter()
-                    .map(|(k, v)| {
-                        let val = match v {
-                            ActionDataValue::String(s) => Value::from(s.to_owned()),
-                            ActionDataValue::Number(n) => Value::from(n.to_owned()),
-                            ActionDataValue::Boolean(b) => Value::from(b.to_owned()),
-                        };
-                        (k.to_string(), val)
-                    })
-                    .collect();
-                return serde_json::to_string(&payload_converted)
-                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()));
+                serde_json::to_vec(&payload_converted)
+                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))
+            }
+            ActionFormat::Cbor => {
+                let mut encoded = Vec::new();
+                ciborium::into_writer(&payload_converted, &mut encoded)
+                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))?;
+                Ok(encoded)
+            }
+            ActionFormat::MessagePack => {
+                rmp_serde::to_vec(&payload_converted)
+                    .map_err(|e| ActionFormatError::UnsupportedFormat(e.to_string()))
+            }
+            ActionFormat::Binary(layout) => {
+                layout
+                    .encode(&payload_converted)
+                    .map_err(ActionFormatError::UnsupportedFormat)
+            }
+            ActionFormat::Protobuf(message) => {
+                message
+                    .encode(&payload_converted)
+                    .map_err(ActionFormatError::UnsupportedFormat)
             }
         }
     }
@@ -60,6 +116,10 @@ impl Display for ActionFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             ActionFormat::Json => write!(f, "json"),
+            ActionFormat::Cbor => write!(f, "cbor"),
+            ActionFormat::MessagePack => write!(f, "msgpack"),
+            ActionFormat::Binary(_) => write!(f, "binary"),
+            ActionFormat::Protobuf(message) => write!(f, "protobuf:{}", message.name()),
         }
     }
 }
@@ -68,8 +128,14 @@ impl TryFrom<&str> for ActionFormat {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some(message) = ProtobufMessage::from_format_name(value) {
+            return Ok(ActionFormat::Protobuf(message));
+        }
         match value.to_lowercase().as_str() {
             "json" => Ok(ActionFormat::Json),
+            "cbor" => Ok(ActionFormat::Cbor),
+            "msgpack" | "messagepack" => Ok(ActionFormat::MessagePack),
+            "binary" => Ok(ActionFormat::Binary(BinaryLayout::default())),
             _ => Err(format!("Unsupported event format: {}", value)),
         }
     }
@@ -77,6 +143,7 @@ impl TryFrom<&str> for ActionFormat {
 
 pub enum ActionFormatError {
     UnsupportedFormat(String),
+    ConstraintViolation(String),
 }
 
 impl Display for ActionFormatError {
@@ -85,6 +152,9 @@ impl Display for ActionFormatError {
             ActionFormatError::UnsupportedFormat(msg) => {
                 write!(f, "Unsupported event format: {}", msg)
             }
+            ActionFormatError::ConstraintViolation(msg) => {
+                write!(f, "Constraint violation: {}", msg)
+            }
         }
     }
 }
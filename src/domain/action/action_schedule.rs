@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Utc};
+use chrono_tz::Tz;
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::{
+    action::{action::Action, action_data_value::ActionDataValue},
+    cron::CronExpression,
+};
+
+/// Action sent to a device each time the cron expression matches, in the schedule time zone.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActionSchedule {
+    pub id: Uuid,
+    pub device_id: String,
+    pub action_name: String,
+    pub payload: HashMap<String, ActionDataValue>,
+    /// Version of the device schema the payload was validated against.
+    pub schema_version: Option<u32>,
+    pub cron: CronExpression,
+    pub timezone: Tz,
+    pub created_at: DateTime<Utc>,
+    /// Next time the schedule runs, none once the expression matches no time anymore.
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl ActionSchedule {
+    /// Schedule sending actions like `action`, whose payload is already validated against the device.
+    #[cfg(feature = "axum")]
+    pub fn new(action: &Action, cron: CronExpression, timezone: Tz, now: &DateTime<Utc>) -> Result<Self, String> {
+        let mut schedule = Self {
+            id: Uuid::new_v4(),
+            device_id: action.device_id.clone(),
+            action_name: action.action_name.clone(),
+            payload: action.payload.clone(),
+            schema_version: action.schema_version,
+            cron,
+            timezone,
+            created_at: *now,
+            next_run_at: None,
+        };
+        schedule.next_run_at = schedule.next_run_after(now);
+        if schedule.next_run_at.is_none() {
+            return Err(format!("Cron expression '{}' never matches", schedule.cron));
+        }
+        Ok(schedule)
+    }
+    /// First run strictly after `after`.
+    pub fn next_run_after(&self, after: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.cron
+            .next_after(&after.with_timezone(&self.timezone))
+            .map(|next| next.with_timezone(&Utc))
+    }
+    /// Action sent by a run of the schedule.
+    pub fn action(&self, now: &DateTime<Utc>) -> Action {
+        let mut action = Action::new(self.device_id.clone(), &self.action_name, now, self.payload.clone());
+        action.schema_version = self.schema_version;
+        action
+    }
+}
+
+/// Outcome of a run of a schedule, `action_id` is the action sent, `error` why none was.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ScheduleRun {
+    pub schedule_id: Uuid,
+    /// Time the run was due.
+    pub scheduled_for: DateTime<Utc>,
+    pub ran_at: DateTime<Utc>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action_id: Option<Uuid>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
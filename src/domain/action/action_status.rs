@@ -0,0 +1,103 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Step of the lifecycle of an action, statuses only move forward:
+/// pending → sent → acknowledged → succeeded, failed or expired.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ActionStatus {
+    /// Stored, not handed to a transport yet.
+    #[default]
+    Pending,
+    /// Published to the device.
+    Sent,
+    /// The device received the action.
+    Acknowledged,
+    Succeeded,
+    Failed,
+    /// The action was not completed in time.
+    Expired,
+}
+
+impl ActionStatus {
+    /// Whether the action cannot change anymore.
+    pub fn is_final(&self) -> bool {
+        matches!(self, ActionStatus::Succeeded | ActionStatus::Failed | ActionStatus::Expired)
+    }
+
+    /// Whether devices may report this status.
+    pub fn is_acknowledgement(&self) -> bool {
+        matches!(self, ActionStatus::Acknowledged | ActionStatus::Succeeded | ActionStatus::Failed)
+    }
+
+    /// Whether an action can move to `next`, steps may be skipped as devices can report
+    /// the outcome directly.
+    pub fn can_become(&self, next: ActionStatus) -> bool {
+        !self.is_final() && next.rank() > self.rank()
+    }
+
+    fn rank(&self) -> u8 {
+        match self {
+            ActionStatus::Pending => 0,
+            ActionStatus::Sent => 1,
+            ActionStatus::Acknowledged => 2,
+            ActionStatus::Succeeded | ActionStatus::Failed | ActionStatus::Expired => 3,
+        }
+    }
+}
+
+impl Display for ActionStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ActionStatus::Pending => write!(f, "pending"),
+            ActionStatus::Sent => write!(f, "sent"),
+            ActionStatus::Acknowledged => write!(f, "acknowledged"),
+            ActionStatus::Succeeded => write!(f, "succeeded"),
+            ActionStatus::Failed => write!(f, "failed"),
+            ActionStatus::Expired => write!(f, "expired"),
+        }
+    }
+}
+
+impl TryFrom<&str> for ActionStatus {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "pending" => Ok(ActionStatus::Pending),
+            "sent" => Ok(ActionStatus::Sent),
+            "acknowledged" => Ok(ActionStatus::Acknowledged),
+            "succeeded" => Ok(ActionStatus::Succeeded),
+            "failed" => Ok(ActionStatus::Failed),
+            "expired" => Ok(ActionStatus::Expired),
+            _ => Err(format!("Unsupported action status: {}", value)),
+        }
+    }
+}
+
+impl Serialize for ActionStatus {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ActionStatus {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        ActionStatus::try_from(s.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Status an action reached and when.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ActionTransition {
+    pub status: ActionStatus,
+    pub timestamp: DateTime<Utc>,
+}
@@ -2,4 +2,6 @@ pub mod action;
 pub mod action_data_type;
 pub mod action_data_value;
 pub mod action_emittable;
-pub mod action_format;
\ No newline at end of file
+pub mod action_format;
+pub mod action_schedule;
+pub mod action_status;
\ No newline at end of file
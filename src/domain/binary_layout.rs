@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Endianness {
+    #[default]
+    Little,
+    Big,
+}
+
+/// Position of a field inside a packed payload.
+/// The raw value is read on `width` bytes at `offset`, then `mask` selects bits inside it.
+/// A mask of a single bit gives a boolean, a scale gives a float (`raw * scale`), an integer otherwise.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BinaryField {
+    pub offset: usize,
+    pub width: usize,
+    #[serde(default)]
+    pub endianness: Endianness,
+    #[serde(default)]
+    pub signed: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scale: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mask: Option<u64>,
+}
+
+impl BinaryField {
+    fn check(&self, key: &str) -> Result<(), String> {
+        if self.width == 0 || self.width > 8 {
+            return Err(format!("width of field {} must be between 1 and 8 bytes", key));
+        }
+        if self.end().is_none() {
+            return Err(format!("offset of field {} is out of range", key));
+        }
+        if self.mask == Some(0) || self.mask.is_some_and(|m| m & !self.width_mask() != 0) {
+            return Err(format!("mask of field {} does not fit in {} bytes", key, self.width));
+        }
+        Ok(())
+    }
+
+    /// Position right after the field, none if it can't be addressed.
+    fn end(&self) -> Option<usize> {
+        self.offset.checked_add(self.width)
+    }
+
+    fn width_mask(&self) -> u64 {
+        if self.width == 8 { u64::MAX } else { (1u64 << (self.width * 8)) - 1 }
+    }
+
+    /// Bits selected by the field, shifted down to bit 0.
+    fn value_mask(&self) -> (u64, u32) {
+        match self.mask {
+            Some(mask) => (mask >> mask.trailing_zeros(), mask.trailing_zeros()),
+            None => (self.width_mask(), 0),
+        }
+    }
+
+    fn is_flag(&self) -> bool {
+        self.mask.is_some_and(|m| m.count_ones() == 1)
+    }
+
+    fn read_raw(&self, payload: &[u8]) -> Option<u64> {
+        let bytes = payload.get(self.offset..self.end()?)?;
+        let mut raw = 0u64;
+        match self.endianness {
+            Endianness::Little => bytes.iter().rev().for_each(|b| raw = (raw << 8) | *b as u64),
+            Endianness::Big => bytes.iter().for_each(|b| raw = (raw << 8) | *b as u64),
+        }
+        Some(raw)
+    }
+
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    fn write_raw(&self, payload: &mut [u8], raw: u64) {
+        let Some(bytes) = self.end().and_then(|end| payload.get_mut(self.offset..end)) else {
+            return;
+        };
+        for i in 0..self.width {
+            let byte = (raw >> (i * 8)) as u8;
+            match self.endianness {
+                Endianness::Little => bytes[i] = byte,
+                Endianness::Big => bytes[self.width - 1 - i] = byte,
+            }
+        }
+    }
+
+    fn decode(&self, key: &str, payload: &[u8]) -> Result<Value, String> {
+        let raw = self
+            .read_raw(payload)
+            .ok_or_else(|| format!("payload too short for field {}", key))?;
+        let (value_mask, shift) = self.value_mask();
+        let bits = (raw >> shift) & value_mask;
+        if self.is_flag() {
+            return Ok(Value::Bool(bits != 0));
+        }
+        let value = if self.signed {
+            // sign extend from the highest selected bit
+            let unused = value_mask.leading_zeros();
+            ((bits << unused) as i64) >> unused
+        } else {
+            i64::try_from(bits).map_err(|_| format!("value of field {} does not fit in an integer", key))?
+        };
+        match self.scale {
+            Some(scale) => Ok(Value::from(value as f64 * scale)),
+            None => Ok(Value::from(value)),
+        }
+    }
+
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    fn encode(&self, key: &str, value: &Value, payload: &mut [u8]) -> Result<(), String> {
+        let number = match value {
+            Value::Bool(b) => *b as i64,
+            Value::Number(n) => {
+                let n = n
+                    .as_f64()
+                    .ok_or_else(|| format!("invalid number for field {}", key))?;
+                (n / self.scale.unwrap_or(1.)).round() as i64
+            }
+            _ => return Err(format!("field {} can't be packed, only numbers and booleans can", key)),
+        };
+        let (value_mask, shift) = self.value_mask();
+        let in_range = if self.signed {
+            let max = (value_mask >> 1) as i64;
+            number >= -max - 1 && number <= max
+        } else {
+            number >= 0 && (number as u64) <= value_mask
+        };
+        if !in_range {
+            return Err(format!("value {} of field {} does not fit in its bits", number, key));
+        }
+        let raw = self.read_raw(payload).unwrap_or_default();
+        let field_mask = value_mask << shift;
+        let raw = (raw & !field_mask) | (((number as u64) & value_mask) << shift);
+        self.write_raw(payload, raw);
+        Ok(())
+    }
+}
+
+/// Field positions of a fixed-layout binary payload, shared by events and actions.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct BinaryLayout {
+    fields: HashMap<String, BinaryField>,
+}
+
+impl BinaryLayout {
+    /// Size of the packed payload, the end of the furthest field.
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    pub fn size(&self) -> usize {
+        self.fields.values().filter_map(BinaryField::end).max().unwrap_or(0)
+    }
+
+    /// Checks every field can be read, for layouts being stored.
+    pub fn validate(&self) -> Result<(), String> {
+        for (key, field) in self.fields.iter() {
+            field.check(key)?;
+        }
+        Ok(())
+    }
+
+    pub fn decode(&self, payload: &[u8]) -> Result<HashMap<String, Value>, String> {
+        let mut values = HashMap::new();
+        for (key, field) in self.fields.iter() {
+            field.check(key)?;
+            values.insert(key.clone(), field.decode(key, payload)?);
+        }
+        Ok(values)
+    }
+
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    pub fn encode(&self, values: &HashMap<String, Value>) -> Result<Vec<u8>, String> {
+        let mut payload = vec![0u8; self.size()];
+        for (key, value) in values.iter() {
+            let field = self
+                .fields
+                .get(key)
+                .ok_or_else(|| format!("key {} has no position in the binary layout", key))?;
+            field.check(key)?;
+            field.encode(key, value, &mut payload)?;
+        }
+        Ok(payload)
+    }
+}
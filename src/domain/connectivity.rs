@@ -0,0 +1,109 @@
+use std::{collections::HashMap, fmt::Display};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::{
+    device::Device,
+    event::{event::Event, event_data_value::EventDataValue},
+};
+
+/// Name of the events recording connectivity transitions, their payload holds the new `status`.
+pub const CONNECTIVITY_EVENT_NAME: &str = "connectivity";
+
+/// Whether a device reports within its heartbeat interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    /// Nothing was received since the device was created.
+    #[default]
+    Unknown,
+    Online,
+    Offline,
+}
+
+impl Display for Connectivity {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Connectivity::Unknown => write!(f, "unknown"),
+            Connectivity::Online => write!(f, "online"),
+            Connectivity::Offline => write!(f, "offline"),
+        }
+    }
+}
+
+impl TryFrom<&str> for Connectivity {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "unknown" => Ok(Connectivity::Unknown),
+            "online" => Ok(Connectivity::Online),
+            "offline" => Ok(Connectivity::Offline),
+            _ => Err(format!("Unsupported connectivity: {}", value)),
+        }
+    }
+}
+
+impl Serialize for Connectivity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for Connectivity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Connectivity::try_from(s.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+/// Whether nothing was received from the device within its heartbeat interval,
+/// devices without an interval never time out.
+pub fn is_timed_out(device: &Device, last_seen: &DateTime<Utc>, now: &DateTime<Utc>) -> bool {
+    match device.heartbeat_interval() {
+        // intervals are validated when the device is stored, an out of range one never elapses
+        Some(interval) => i64::try_from(interval)
+            .ok()
+            .and_then(TimeDelta::try_seconds)
+            .is_some_and(|interval| *now - *last_seen > interval),
+        None => false,
+    }
+}
+
+/// Device going online or offline.
+#[derive(Debug, Clone)]
+pub struct ConnectivityChange {
+    // only published over MQTT
+    #[cfg_attr(not(feature = "mqtt_inbound"), allow(dead_code))]
+    pub device_id: Uuid,
+    pub device_physical_id: String,
+    pub connectivity: Connectivity,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl ConnectivityChange {
+    pub fn new(device: &Device, connectivity: Connectivity, timestamp: &DateTime<Utc>) -> Self {
+        Self {
+            device_id: *device.id(),
+            device_physical_id: device.physical_id().to_string(),
+            connectivity,
+            timestamp: *timestamp,
+        }
+    }
+
+    /// Event recording the change among the events of the device.
+    pub fn to_event(&self) -> Event {
+        let payload = HashMap::from([(
+            "status".to_string(),
+            EventDataValue::String(self.connectivity.to_string()),
+        )]);
+        Event::new(self.device_physical_id.clone(), CONNECTIVITY_EVENT_NAME, &self.timestamp, payload)
+    }
+}
@@ -0,0 +1,170 @@
+use std::fmt::Display;
+
+use chrono::{DateTime, Datelike, Duration, LocalResult, NaiveDate, NaiveDateTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+
+const MONTHS: [&str; 12] = ["jan", "feb", "mar", "apr", "may", "jun", "jul", "aug", "sep", "oct", "nov", "dec"];
+const WEEKDAYS: [&str; 7] = ["sun", "mon", "tue", "wed", "thu", "fri", "sat"];
+
+// a matching time exists within 4 years for any valid expression, 29th of February included
+const MAX_SEARCHED_DAYS: i64 = 4 * 366;
+
+/// Cron expression with five fields: minute, hour, day of month, month and day of week,
+/// e.g. `0 7 * * mon-fri`. Fields accept `*`, values, ranges `a-b`, steps `*/n` or `a-b/n`
+/// and lists separated by commas. Months and days of week may be written by their
+/// three first letters, sunday is 0 or 7. As in cron, a time matches when both the day
+/// of month and the day of week match, or either of them when both are restricted.
+#[derive(Debug, Clone)]
+pub struct CronExpression {
+    source: String,
+    minutes: u64,
+    hours: u64,
+    days_of_month: u64,
+    months: u64,
+    days_of_week: u64,
+    days_of_month_restricted: bool,
+    days_of_week_restricted: bool,
+}
+
+impl CronExpression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let fields: Vec<&str> = source.split_whitespace().collect();
+        let [minutes, hours, days_of_month, months, days_of_week] = fields.as_slice() else {
+            return Err(format!("Cron expression '{}' must have 5 fields, {} given", source, fields.len()));
+        };
+        let mut days_of_week_mask = parse_field(days_of_week, "day of week", 0, 7, &WEEKDAYS)?;
+        // 7 is another way to write sunday
+        if days_of_week_mask & (1 << 7) != 0 {
+            days_of_week_mask = (days_of_week_mask & !(1 << 7)) | 1;
+        }
+        Ok(Self {
+            source: fields.join(" "),
+            minutes: parse_field(minutes, "minute", 0, 59, &[])?,
+            hours: parse_field(hours, "hour", 0, 23, &[])?,
+            days_of_month: parse_field(days_of_month, "day of month", 1, 31, &[])?,
+            months: parse_field(months, "month", 1, 12, &MONTHS)?,
+            days_of_week: days_of_week_mask,
+            days_of_month_restricted: *days_of_month != "*",
+            days_of_week_restricted: *days_of_week != "*",
+        })
+    }
+
+    /// First matching time strictly after `after`, in its time zone. Local times skipped by
+    /// a daylight saving change do not run, repeated ones run once.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let timezone = after.timezone();
+        let start = after.naive_local().with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let last_day = start.date() + Duration::days(MAX_SEARCHED_DAYS);
+        let mut date = start.date();
+        while date <= last_day {
+            if self.matches_day(&date) {
+                let first_minute = if date == start.date() { start.hour() * 60 + start.minute() } else { 0 };
+                for minute_of_day in first_minute..24 * 60 {
+                    let (hour, minute) = (minute_of_day / 60, minute_of_day % 60);
+                    if !has(self.hours, hour) || !has(self.minutes, minute) {
+                        continue;
+                    }
+                    let local = NaiveDateTime::new(date, chrono::NaiveTime::from_hms_opt(hour, minute, 0)?);
+                    match timezone.from_local_datetime(&local) {
+                        LocalResult::Single(time) | LocalResult::Ambiguous(time, _) if time > *after => return Some(time),
+                        _ => {}
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+        None
+    }
+
+    fn matches_day(&self, date: &NaiveDate) -> bool {
+        if !has(self.months, date.month()) {
+            return false;
+        }
+        let day_of_month = has(self.days_of_month, date.day());
+        let day_of_week = has(self.days_of_week, date.weekday().num_days_from_sunday());
+        match (self.days_of_month_restricted, self.days_of_week_restricted) {
+            (true, true) => day_of_month || day_of_week,
+            _ => day_of_month && day_of_week,
+        }
+    }
+}
+
+fn has(mask: u64, value: u32) -> bool {
+    mask & (1 << value) != 0
+}
+
+fn parse_field(field: &str, name: &str, min: u32, max: u32, names: &[&str]) -> Result<u64, String> {
+    let mut mask = 0;
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step = step
+                    .parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("Invalid step '{}' in {} field", step, name))?;
+                (range, step)
+            }
+            None => (part, 1),
+        };
+        let (first, last) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((first, last)) => (parse_value(first, name, names, min)?, parse_value(last, name, names, min)?),
+                // `a/n` runs from `a` to the end of the range
+                None if step > 1 => (parse_value(range, name, names, min)?, max),
+                None => {
+                    let value = parse_value(range, name, names, min)?;
+                    (value, value)
+                }
+            },
+        };
+        if first < min || last > max || first > last {
+            return Err(format!("Invalid range '{}' in {} field, values go from {} to {}", range, name, min, max));
+        }
+        for value in (first..=last).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+    Ok(mask)
+}
+
+fn parse_value(value: &str, name: &str, names: &[&str], first_name_value: u32) -> Result<u32, String> {
+    if let Some(position) = names.iter().position(|n| n.eq_ignore_ascii_case(value)) {
+        return Ok(position as u32 + first_name_value);
+    }
+    value
+        .parse::<u32>()
+        .map_err(|_| format!("Invalid value '{}' in {} field", value, name))
+}
+
+impl PartialEq for CronExpression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Display for CronExpression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Serialize for CronExpression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for CronExpression {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        CronExpression::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
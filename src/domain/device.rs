@@ -1,7 +1,8 @@
+use chrono::{DateTime, TimeDelta, Utc};
 use uuid::Uuid;
-use std::{collections::HashMap};
+use std::collections::{BTreeSet, HashMap};
 
-use crate::domain::{action::action_emittable::ActionEmittable, event::event_emittable::EventEmittable};
+use crate::domain::{action::{action_emittable::ActionEmittable, action_format::ActionFormat}, device_model::DeviceModel, device_schema::DeviceSchema, event::{event_emittable::EventEmittable, event_format::EventFormat}, protobuf, state::StateMergePolicy, connectivity::Connectivity};
 
 #[derive(Debug, Clone)]
 pub struct Device {
@@ -11,11 +12,22 @@ pub struct Device {
     name: String,
     events: HashMap<String, EventEmittable>,
     actions: HashMap<String, ActionEmittable>,
+    descriptor_set: Option<Vec<u8>>,
+    schema_version: u32,
+    model_id: Option<Uuid>,
+    labels: HashMap<String, String>,
+    tags: BTreeSet<String>,
+    state_merge_policy: StateMergePolicy,
+    heartbeat_interval: Option<u64>,
+    connectivity: Connectivity,
+    /// Emittables of the model, set by `apply_model` when the device is loaded, never stored with the device.
+    model_events: HashMap<String, EventEmittable>,
+    model_actions: HashMap<String, ActionEmittable>,
 }
 
 impl Device {
     pub fn new(id: &Uuid, physical_id: &str, user_id: &Uuid, name: &str, events: HashMap<String, EventEmittable>, actions: HashMap<String, ActionEmittable>) -> Self {
-        return Self { id: id.clone(), physical_id: physical_id.to_string(), user_id: user_id.clone(), name: name.to_string(), events, actions }
+        Self { id: *id, physical_id: physical_id.to_string(), user_id: *user_id, name: name.to_string(), events, actions, descriptor_set: None, schema_version: 1, model_id: None, labels: HashMap::new(), tags: BTreeSet::new(), state_merge_policy: StateMergePolicy::default(), heartbeat_interval: None, connectivity: Connectivity::default(), model_events: HashMap::new(), model_actions: HashMap::new() }
     }
     pub fn id(&self) -> &Uuid {
         &self.id
@@ -35,17 +47,158 @@ impl Device {
     pub fn set_name(&mut self, name: &str) {
         self.name = name.to_string();
     }
+    /// Events declared on the device itself, they override the events of its model.
     pub fn events(&self) -> &HashMap<String, EventEmittable> {
         &self.events
     }
     pub fn set_events(&mut self, events: HashMap<String, EventEmittable>) {
         self.events = events;
     }
+    /// Actions declared on the device itself, they override the actions of its model.
     pub fn actions(&self) -> &HashMap<String, ActionEmittable> {
         &self.actions
     }
     pub fn set_actions(&mut self, actions: HashMap<String, ActionEmittable>) {
         self.actions = actions;
     }
+    pub fn model_id(&self) -> Option<&Uuid> {
+        self.model_id.as_ref()
+    }
+    pub fn set_model_id(&mut self, model_id: Option<Uuid>) {
+        self.model_id = model_id;
+    }
+    /// Free-form key/value metadata, e.g. `site=plant-2`.
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+    pub fn set_labels(&mut self, labels: HashMap<String, String>) {
+        self.labels = labels;
+    }
+    pub fn tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+    pub fn set_tags(&mut self, tags: BTreeSet<String>) {
+        self.tags = tags;
+    }
+    /// How reported values replace the stored state of the device.
+    pub fn state_merge_policy(&self) -> StateMergePolicy {
+        self.state_merge_policy
+    }
+    pub fn set_state_merge_policy(&mut self, state_merge_policy: StateMergePolicy) {
+        self.state_merge_policy = state_merge_policy;
+    }
+    /// Seconds the device is expected to report within, devices without one are not monitored.
+    pub fn heartbeat_interval(&self) -> Option<u64> {
+        self.heartbeat_interval
+    }
+    pub fn set_heartbeat_interval(&mut self, heartbeat_interval: Option<u64>) {
+        self.heartbeat_interval = heartbeat_interval;
+    }
+    /// Checks the binary layouts of every event and action, model ones included.
+    pub fn validate_binary_layouts(&self) -> Result<(), String> {
+        for (name, event) in self.effective_events() {
+            if let Some(layout) = event.format().binary_layout() {
+                layout.validate().map_err(|e| format!("Event '{}': {}", name, e))?;
+            }
+        }
+        for (name, action) in self.effective_actions() {
+            if let Some(layout) = action.format().binary_layout() {
+                layout.validate().map_err(|e| format!("Action '{}': {}", name, e))?;
+            }
+        }
+        Ok(())
+    }
+    /// Checks the heartbeat interval can be compared with the time elapsed since the device was last seen.
+    pub fn validate_heartbeat_interval(&self) -> Result<(), String> {
+        match self.heartbeat_interval {
+            Some(interval) if i64::try_from(interval).ok().and_then(TimeDelta::try_seconds).is_none() => {
+                Err(format!("Heartbeat interval of {} seconds out of range", interval))
+            }
+            _ => Ok(()),
+        }
+    }
+    pub fn connectivity(&self) -> Connectivity {
+        self.connectivity
+    }
+    pub fn set_connectivity(&mut self, connectivity: Connectivity) {
+        self.connectivity = connectivity;
+    }
+    pub fn apply_model(&mut self, model: &DeviceModel) {
+        self.model_events = model.events().clone();
+        self.model_actions = model.actions().clone();
+    }
+    /// Event as the device emits it, its own definition first, then the one of its model.
+    pub fn event(&self, event_name: &str) -> Option<&EventEmittable> {
+        self.events.get(event_name).or_else(|| self.model_events.get(event_name))
+    }
+    pub fn action(&self, action_name: &str) -> Option<&ActionEmittable> {
+        self.actions.get(action_name).or_else(|| self.model_actions.get(action_name))
+    }
+    /// Events of the model with the overrides of the device applied.
+    pub fn effective_events(&self) -> HashMap<String, EventEmittable> {
+        let mut events = self.model_events.clone();
+        events.extend(self.events.clone());
+        events
+    }
+    pub fn effective_actions(&self) -> HashMap<String, ActionEmittable> {
+        let mut actions = self.model_actions.clone();
+        actions.extend(self.actions.clone());
+        actions
+    }
+    /// Version of the current events and actions, starting at 1.
+    pub fn schema_version(&self) -> u32 {
+        self.schema_version
+    }
+    pub fn set_schema_version(&mut self, schema_version: u32) {
+        self.schema_version = schema_version;
+    }
+    /// Snapshot of the current events and actions.
+    pub fn schema(&self, created_at: &DateTime<Utc>) -> DeviceSchema {
+        DeviceSchema::new(self.schema_version, created_at, self.effective_events(), self.effective_actions())
+    }
+    /// Serialized `FileDescriptorSet` holding the message types of protobuf emittables.
+    pub fn descriptor_set(&self) -> Option<&Vec<u8>> {
+        self.descriptor_set.as_ref()
+    }
+    pub fn set_descriptor_set(&mut self, descriptor_set: Option<Vec<u8>>) {
+        self.descriptor_set = descriptor_set;
+    }
+    /// Checks the computed metrics of every event, model events included.
+    pub fn validate_computed_fields(&self) -> Result<(), String> {
+        for (name, event) in self.effective_events() {
+            event
+                .validate_computed()
+                .map_err(|e| format!("Event '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
+    /// Attaches message descriptors to protobuf emittables, to be called once events, actions
+    /// and descriptor set are all set. The descriptor set is only parsed when a protobuf format is used.
+    pub fn resolve_protobuf_messages(&mut self) -> Result<(), String> {
+        let messages: Vec<_> = self
+            .events
+            .values_mut()
+            .chain(self.model_events.values_mut())
+            .filter_map(|e| match e.format_mut() {
+                EventFormat::Protobuf(message) => Some(message),
+                _ => None,
+            })
+            .chain(self.actions.values_mut().chain(self.model_actions.values_mut()).filter_map(|a| match a.format_mut() {
+                ActionFormat::Protobuf(message) => Some(message),
+                _ => None,
+            }))
+            .collect();
+        if messages.is_empty() {
+            return Ok(());
+        }
+        let pool = match &self.descriptor_set {
+            Some(descriptor_set) => protobuf::parse_descriptor_set(descriptor_set)?,
+            None => return Err(format!("No descriptor set registered for message {}", messages[0].name())),
+        };
+        for message in messages {
+            message.resolve(&pool)?;
+        }
+        Ok(())
+    }
 
 }
@@ -0,0 +1,38 @@
+use std::collections::{BTreeSet, HashMap};
+
+#[cfg(feature = "in_memory")]
+use crate::domain::device::Device;
+
+/// Selects devices having all the given labels and tags, an empty filter matches every device.
+#[derive(Debug, Clone, Default)]
+pub struct DeviceFilter {
+    labels: HashMap<String, String>,
+    tags: BTreeSet<String>,
+}
+
+impl DeviceFilter {
+    #[cfg(any(feature = "axum", feature = "egui"))]
+    pub fn new(labels: HashMap<String, String>, tags: BTreeSet<String>) -> Self {
+        Self { labels, tags }
+    }
+    // read by the repositories filtering on their side, in-memory ones use `matches`
+    #[cfg(any(feature = "postgres", feature = "reqwest"))]
+    pub fn labels(&self) -> &HashMap<String, String> {
+        &self.labels
+    }
+    #[cfg(any(feature = "postgres", feature = "reqwest"))]
+    pub fn tags(&self) -> &BTreeSet<String> {
+        &self.tags
+    }
+    #[cfg(any(feature = "axum", feature = "egui"))]
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty() && self.tags.is_empty()
+    }
+    #[cfg(feature = "in_memory")]
+    pub fn matches(&self, device: &Device) -> bool {
+        self.labels
+            .iter()
+            .all(|(key, value)| device.labels().get(key) == Some(value))
+            && self.tags.is_subset(device.tags())
+    }
+}
@@ -0,0 +1,51 @@
+use std::collections::BTreeSet;
+
+use uuid::Uuid;
+
+/// Named set of devices. Groups nest through their parent to form a tree, e.g. site → building → floor.
+#[derive(Debug, Clone)]
+pub struct DeviceGroup {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    parent_id: Option<Uuid>,
+    device_ids: BTreeSet<Uuid>,
+}
+
+impl DeviceGroup {
+    pub fn new(id: &Uuid, user_id: &Uuid, name: &str, parent_id: Option<Uuid>) -> Self {
+        Self { id: *id, user_id: *user_id, name: name.to_string(), parent_id, device_ids: BTreeSet::new() }
+    }
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+    /// Group this group is nested in, `None` for a root group.
+    pub fn parent_id(&self) -> Option<&Uuid> {
+        self.parent_id.as_ref()
+    }
+    pub fn set_parent_id(&mut self, parent_id: Option<Uuid>) {
+        self.parent_id = parent_id;
+    }
+    /// Devices directly in the group, devices of sub-groups are not included.
+    pub fn device_ids(&self) -> &BTreeSet<Uuid> {
+        &self.device_ids
+    }
+    pub fn set_device_ids(&mut self, device_ids: BTreeSet<Uuid>) {
+        self.device_ids = device_ids;
+    }
+    pub fn add_device(&mut self, device_id: Uuid) {
+        self.device_ids.insert(device_id);
+    }
+    pub fn remove_device(&mut self, device_id: &Uuid) -> bool {
+        self.device_ids.remove(device_id)
+    }
+}
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
+use crate::domain::{action::action_emittable::ActionEmittable, event::event_emittable::EventEmittable};
+
+/// Events and actions shared by devices of the same kind.
+/// Devices reference a model by ID, their own events and actions override those of the model.
+#[derive(Debug, Clone)]
+pub struct DeviceModel {
+    id: Uuid,
+    user_id: Uuid,
+    name: String,
+    events: HashMap<String, EventEmittable>,
+    actions: HashMap<String, ActionEmittable>,
+}
+
+impl DeviceModel {
+    pub fn new(id: &Uuid, user_id: &Uuid, name: &str, events: HashMap<String, EventEmittable>, actions: HashMap<String, ActionEmittable>) -> Self {
+        Self { id: *id, user_id: *user_id, name: name.to_string(), events, actions }
+    }
+    pub fn id(&self) -> &Uuid {
+        &self.id
+    }
+    pub fn user_id(&self) -> &Uuid {
+        &self.user_id
+    }
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    pub fn set_name(&mut self, name: &str) {
+        self.name = name.to_string();
+    }
+    pub fn events(&self) -> &HashMap<String, EventEmittable> {
+        &self.events
+    }
+    pub fn set_events(&mut self, events: HashMap<String, EventEmittable>) {
+        self.events = events;
+    }
+    pub fn actions(&self) -> &HashMap<String, ActionEmittable> {
+        &self.actions
+    }
+    pub fn set_actions(&mut self, actions: HashMap<String, ActionEmittable>) {
+        self.actions = actions;
+    }
+}
@@ -0,0 +1,185 @@
+use std::{collections::HashMap, fmt::Display};
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::domain::{
+    action::action_emittable::ActionEmittable, event::event_emittable::EventEmittable,
+    field_constraints::FieldConstraints,
+};
+
+/// Events and actions of a device as they were at a given version.
+/// A new version is created each time the emittables of the device change.
+#[derive(Debug, Clone)]
+pub struct DeviceSchema {
+    version: u32,
+    created_at: DateTime<Utc>,
+    events: HashMap<String, EventEmittable>,
+    actions: HashMap<String, ActionEmittable>,
+}
+
+impl DeviceSchema {
+    pub fn new(
+        version: u32,
+        created_at: &DateTime<Utc>,
+        events: HashMap<String, EventEmittable>,
+        actions: HashMap<String, ActionEmittable>,
+    ) -> Self {
+        Self { version, created_at: *created_at, events, actions }
+    }
+    pub fn version(&self) -> u32 {
+        self.version
+    }
+    pub fn created_at(&self) -> &DateTime<Utc> {
+        &self.created_at
+    }
+    pub fn events(&self) -> &HashMap<String, EventEmittable> {
+        &self.events
+    }
+    pub fn actions(&self) -> &HashMap<String, ActionEmittable> {
+        &self.actions
+    }
+
+    /// Changes needed to go from this version to `other`.
+    pub fn diff(&self, other: &DeviceSchema) -> SchemaDiff {
+        let (added_events, removed_events, changed_events) = diff_emittables(
+            &self.events,
+            &other.events,
+            |e| EmittableParts {
+                format: e.format().to_string(),
+                payload: e.payload(),
+                constraints: e.constraints(),
+                settings: vec![
+                    ("binary_layout", serde_json::to_value(e.format().binary_layout()).ok()),
+                    ("unknown_keys", serde_json::to_value(e.unknown_keys()).ok()),
+                    ("timestamp_field", serde_json::to_value(e.timestamp_field()).ok()),
+                    ("computed", serde_json::to_value(e.computed()).ok()),
+                ],
+            },
+        );
+        let (added_actions, removed_actions, changed_actions) = diff_emittables(
+            &self.actions,
+            &other.actions,
+            |a| EmittableParts {
+                format: a.format().to_string(),
+                payload: a.payload(),
+                constraints: a.constraints(),
+                settings: vec![
+                    ("binary_layout", serde_json::to_value(a.format().binary_layout()).ok()),
+                    ("unknown_keys", serde_json::to_value(a.unknown_keys()).ok()),
+                ],
+            },
+        );
+        SchemaDiff {
+            from: self.version,
+            to: other.version,
+            added_events,
+            removed_events,
+            changed_events,
+            added_actions,
+            removed_actions,
+            changed_actions,
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SchemaDiff {
+    pub from: u32,
+    pub to: u32,
+    pub added_events: Vec<String>,
+    pub removed_events: Vec<String>,
+    pub changed_events: Vec<EmittableDiff>,
+    pub added_actions: Vec<String>,
+    pub removed_actions: Vec<String>,
+    pub changed_actions: Vec<EmittableDiff>,
+}
+
+impl SchemaDiff {
+    /// Whether both versions accept the same events and actions.
+    pub fn is_empty(&self) -> bool {
+        self.added_events.is_empty()
+            && self.removed_events.is_empty()
+            && self.changed_events.is_empty()
+            && self.added_actions.is_empty()
+            && self.removed_actions.is_empty()
+            && self.changed_actions.is_empty()
+    }
+}
+
+/// Changes of an event or action present in both versions.
+#[derive(Debug, Serialize)]
+pub struct EmittableDiff {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub format: Option<(String, String)>,
+    pub added_keys: Vec<String>,
+    pub removed_keys: Vec<String>,
+    /// Keys whose data type changed, with the old and new type.
+    pub retyped_keys: HashMap<String, (String, String)>,
+    pub constraints_changed: bool,
+    /// Other settings that changed, e.g. `binary_layout` or `timestamp_field`.
+    pub changed_settings: Vec<String>,
+}
+
+/// What is compared between two versions of an emittable.
+struct EmittableParts<'a, T> {
+    format: String,
+    payload: &'a HashMap<String, T>,
+    constraints: &'a HashMap<String, FieldConstraints>,
+    /// Settings compared in their serialized form, by name.
+    settings: Vec<(&'static str, Option<Value>)>,
+}
+
+fn diff_emittables<E, T: PartialEq + Display>(
+    from: &HashMap<String, E>,
+    to: &HashMap<String, E>,
+    parts: impl for<'a> Fn(&'a E) -> EmittableParts<'a, T>,
+) -> (Vec<String>, Vec<String>, Vec<EmittableDiff>) {
+    let mut added: Vec<String> = to.keys().filter(|k| !from.contains_key(*k)).cloned().collect();
+    let mut removed: Vec<String> = from.keys().filter(|k| !to.contains_key(*k)).cloned().collect();
+    let mut changed = Vec::new();
+    for (name, old) in from.iter() {
+        let Some(new) = to.get(name) else {
+            continue;
+        };
+        let EmittableParts { format: old_format, payload: old_payload, constraints: old_constraints, settings: old_settings } = parts(old);
+        let EmittableParts { format: new_format, payload: new_payload, constraints: new_constraints, settings: new_settings } = parts(new);
+        let mut added_keys: Vec<String> = new_payload.keys().filter(|k| !old_payload.contains_key(*k)).cloned().collect();
+        let mut removed_keys: Vec<String> = old_payload.keys().filter(|k| !new_payload.contains_key(*k)).cloned().collect();
+        added_keys.sort();
+        removed_keys.sort();
+        let retyped_keys: HashMap<String, (String, String)> = old_payload
+            .iter()
+            .filter_map(|(k, old_type)| match new_payload.get(k) {
+                Some(new_type) if new_type != old_type => Some((k.clone(), (old_type.to_string(), new_type.to_string()))),
+                _ => None,
+            })
+            .collect();
+        // constraints hold regexes, they are compared in their serialized form
+        let constraints_changed = serde_json::to_value(old_constraints).ok() != serde_json::to_value(new_constraints).ok();
+        let changed_settings: Vec<String> = old_settings
+            .into_iter()
+            .zip(new_settings)
+            .filter(|((_, old_setting), (_, new_setting))| old_setting != new_setting)
+            .map(|((name, _), _)| name.to_string())
+            .collect();
+        let format = (old_format != new_format).then_some((old_format, new_format));
+        if format.is_some() || !added_keys.is_empty() || !removed_keys.is_empty() || !retyped_keys.is_empty() || constraints_changed || !changed_settings.is_empty() {
+            changed.push(EmittableDiff {
+                name: name.clone(),
+                format,
+                added_keys,
+                removed_keys,
+                retyped_keys,
+                constraints_changed,
+                changed_settings,
+            });
+        }
+    }
+    added.sort();
+    removed.sort();
+    changed.sort_by(|a, b| a.name.cmp(&b.name));
+    (added, removed, changed)
+}
@@ -0,0 +1,58 @@
+use std::collections::{BTreeSet, HashMap};
+
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+
+use crate::domain::{
+    action::{action::Action, action_data_value::ActionDataValue},
+    device::Device,
+    event::event_data_value::EventDataValue,
+    state::DeviceState,
+};
+
+/// Checks every desired key can be set by an action of the device.
+pub fn check_desired(device: &Device, desired: &HashMap<String, EventDataValue>) -> Result<(), String> {
+    let actions = device.effective_actions();
+    for key in desired.keys() {
+        if !actions.values().any(|action| action.payload().contains_key(key)) {
+            return Err(format!("No action of the device sets '{}'", key));
+        }
+    }
+    Ok(())
+}
+
+/// Actions setting the desired values the device does not report yet. A key is set by the
+/// first action, by name, declaring it; the other keys of the action take their desired value,
+/// or the reported one.
+pub fn converging_actions(device: &Device, state: &DeviceState, timestamp: &DateTime<Utc>) -> Result<Vec<Action>, String> {
+    let delta = state.delta();
+    let mut remaining: BTreeSet<&String> = delta.keys().collect();
+    let mut emittables: Vec<_> = device.effective_actions().into_iter().collect();
+    emittables.sort_by(|(a, _), (b, _)| a.cmp(b));
+    let mut actions = Vec::new();
+    for (name, emittable) in emittables {
+        if !emittable.payload().keys().any(|key| remaining.contains(key)) {
+            continue;
+        }
+        let mut payload = HashMap::new();
+        for key in emittable.payload().keys() {
+            let value = state
+                .desired
+                .get(key)
+                .or_else(|| state.values.get(key).map(|reported| &reported.value));
+            if let Some(value) = value {
+                let value = ActionDataValue::try_from(Value::from(value.clone()))
+                    .map_err(|_| format!("Value of '{}' cannot be sent with action '{}'", key, name))?;
+                payload.insert(key.clone(), value);
+            }
+            remaining.remove(key);
+        }
+        let action = Action::from_values(device, timestamp, &name, payload)
+            .map_err(|e| format!("Action '{}': {}", name, e))?;
+        actions.push(action);
+    }
+    if let Some(key) = remaining.first() {
+        return Err(format!("No action of the device sets '{}'", key));
+    }
+    Ok(actions)
+}
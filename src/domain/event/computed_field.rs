@@ -0,0 +1,365 @@
+use std::{collections::HashMap, fmt::Display};
+
+use serde::{Deserialize, Serialize};
+
+use crate::domain::event::{event_data_type::EventDataType, event_data_value::EventDataValue};
+
+const FUNCTIONS: [(&str, usize); 11] = [
+    ("abs", 1),
+    ("sqrt", 1),
+    ("ln", 1),
+    ("log10", 1),
+    ("exp", 1),
+    ("round", 1),
+    ("floor", 1),
+    ("ceil", 1),
+    ("min", 2),
+    ("max", 2),
+    ("pow", 2),
+];
+
+/// Parentheses, function calls and minus signs an expression may nest, deeper expressions
+/// would exhaust the stack when parsed or evaluated.
+const MAX_NESTING: usize = 32;
+/// Tokens of an expression, operator chains nest their operands as deep as they are long.
+const MAX_TOKENS: usize = 512;
+
+/// Arithmetic expression computing a virtual metric from the payload of an event,
+/// e.g. `voltage * current`. Fields are referenced by name, nested fields as `gps.lat`.
+/// Supports `+ - * / % ^`, parentheses and the functions abs, sqrt, ln, log10, exp,
+/// round, floor, ceil, min, max and pow.
+#[derive(Debug, Clone)]
+pub struct Expression {
+    source: String,
+    root: Node,
+}
+
+#[derive(Debug, Clone)]
+enum Node {
+    Number(f64),
+    Field(String),
+    Neg(Box<Node>),
+    Binary(char, Box<Node>, Box<Node>),
+    Call(String, Vec<Node>),
+}
+
+impl Expression {
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let tokens = tokenize(source)?;
+        if tokens.len() > MAX_TOKENS {
+            return Err(format!("Expression longer than {} tokens", MAX_TOKENS));
+        }
+        let mut parser = Parser { tokens, pos: 0, depth: 0 };
+        let root = parser.expr()?;
+        if let Some(token) = parser.peek() {
+            return Err(format!("Unexpected {} in expression '{}'", token, source));
+        }
+        Ok(Self { source: source.trim().to_string(), root })
+    }
+
+    /// Payload fields the expression reads.
+    pub fn fields(&self) -> Vec<&str> {
+        let mut fields = Vec::new();
+        self.root.fields(&mut fields);
+        fields
+    }
+
+    /// Value of the expression, none if a field is missing or not numeric,
+    /// or if the result is not a finite number.
+    pub fn evaluate(&self, payload: &HashMap<String, EventDataValue>) -> Option<f64> {
+        self.root.evaluate(payload).filter(|v| v.is_finite())
+    }
+
+    /// Checks the fields read are numeric fields of the payload.
+    pub fn validate(&self, payload: &HashMap<String, EventDataType>) -> Result<(), String> {
+        for field in self.fields() {
+            let mut parts = field.split('.');
+            let mut data_type = parts.next().and_then(|key| payload.get(key));
+            for part in parts {
+                data_type = match data_type {
+                    Some(EventDataType::Object(fields)) => fields.get(part),
+                    _ => None,
+                };
+            }
+            match data_type {
+                Some(EventDataType::Integer | EventDataType::Float | EventDataType::Number) => {}
+                Some(other) => return Err(format!("Field '{}' is {}, a number is expected", field, other)),
+                None => return Err(format!("Field '{}' is not declared in the payload", field)),
+            }
+        }
+        Ok(())
+    }
+}
+
+impl PartialEq for Expression {
+    fn eq(&self, other: &Self) -> bool {
+        self.source == other.source
+    }
+}
+
+impl Display for Expression {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.source)
+    }
+}
+
+impl Serialize for Expression {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.source)
+    }
+}
+
+impl<'de> Deserialize<'de> for Expression {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Expression::parse(&s).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Node {
+    fn fields<'a>(&'a self, fields: &mut Vec<&'a str>) {
+        match self {
+            Node::Number(_) => {}
+            Node::Field(name) => fields.push(name),
+            Node::Neg(inner) => inner.fields(fields),
+            Node::Binary(_, left, right) => {
+                left.fields(fields);
+                right.fields(fields);
+            }
+            Node::Call(_, args) => args.iter().for_each(|arg| arg.fields(fields)),
+        }
+    }
+
+    fn evaluate(&self, payload: &HashMap<String, EventDataValue>) -> Option<f64> {
+        match self {
+            Node::Number(n) => Some(*n),
+            Node::Field(name) => field_value(payload, name),
+            Node::Neg(inner) => Some(-inner.evaluate(payload)?),
+            Node::Binary(op, left, right) => {
+                let (l, r) = (left.evaluate(payload)?, right.evaluate(payload)?);
+                Some(match op {
+                    '+' => l + r,
+                    '-' => l - r,
+                    '*' => l * r,
+                    '/' => l / r,
+                    '%' => l % r,
+                    _ => l.powf(r),
+                })
+            }
+            Node::Call(name, args) => {
+                let args = args.iter().map(|arg| arg.evaluate(payload)).collect::<Option<Vec<f64>>>()?;
+                Some(match (name.as_str(), args.as_slice()) {
+                    ("abs", [x]) => x.abs(),
+                    ("sqrt", [x]) => x.sqrt(),
+                    ("ln", [x]) => x.ln(),
+                    ("log10", [x]) => x.log10(),
+                    ("exp", [x]) => x.exp(),
+                    ("round", [x]) => x.round(),
+                    ("floor", [x]) => x.floor(),
+                    ("ceil", [x]) => x.ceil(),
+                    ("min", [x, y]) => x.min(*y),
+                    ("max", [x, y]) => x.max(*y),
+                    ("pow", [x, y]) => x.powf(*y),
+                    _ => return None,
+                })
+            }
+        }
+    }
+}
+
+fn field_value(payload: &HashMap<String, EventDataValue>, path: &str) -> Option<f64> {
+    let mut parts = path.split('.');
+    let mut value = payload.get(parts.next()?)?;
+    for part in parts {
+        value = match value {
+            EventDataValue::Object(fields) => fields.get(part)?,
+            _ => return None,
+        };
+    }
+    match value {
+        EventDataValue::Integer(n) => Some(*n as f64),
+        EventDataValue::Float(n) => Some(*n),
+        _ => None,
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Ident(String),
+    Op(char),
+    LParen,
+    RParen,
+    Comma,
+}
+
+impl Display for Token {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Token::Number(n) => write!(f, "'{}'", n),
+            Token::Ident(name) => write!(f, "'{}'", name),
+            Token::Op(op) => write!(f, "'{}'", op),
+            Token::LParen => write!(f, "'('"),
+            Token::RParen => write!(f, "')'"),
+            Token::Comma => write!(f, "','"),
+        }
+    }
+}
+
+fn tokenize(source: &str) -> Result<Vec<Token>, String> {
+    let chars: Vec<char> = source.chars().collect();
+    let mut tokens = Vec::new();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            c if c.is_whitespace() => i += 1,
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                tokens.push(Token::Op(c));
+                i += 1;
+            }
+            '(' => {
+                tokens.push(Token::LParen);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::RParen);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            c if c.is_ascii_digit() || c == '.' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_ascii_digit() || chars[i] == '.') {
+                    i += 1;
+                }
+                let raw: String = chars[start..i].iter().collect();
+                let n = raw.parse::<f64>().map_err(|_| format!("Invalid number '{}'", raw))?;
+                tokens.push(Token::Number(n));
+            }
+            c if c.is_alphabetic() || c == '_' => {
+                let start = i;
+                while i < chars.len() && (chars[i].is_alphanumeric() || chars[i] == '_' || chars[i] == '.') {
+                    i += 1;
+                }
+                tokens.push(Token::Ident(chars[start..i].iter().collect()));
+            }
+            c => return Err(format!("Unexpected character '{}' in expression", c)),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    /// Nesting of the unary being parsed.
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.pos).cloned();
+        self.pos += 1;
+        token
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), String> {
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            Some(token) => Err(format!("Expected {} but found {}", expected, token)),
+            None => Err(format!("Expected {} at the end of the expression", expected)),
+        }
+    }
+
+    // expr := term (('+' | '-') term)*
+    fn expr(&mut self) -> Result<Node, String> {
+        let mut node = self.term()?;
+        while let Some(Token::Op(op @ ('+' | '-'))) = self.peek().cloned() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.term()?));
+        }
+        Ok(node)
+    }
+
+    // term := unary (('*' | '/' | '%') unary)*
+    fn term(&mut self) -> Result<Node, String> {
+        let mut node = self.unary()?;
+        while let Some(Token::Op(op @ ('*' | '/' | '%'))) = self.peek().cloned() {
+            self.pos += 1;
+            node = Node::Binary(op, Box::new(node), Box::new(self.unary()?));
+        }
+        Ok(node)
+    }
+
+    // unary := '-' unary | power
+    // every recursion of the grammar goes through unary, its nesting bounds the parser's
+    fn unary(&mut self) -> Result<Node, String> {
+        if self.depth == MAX_NESTING {
+            return Err(format!("Expression nested deeper than {} levels", MAX_NESTING));
+        }
+        self.depth += 1;
+        let node = if let Some(Token::Op('-')) = self.peek() {
+            self.pos += 1;
+            self.unary().map(|node| Node::Neg(Box::new(node)))
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        node
+    }
+
+    // power := primary ('^' unary)?, right associative
+    fn power(&mut self) -> Result<Node, String> {
+        let base = self.primary()?;
+        if let Some(Token::Op('^')) = self.peek() {
+            self.pos += 1;
+            return Ok(Node::Binary('^', Box::new(base), Box::new(self.unary()?)));
+        }
+        Ok(base)
+    }
+
+    fn primary(&mut self) -> Result<Node, String> {
+        match self.next() {
+            Some(Token::Number(n)) => Ok(Node::Number(n)),
+            Some(Token::Ident(name)) if self.peek() == Some(&Token::LParen) => {
+                self.pos += 1;
+                let arity = FUNCTIONS
+                    .iter()
+                    .find(|(f, _)| *f == name)
+                    .map(|(_, arity)| *arity)
+                    .ok_or_else(|| format!("Unknown function '{}'", name))?;
+                let mut args = vec![self.expr()?];
+                while self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                    args.push(self.expr()?);
+                }
+                self.expect(Token::RParen)?;
+                if args.len() != arity {
+                    return Err(format!("Function '{}' takes {} argument(s), {} given", name, arity, args.len()));
+                }
+                Ok(Node::Call(name, args))
+            }
+            Some(Token::Ident(name)) => Ok(Node::Field(name)),
+            Some(Token::LParen) => {
+                let node = self.expr()?;
+                self.expect(Token::RParen)?;
+                Ok(node)
+            }
+            Some(token) => Err(format!("Unexpected {} in expression", token)),
+            None => Err("Unexpected end of expression".to_string()),
+        }
+    }
+}
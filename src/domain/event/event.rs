@@ -1,25 +1,20 @@
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use std::{collections::HashMap, hash::Hash };
+use std::collections::HashMap;
 
-use crate::domain::{device::Device, event::{event_data_type::EventDataType, event_data_value::EventDataValue, event_format::EventFormatError}};
+use crate::domain::{device::Device, state::{StateValue, ValueSource}, event::{event_data_type::EventDataType, event_data_value::EventDataValue, event_format::EventFormatError}, field_constraints::{FieldConstraints, UnknownKeyPolicy}};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Event {
     pub id: Uuid,
     pub device_physical_id: String,
     pub event_name: String,
     pub timestamp: DateTime<Utc>,
     pub payload: HashMap<String, EventDataValue>,
+    /// Version of the device schema the event was validated against.
+    pub schema_version: Option<u32>,
 }
 
-impl Hash for Event {
-    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-        self.id.hash(state);
-        self.device_physical_id.hash(state);
-        self.timestamp.hash(state);
-    }
-}
 impl Event {
     pub fn new(device_physical_id: String, event_name: &str, timestamp: &DateTime<Utc>, payload: HashMap<String, EventDataValue>) -> Self{
         return Self {
@@ -28,34 +23,155 @@ impl Event {
             event_name: event_name.to_string(),
             timestamp: *timestamp,
             payload,
+            schema_version: None,
+        };
+    }
+    /// Values of the payload as state values coming from this event.
+    pub fn state_values(&self) -> HashMap<String, StateValue> {
+        let source = ValueSource {
+            timestamp: self.timestamp,
+            event_id: Some(self.id),
+            event_name: Some(self.event_name.clone()),
         };
+        self.payload
+            .iter()
+            .map(|(key, value)| (key.clone(), StateValue { value: value.clone(), source: source.clone() }))
+            .collect()
     }
     pub fn new_checked(device: &Device, timestamp: &DateTime<Utc>, event_name: &str, payload: &[u8]) -> Result<Self, EventFormatError> {
-        let event_concerned = match device.events().get(event_name) {
+        let event_concerned = match device.event(event_name) {
             Some(evt) => evt,
             None => return Err(EventFormatError::UnsupportedFormat(format!("Event '{event_name}' not found in device events")))
         };
-        let payload_received = event_concerned.format().decode_event(&payload)?;
+        let decoded = event_concerned.format().decode_event(payload, timestamp)?;
+        let mut payload_received = decoded.payload;
         println!("Received payload: {:?}", payload_received);
+        let timestamp = match event_concerned.timestamp_field() {
+            Some(field) => {
+                let device_timestamp = field.extract(&payload_received, timestamp);
+                // the field only carries the time, it is not part of the values unless declared
+                if !event_concerned.payload().contains_key(&field.key) {
+                    payload_received.remove(&field.key);
+                }
+                device_timestamp
+            }
+            None => None,
+        }
+        .or(decoded.timestamp)
+        .unwrap_or(*timestamp);
+        let unknown_keys: Vec<String> = payload_received
+            .keys()
+            .filter(|k| !event_concerned.payload().contains_key(*k))
+            .cloned()
+            .collect();
+        match event_concerned.unknown_keys() {
+            UnknownKeyPolicy::Accept => {}
+            UnknownKeyPolicy::Drop => payload_received.retain(|k, _| !unknown_keys.contains(k)),
+            UnknownKeyPolicy::Reject => {
+                if let Some(key) = unknown_keys.first() {
+                    return Err(EventFormatError::UnsupportedFormat(format!("Key '{}' is not declared for event '{}'", key, event_name)));
+                }
+            }
+        }
         // iterate over the device's event_data to ensure all keys in payload are valid
         for (key, data_type) in event_concerned.payload().clone().into_iter() {
-            let is_valid = match payload_received.get(&key) {
-                Some(EventDataValue::String(_)) => data_type == EventDataType::String,
-                Some(EventDataValue::Number(_)) => data_type == EventDataType::Number,
-                Some(EventDataValue::Boolean(_)) => data_type == EventDataType::Boolean,
-                None => return Err(EventFormatError::UnsupportedFormat(format!("Key '{}' not found in payload", key)))
+            let value = match payload_received.remove(&key) {
+                Some(value) => value,
+                None => match missing_value(&key, &key, event_concerned.constraints())? {
+                    Some(value) => value,
+                    None => continue,
+                }
+            };
+            let value = match value {
+                EventDataValue::String(raw) if event_concerned.format().is_untyped() => {
+                    EventDataValue::parse_event_data_type(data_type.clone(), &raw).map_err(|_| {
+                        EventFormatError::UnsupportedFormat(format!("Invalid value for key {}, {} expected", key, data_type))
+                    })?
+                }
+                value => value,
             };
-            if !is_valid {
-                return Err(EventFormatError::UnsupportedFormat(format!("Invalid value for key {}, {} expected", &key, &data_type.to_string())));
+            let value = check_value(&key, &key, &data_type, value, event_concerned.constraints())?;
+            payload_received.insert(key, value);
+        }
+        // computed metrics read the checked values, one that cannot be evaluated is left out
+        for (key, expression) in event_concerned.computed() {
+            match expression.evaluate(&payload_received) {
+                Some(value) => {
+                    payload_received.insert(key.clone(), EventDataValue::Float(value));
+                }
+                None => {
+                    payload_received.remove(key);
+                }
             }
         }
         return Ok(Self {
             id: Uuid::new_v4(),
             device_physical_id: device.physical_id().to_owned(),
             event_name: event_name.to_string(),
-            timestamp: *timestamp,
+            timestamp,
             payload: payload_received,
+            schema_version: Some(device.schema_version()),
         });
     }
 }
 
+/// Checks a value against its declared type and constraints, nested values are checked recursively.
+/// `path` locates the value in the payload (`samples[2]`) while `schema_path` is the key
+/// constraints are declared with (`samples`). Integers sent for a float field are returned as floats.
+fn check_value(path: &str, schema_path: &str, data_type: &EventDataType, value: EventDataValue, constraints: &HashMap<String, FieldConstraints>) -> Result<EventDataValue, EventFormatError> {
+    let invalid = || EventFormatError::UnsupportedFormat(format!("Invalid value for key {}, {} expected", path, data_type));
+    let value = match (data_type, value) {
+        (EventDataType::String, v @ EventDataValue::String(_)) => Ok(v),
+        (EventDataType::Boolean, v @ EventDataValue::Boolean(_)) => Ok(v),
+        (EventDataType::Integer, v @ EventDataValue::Integer(_)) => Ok(v),
+        (EventDataType::Number, v @ (EventDataValue::Integer(_) | EventDataValue::Float(_))) => Ok(v),
+        (EventDataType::Float, EventDataValue::Integer(n)) => Ok(EventDataValue::Float(n as f64)),
+        (EventDataType::Float, v @ EventDataValue::Float(_)) => Ok(v),
+        (EventDataType::Array(item_type), EventDataValue::Array(items)) => {
+            let mut checked = Vec::with_capacity(items.len());
+            for (i, item) in items.into_iter().enumerate() {
+                checked.push(check_value(&format!("{}[{}]", path, i), schema_path, item_type, item, constraints)?);
+            }
+            Ok(EventDataValue::Array(checked))
+        }
+        (EventDataType::Object(field_types), EventDataValue::Object(mut fields)) => {
+            for (field, field_type) in field_types {
+                let field_path = format!("{}.{}", path, field);
+                let field_schema_path = format!("{}.{}", schema_path, field);
+                let field_value = match fields.remove(field) {
+                    Some(v) => v,
+                    None => match missing_value(&field_path, &field_schema_path, constraints)? {
+                        Some(v) => v,
+                        None => continue,
+                    }
+                };
+                fields.insert(field.clone(), check_value(&field_path, &field_schema_path, field_type, field_value, constraints)?);
+            }
+            Ok(EventDataValue::Object(fields))
+        }
+        _ => Err(invalid()),
+    }?;
+    if let Some(field_constraints) = constraints.get(schema_path) {
+        // structured values are constrained through their items and fields
+        if !matches!(value, EventDataValue::Array(_) | EventDataValue::Object(_)) {
+            field_constraints
+                .check(path, &value.clone().into())
+                .map_err(EventFormatError::ConstraintViolation)?;
+        }
+    }
+    Ok(value)
+}
+
+/// Resolves a field missing from the payload: its default value, nothing if it is optional,
+/// or an error if it is required.
+fn missing_value(path: &str, schema_path: &str, constraints: &HashMap<String, FieldConstraints>) -> Result<Option<EventDataValue>, EventFormatError> {
+    match constraints.get(schema_path) {
+        Some(field_constraints) if !field_constraints.is_required() => match &field_constraints.default {
+            Some(default) => EventDataValue::try_from(default.clone())
+                .map(Some)
+                .map_err(|_| EventFormatError::UnsupportedFormat(format!("Invalid default value for key {}", path))),
+            None => Ok(None),
+        },
+        _ => Err(EventFormatError::UnsupportedFormat(format!("Key '{}' not found in payload", path))),
+    }
+}
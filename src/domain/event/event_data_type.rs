@@ -1,4 +1,4 @@
-use std::fmt::Display;
+use std::{collections::HashMap, fmt::Display};
 
 use serde::{Deserialize, Serialize};
 
@@ -7,26 +7,87 @@ use serde::{Deserialize, Serialize};
 pub enum EventDataType {
     String,
     Number,
+    Integer,
+    Float,
     Boolean,
+    Array(Box<EventDataType>),
+    Object(HashMap<String, EventDataType>),
 }
 
 impl EventDataType {
+    /// Parses a data type, structured types are written as `array<float>`
+    /// or `object{lat:float,lon:float}` and can be nested.
     pub fn from_str(s: &str) -> Result<Self, String> {
-        match s.to_lowercase().as_str() {
+        let trimmed = s.trim();
+        let lowered = trimmed.to_lowercase();
+        if lowered.starts_with("array<") && lowered.ends_with('>') {
+            let inner = &trimmed["array<".len()..trimmed.len() - 1];
+            return Ok(EventDataType::Array(Box::new(EventDataType::from_str(inner)?)));
+        }
+        if lowered.starts_with("object{") && lowered.ends_with('}') {
+            let inner = &trimmed["object{".len()..trimmed.len() - 1];
+            let mut fields = HashMap::new();
+            for field in split_top_level(inner) {
+                if field.trim().is_empty() {
+                    continue;
+                }
+                let (key, data_type) = field
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid object field: {}", field))?;
+                fields.insert(key.trim().to_string(), EventDataType::from_str(data_type)?);
+            }
+            return Ok(EventDataType::Object(fields));
+        }
+        match lowered.as_str() {
             "string" => Ok(EventDataType::String),
             "number" => Ok(EventDataType::Number),
+            "integer" => Ok(EventDataType::Integer),
+            "float" => Ok(EventDataType::Float),
             "boolean" => Ok(EventDataType::Boolean),
             _ => Err(format!("Unsupported event data type: {}", s)),
         }
     }
 }
 
+/// Splits on commas that are not nested inside `<>` or `{}`.
+fn split_top_level(s: &str) -> Vec<&str> {
+    let mut parts = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in s.char_indices() {
+        match c {
+            '<' | '{' => depth += 1,
+            '>' | '}' => depth -= 1,
+            ',' if depth == 0 => {
+                parts.push(&s[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    parts.push(&s[start..]);
+    parts
+}
+
 impl Display for EventDataType {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             EventDataType::String => write!(f, "string"),
             EventDataType::Number => write!(f, "number"),
+            EventDataType::Integer => write!(f, "integer"),
+            EventDataType::Float => write!(f, "float"),
             EventDataType::Boolean => write!(f, "boolean"),
+            EventDataType::Array(inner) => write!(f, "array<{}>", inner),
+            EventDataType::Object(fields) => {
+                // keys are sorted so the representation is stable
+                let mut keys: Vec<&String> = fields.keys().collect();
+                keys.sort();
+                let fields: Vec<String> = keys
+                    .into_iter()
+                    .map(|k| format!("{}:{}", k, fields[k]))
+                    .collect();
+                write!(f, "object{{{}}}", fields.join(","))
+            }
         }
     }
 }
@@ -1,22 +1,37 @@
+use std::collections::HashMap;
 
 use serde_json::Value;
 
 use crate::domain::event::event_data_type::EventDataType;
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum EventDataValue {
     String(String),
-    Number(u64),
+    Integer(i64),
+    Float(f64),
     Boolean(bool),
+    Array(Vec<EventDataValue>),
+    Object(HashMap<String, EventDataValue>),
 }
 
 impl EventDataValue {
     pub fn parse_event_data_type(data_type: EventDataType, value: &str) -> Result<EventDataValue, EventDataValueError> {
         match data_type {
             EventDataType::String => Ok(EventDataValue::String(value.to_string())),
+            EventDataType::Integer => {
+                let num = value.parse::<i64>().map_err(|_| EventDataValueError::InvalidNumber(value.to_owned()))?;
+                Ok(EventDataValue::Integer(num))
+            }
+            EventDataType::Float => {
+                let num = parse_finite_float(value).ok_or_else(|| EventDataValueError::InvalidNumber(value.to_owned()))?;
+                Ok(EventDataValue::Float(num))
+            }
             EventDataType::Number => {
-                let num = value.parse::<u64>().map_err(|_| EventDataValueError::InvalidNumber(value.to_owned()))?;
-                Ok(EventDataValue::Number(num))
+                if let Ok(num) = value.parse::<i64>() {
+                    return Ok(EventDataValue::Integer(num));
+                }
+                let num = parse_finite_float(value).ok_or_else(|| EventDataValueError::InvalidNumber(value.to_owned()))?;
+                Ok(EventDataValue::Float(num))
             }
             EventDataType::Boolean => {
                 match value.to_lowercase().as_str() {
@@ -25,16 +40,32 @@ impl EventDataValue {
                     _ => Err(EventDataValueError::InvalidBoolean(value.to_owned())),
                 }
             }
+            EventDataType::Array(_) | EventDataType::Object(_) => {
+                // structured values are expected as JSON text
+                let raw: Value = serde_json::from_str(value).map_err(|_| EventDataValueError::InvalidType)?;
+                match (&data_type, EventDataValue::try_from(raw)?) {
+                    (EventDataType::Array(_), v @ EventDataValue::Array(_)) => Ok(v),
+                    (EventDataType::Object(_), v @ EventDataValue::Object(_)) => Ok(v),
+                    _ => Err(EventDataValueError::InvalidType),
+                }
+            }
         }
     }
 }
 
+fn parse_finite_float(value: &str) -> Option<f64> {
+    value.parse::<f64>().ok().filter(|f| f.is_finite())
+}
+
 impl From<EventDataValue> for Value {
     fn from(value: EventDataValue) -> Self {
         match value {
             EventDataValue::String(s) => Value::from(s.to_owned()),
-            EventDataValue::Number(n) => Value::from(n.to_owned()),
+            EventDataValue::Integer(n) => Value::from(n.to_owned()),
+            EventDataValue::Float(n) => Value::from(n.to_owned()),
             EventDataValue::Boolean(b) => Value::from(b.to_owned()),
+            EventDataValue::Array(items) => Value::Array(items.into_iter().map(Value::from).collect()),
+            EventDataValue::Object(fields) => Value::Object(fields.into_iter().map(|(k, v)| (k, v.into())).collect()),
         }
     }
 }
@@ -44,8 +75,28 @@ impl TryFrom<Value> for EventDataValue {
     fn try_from(value: Value) -> Result<Self, Self::Error> {
         match value {
             Value::Bool(b) => Ok(EventDataValue::Boolean(b)),
-            Value::Number(n) => Ok(EventDataValue::Number(n.as_u64().expect("Should be a valid u64"))),
+            Value::Number(n) => {
+                if let Some(i) = n.as_i64() {
+                    return Ok(EventDataValue::Integer(i));
+                }
+                // u64 values above i64::MAX and fractional values end up here
+                match n.as_f64() {
+                    Some(f) => Ok(EventDataValue::Float(f)),
+                    None => Err(EventDataValueError::InvalidNumber(n.to_string())),
+                }
+            }
             Value::String(s) => Ok(EventDataValue::String(s)),
+            Value::Array(items) => {
+                let items = items.into_iter().map(EventDataValue::try_from).collect::<Result<Vec<_>, _>>()?;
+                Ok(EventDataValue::Array(items))
+            }
+            Value::Object(fields) => {
+                let mut values = HashMap::new();
+                for (k, v) in fields {
+                    values.insert(k, EventDataValue::try_from(v)?);
+                }
+                Ok(EventDataValue::Object(values))
+            }
             _ => return Err(EventDataValueError::InvalidType)
         }
     }
@@ -58,9 +109,12 @@ impl TryFrom<&str> for EventDataValue {
         if value.is_empty() {
             return Err(EventDataValueError::InvalidType);
         }
-        // Attempt to parse as a number first
-        if let Ok(num) = value.parse::<u64>() {
-            return Ok(EventDataValue::Number(num));
+        // Attempt to parse as an integer first, then as a float
+        if let Ok(num) = value.parse::<i64>() {
+            return Ok(EventDataValue::Integer(num));
+        }
+        if let Some(num) = parse_finite_float(value) {
+            return Ok(EventDataValue::Float(num));
         }
         // Attempt to parse as a boolean
         if let Ok(boolean) = value.parse::<bool>() {
@@ -1,21 +1,67 @@
 use std::{collections::HashMap};
 
-use crate::domain::event::{event_data_type::EventDataType, event_format::EventFormat};
+use crate::domain::{event::{computed_field::Expression, event_data_type::EventDataType, event_format::EventFormat, event_timestamp::TimestampField}, field_constraints::{FieldConstraints, UnknownKeyPolicy}};
 
 #[derive(Debug, Clone)]
 pub struct EventEmittable {
     format: EventFormat,
     payload: HashMap<String, EventDataType>,
+    constraints: HashMap<String, FieldConstraints>,
+    unknown_keys: UnknownKeyPolicy,
+    timestamp_field: Option<TimestampField>,
+    computed: HashMap<String, Expression>,
 }
 
 impl EventEmittable {
     pub fn new(format: EventFormat, payload: HashMap<String, EventDataType>) -> Self {
-        Self { format, payload }
+        Self { format, payload, constraints: HashMap::new(), unknown_keys: UnknownKeyPolicy::default(), timestamp_field: None, computed: HashMap::new() }
     }
     pub fn format(&self) -> &EventFormat {
         &self.format
     }
+    pub(crate) fn format_mut(&mut self) -> &mut EventFormat {
+        &mut self.format
+    }
     pub fn payload(&self) -> &HashMap<String, EventDataType> {
         &self.payload
     }
+    /// Constraints keyed by field path, nested fields are written as `gps.lat`.
+    pub fn constraints(&self) -> &HashMap<String, FieldConstraints> {
+        &self.constraints
+    }
+    pub fn set_constraints(&mut self, constraints: HashMap<String, FieldConstraints>) {
+        self.constraints = constraints;
+    }
+    pub fn unknown_keys(&self) -> &UnknownKeyPolicy {
+        &self.unknown_keys
+    }
+    pub fn set_unknown_keys(&mut self, unknown_keys: UnknownKeyPolicy) {
+        self.unknown_keys = unknown_keys;
+    }
+    /// Field giving the time of the event, the receive time is used without it.
+    pub fn timestamp_field(&self) -> Option<&TimestampField> {
+        self.timestamp_field.as_ref()
+    }
+    pub fn set_timestamp_field(&mut self, timestamp_field: Option<TimestampField>) {
+        self.timestamp_field = timestamp_field;
+    }
+    /// Virtual metrics added to the payload, keyed by the name they are stored under.
+    pub fn computed(&self) -> &HashMap<String, Expression> {
+        &self.computed
+    }
+    pub fn set_computed(&mut self, computed: HashMap<String, Expression>) {
+        self.computed = computed;
+    }
+    /// Checks computed metrics only read numeric payload fields and do not shadow them.
+    pub fn validate_computed(&self) -> Result<(), String> {
+        for (name, expression) in &self.computed {
+            if self.payload.contains_key(name) {
+                return Err(format!("Computed field '{}' is already a payload field", name));
+            }
+            expression
+                .validate(&self.payload)
+                .map_err(|e| format!("Invalid computed field '{}': {}", name, e))?;
+        }
+        Ok(())
+    }
 }
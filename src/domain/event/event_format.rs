@@ -1,62 +1,144 @@
 use std::{collections::HashMap, fmt::Display};
 
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 
-use crate::domain::event::event_data_value::EventDataValue;
+use crate::domain::{binary_layout::BinaryLayout, protobuf::ProtobufMessage, event::{event_data_value::EventDataValue, key_value::KeyValueSeparators, senml}};
 
 #[derive(Debug, Clone)]
 pub enum EventFormat {
     Json,
+    Cbor,
+    MessagePack,
+    /// Packed struct, the layout gives the position of each field.
+    Binary(BinaryLayout),
+    /// Protobuf message, written `protobuf:<message type>` in device definitions.
+    Protobuf(ProtobufMessage),
+    /// SenML JSON pack (RFC 8428), fields are the record names relative to the first base name.
+    SenML,
+    /// `key=value` pairs on a single line, values are typed with the device schema.
+    KeyValue(KeyValueSeparators),
+}
+
+/// Values of a payload and the time it carries, if any.
+pub struct DecodedEvent {
+    pub payload: HashMap<String, EventDataValue>,
+    pub timestamp: Option<DateTime<Utc>>,
 }
 
 impl EventFormat {
+    /// Binary formats can't be carried as is in text envelopes (MQTT messages, HTTP JSON bodies).
+    #[cfg(any(feature = "mqtt", feature = "reqwest"))]
+    pub fn is_binary(&self) -> bool {
+        match self {
+            EventFormat::Json | EventFormat::SenML | EventFormat::KeyValue(_) => false,
+            EventFormat::Cbor | EventFormat::MessagePack | EventFormat::Binary(_) | EventFormat::Protobuf(_) => true,
+        }
+    }
+    /// Builds a format from its name, the binary format requires a layout.
+    pub fn from_parts(format: &str, binary_layout: Option<BinaryLayout>) -> Result<Self, String> {
+        match (EventFormat::try_from(format)?, binary_layout) {
+            (EventFormat::Binary(_), Some(layout)) => Ok(EventFormat::Binary(layout)),
+            (EventFormat::Binary(_), None) => Err(format!("Missing binary layout for format {}", format)),
+            (format, _) => Ok(format),
+        }
+    }
+    pub fn binary_layout(&self) -> Option<&BinaryLayout> {
+        match self {
+            EventFormat::Binary(layout) => Some(layout),
+            _ => None,
+        }
+    }
+    /// Formats whose values carry no type, they are parsed with the declared data type.
+    pub fn is_untyped(&self) -> bool {
+        matches!(self, EventFormat::KeyValue(_))
+    }
+    /// Decodes the values of a payload along with the time it carries itself, for formats
+    /// that have one.
     pub fn decode_event(
         &self,
         payload: &[u8],
-    ) -> Result<HashMap<String, EventDataValue>, EventFormatError> {
-        match self {
+        received: &DateTime<Utc>,
+    ) -> Result<DecodedEvent, EventFormatError> {
+        let mut timestamp = None;
+        let event_raw: HashMap<String, Value> = match self {
             EventFormat::Json => {
-                // For simplicity, we assume the payload is a JSON string that can be parsed into a HashMap
-                // In a real application, you would use a JSON library to parse the payload
                 let json_str = String::from_utf8_lossy(payload);
-                let mut payload = HashMap::new();
-                let event_raw: HashMap<String, Value> = serde_json::from_str(&json_str)
-                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))?;
-                // iterate over the device's event_data to ensure all keys in payload are valid
-                for (key, value) in event_raw.into_iter() {
-                    let value = EventDataValue::try_from(value)
-                        .map_err(|_| EventFormatError::UnsupportedFormat(key.clone()))?;
-                    payload.insert(key, value);
+                serde_json::from_str(&json_str)
+                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))?
+            }
+            EventFormat::Cbor => ciborium::from_reader(payload)
+                .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))?,
+            EventFormat::MessagePack => rmp_serde::from_slice(payload)
+                .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))?,
+            EventFormat::Binary(layout) => layout
+                .decode(payload)
+                .map_err(EventFormatError::UnsupportedFormat)?,
+            EventFormat::Protobuf(message) => message
+                .decode(payload)
+                .map_err(EventFormatError::UnsupportedFormat)?,
+            EventFormat::SenML => {
+                let pack = senml::resolve_pack(payload).map_err(EventFormatError::UnsupportedFormat)?;
+                if let Some(time) = pack.time {
+                    timestamp = Some(senml::to_timestamp(time, received).ok_or_else(|| {
+                        EventFormatError::UnsupportedFormat(format!("Invalid SenML time {}", time))
+                    })?);
                 }
-                return Ok(payload);
+                pack.values
             }
+            EventFormat::KeyValue(separators) => separators
+                .decode(payload)
+                .map_err(EventFormatError::UnsupportedFormat)?,
+        };
+        let mut payload = HashMap::new();
+        for (key, value) in event_raw.into_iter() {
+            let value = EventDataValue::try_from(value)
+                .map_err(|_| EventFormatError::UnsupportedFormat(key.clone()))?;
+            payload.insert(key, value);
         }
+        Ok(DecodedEvent { payload, timestamp })
     }
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
     pub fn encode_event(
         &self,
         event_payload: HashMap<String, EventDataValue>,
-    ) -> Result<String, EventFormatError> {
+    ) -> Result<Vec<u8>, EventFormatError> {
+        let payload_converted: HashMap<String, Value> = event_payload
+            .iter()
+            .map(|(k, v)| (k.to_string(), Value::from(v.to_owned())))
+            .collect();
         match self {
             EventFormat::Json => {
-                let payload_converted: HashMap<String, Value> = event_payload
-                    .iter()
-                    .map(|(k, v)| {
-                        let val = match v {
-                            EventDataValue::String(s) => {
-                                Value::from(s.to_owned())
-                            }
-                            EventDataValue::Number(n) => {
-                                Value::from(n.to_owned())
-                            }
-                            EventDataValue::Boolean(b) => {
-                                Value::from(b.to_owned())
-                            }
-                        };
-                        (k.to_string(), val)
-                    })
-                    .collect();
-                return serde_json::to_string(&payload_converted)
-                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()));
+                serde_json::to_vec(&payload_converted)
+                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))
+            }
+            EventFormat::Cbor => {
+                let mut encoded = Vec::new();
+                ciborium::into_writer(&payload_converted, &mut encoded)
+                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))?;
+                Ok(encoded)
+            }
+            EventFormat::MessagePack => {
+                rmp_serde::to_vec(&payload_converted)
+                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))
+            }
+            EventFormat::Binary(layout) => {
+                layout
+                    .encode(&payload_converted)
+                    .map_err(EventFormatError::UnsupportedFormat)
+            }
+            EventFormat::Protobuf(message) => {
+                message
+                    .encode(&payload_converted)
+                    .map_err(EventFormatError::UnsupportedFormat)
+            }
+            EventFormat::SenML => {
+                let records = senml::to_records(payload_converted, &HashMap::new());
+                serde_json::to_vec(&records)
+                    .map_err(|e| EventFormatError::UnsupportedFormat(e.to_string()))
+            }
+            EventFormat::KeyValue(separators) => {
+                Ok(separators.encode(&payload_converted))
             }
         }
     }
@@ -66,16 +148,37 @@ impl Display for EventFormat {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
             EventFormat::Json => write!(f, "json"),
+            EventFormat::Cbor => write!(f, "cbor"),
+            EventFormat::MessagePack => write!(f, "msgpack"),
+            EventFormat::Binary(_) => write!(f, "binary"),
+            EventFormat::Protobuf(message) => write!(f, "protobuf:{}", message.name()),
+            EventFormat::SenML => write!(f, "senml"),
+            EventFormat::KeyValue(separators) if *separators == KeyValueSeparators::default() => write!(f, "keyvalue"),
+            EventFormat::KeyValue(separators) => write!(f, "keyvalue:{}{}", separators.pair, separators.field),
         }
     }
 }
 
+const KEY_VALUE_PREFIX: &str = "keyvalue:";
+
 impl TryFrom<&str> for EventFormat {
     type Error = String;
 
     fn try_from(value: &str) -> Result<Self, Self::Error> {
+        if let Some(message) = ProtobufMessage::from_format_name(value) {
+            return Ok(EventFormat::Protobuf(message));
+        }
+        // separators may be whitespace, the suffix is taken as is
+        if value.get(..KEY_VALUE_PREFIX.len()).is_some_and(|prefix| prefix.eq_ignore_ascii_case(KEY_VALUE_PREFIX)) {
+            return KeyValueSeparators::from_suffix(&value[KEY_VALUE_PREFIX.len()..]).map(EventFormat::KeyValue);
+        }
         match value.to_lowercase().as_str() {
             "json" => Ok(EventFormat::Json),
+            "cbor" => Ok(EventFormat::Cbor),
+            "msgpack" | "messagepack" => Ok(EventFormat::MessagePack),
+            "binary" => Ok(EventFormat::Binary(BinaryLayout::default())),
+            "senml" => Ok(EventFormat::SenML),
+            "keyvalue" => Ok(EventFormat::KeyValue(KeyValueSeparators::default())),
             _ => Err(format!("Unsupported event format: {}", value)),
         }
     }
@@ -83,6 +186,7 @@ impl TryFrom<&str> for EventFormat {
 
 pub enum EventFormatError {
     UnsupportedFormat(String),
+    ConstraintViolation(String),
 }
 
 impl Display for EventFormatError {
@@ -91,6 +195,9 @@ impl Display for EventFormatError {
             EventFormatError::UnsupportedFormat(msg) => {
                 write!(f, "Unsupported event format: {}", msg)
             }
+            EventFormatError::ConstraintViolation(msg) => {
+                write!(f, "Constraint violation: {}", msg)
+            }
         }
     }
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+
+use crate::domain::event::event_data_value::EventDataValue;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimestampEncoding {
+    Rfc3339,
+    EpochSeconds,
+    EpochMillis,
+}
+
+/// Payload field holding the time the device took the reading.
+/// Readings buffered by the device may be far in the past, only timestamps more than
+/// `max_skew` seconds ahead of the reception are discarded.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct TimestampField {
+    pub key: String,
+    pub encoding: TimestampEncoding,
+    /// Seconds a reading may be ahead of its reception, readings in the past are never discarded.
+    #[serde(default, skip_serializing_if = "Option::is_none", deserialize_with = "deserialize_max_skew")]
+    pub max_skew: Option<u64>,
+}
+
+fn deserialize_max_skew<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    let max_skew = Option::<u64>::deserialize(deserializer)?;
+    match max_skew {
+        Some(max_skew) if skew_delta(max_skew).is_none() => {
+            Err(serde::de::Error::custom(format!("max_skew of {} seconds out of range", max_skew)))
+        }
+        _ => Ok(max_skew),
+    }
+}
+
+fn skew_delta(max_skew: u64) -> Option<TimeDelta> {
+    i64::try_from(max_skew).ok().and_then(TimeDelta::try_seconds)
+}
+
+impl TimestampField {
+    /// Time of the reading, none if the field is missing, invalid or too far ahead.
+    pub fn extract(&self, payload: &HashMap<String, EventDataValue>, received: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let timestamp = match (self.encoding, payload.get(&self.key)?) {
+            (TimestampEncoding::Rfc3339, EventDataValue::String(s)) => {
+                DateTime::parse_from_rfc3339(s).ok()?.with_timezone(&Utc)
+            }
+            (TimestampEncoding::EpochSeconds, value) => DateTime::from_timestamp_millis((epoch(value)? * 1000.) as i64)?,
+            (TimestampEncoding::EpochMillis, value) => DateTime::from_timestamp_millis(epoch(value)? as i64)?,
+            _ => return None,
+        };
+        // only future skew is rejected, see `max_skew`
+        match self.max_skew.map(skew_delta) {
+            Some(Some(max_skew)) if timestamp - *received > max_skew => None,
+            _ => Some(timestamp),
+        }
+    }
+}
+
+// key=value payloads are not typed yet when the timestamp is extracted
+fn epoch(value: &EventDataValue) -> Option<f64> {
+    match value {
+        EventDataValue::Integer(n) => Some(*n as f64),
+        EventDataValue::Float(n) => Some(*n),
+        EventDataValue::String(s) => s.trim().parse::<f64>().ok().filter(|n| n.is_finite()),
+        _ => None,
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+
+use serde_json::Value;
+
+/// Separators of a `key=value,key=value` line.
+/// Written `keyvalue:<pair><field>` in device definitions, e.g. `keyvalue:;:` for `key:value;key:value`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyValueSeparators {
+    /// Separates two `key=value` pairs.
+    pub pair: char,
+    /// Separates the key from its value.
+    pub field: char,
+}
+
+impl Default for KeyValueSeparators {
+    fn default() -> Self {
+        Self { pair: ',', field: '=' }
+    }
+}
+
+impl KeyValueSeparators {
+    /// Parses the separators following `keyvalue:`, both are required.
+    pub fn from_suffix(suffix: &str) -> Result<Self, String> {
+        let mut chars = suffix.chars();
+        match (chars.next(), chars.next(), chars.next()) {
+            (Some(pair), Some(field), None) if pair != field => Ok(Self { pair, field }),
+            _ => Err(format!("Invalid key value separators '{}', expected a pair and a field separator", suffix)),
+        }
+    }
+
+    /// Values are kept as strings, they are typed with the device schema by `Event::new_checked`.
+    pub fn decode(&self, payload: &[u8]) -> Result<HashMap<String, Value>, String> {
+        let line = std::str::from_utf8(payload).map_err(|e| e.to_string())?;
+        let mut values = HashMap::new();
+        // a trailing pair separator leaves an empty pair behind
+        for pair in line.trim_end_matches(['\r', '\n']).split(self.pair).filter(|p| !p.is_empty()) {
+            let (key, value) = pair
+                .split_once(self.field)
+                .ok_or_else(|| format!("Invalid pair '{}', missing '{}'", pair, self.field))?;
+            let key = key.trim();
+            if key.is_empty() {
+                return Err(format!("Invalid pair '{}', missing key", pair));
+            }
+            values.insert(key.to_string(), Value::String(value.trim().to_string()));
+        }
+        Ok(values)
+    }
+
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    pub fn encode(&self, payload: &HashMap<String, Value>) -> Vec<u8> {
+        let mut keys: Vec<&String> = payload.keys().collect();
+        keys.sort();
+        keys.into_iter()
+            .map(|key| match &payload[key] {
+                Value::String(s) => format!("{}{}{}", key, self.field, s),
+                other => format!("{}{}{}", key, self.field, other),
+            })
+            .collect::<Vec<String>>()
+            .join(&self.pair.to_string())
+            .into_bytes()
+    }
+}
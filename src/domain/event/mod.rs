@@ -2,4 +2,8 @@ pub mod event;
 pub mod event_emittable;
 pub mod event_format;
 pub mod event_data_value;
-pub mod event_data_type;
\ No newline at end of file
+pub mod event_data_type;
+pub mod senml;
+pub mod key_value;
+pub mod event_timestamp;
+pub mod computed_field;
\ No newline at end of file
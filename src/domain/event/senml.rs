@@ -0,0 +1,121 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::{Number, Value};
+
+/// Times below 2^28 seconds are relative to the time the pack was received (RFC 8428 section 4.5.3).
+const RELATIVE_TIME_LIMIT: f64 = 268_435_456.;
+
+/// A SenML record (RFC 8428), only the JSON representation is supported.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SenmlRecord {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bn: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bt: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bu: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bv: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub n: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub u: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub v: Option<Number>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vs: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vb: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vd: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub t: Option<f64>,
+}
+
+/// Pack with base name and base time applied to every record.
+pub struct ResolvedPack {
+    /// Values keyed by record name relative to the first base name of the pack.
+    pub values: HashMap<String, Value>,
+    /// Time of the first record, in seconds, absolute or relative to the reception.
+    pub time: Option<f64>,
+}
+
+pub fn resolve_pack(payload: &[u8]) -> Result<ResolvedPack, String> {
+    let records: Vec<SenmlRecord> = serde_json::from_slice(payload).map_err(|e| e.to_string())?;
+    let device_base_name = records.iter().find_map(|r| r.bn.clone()).unwrap_or_default();
+    let mut base_name = String::new();
+    let mut base_time = 0.;
+    let mut base_value = None;
+    let mut values = HashMap::new();
+    let mut time = None;
+    for record in records {
+        if let Some(bn) = record.bn {
+            base_name = bn;
+        }
+        if let Some(bt) = record.bt {
+            base_time = bt;
+        }
+        if let Some(bv) = record.bv {
+            base_value = Some(bv);
+        }
+        let value = match (record.v, record.vs, record.vb, record.vd) {
+            (Some(v), _, _, _) => match base_value {
+                Some(bv) => Value::from(bv + v.as_f64().unwrap_or_default()),
+                None => Value::Number(v),
+            },
+            (_, Some(vs), _, _) => Value::String(vs),
+            (_, _, Some(vb), _) => Value::Bool(vb),
+            (_, _, _, Some(vd)) => Value::String(vd),
+            // records only carrying base fields
+            _ => continue,
+        };
+        let name = format!("{}{}", base_name, record.n.unwrap_or_default());
+        let key = name.strip_prefix(device_base_name.as_str()).unwrap_or(&name);
+        if key.is_empty() {
+            return Err(format!("record named {} has no name relative to {}", name, device_base_name));
+        }
+        time.get_or_insert(base_time + record.t.unwrap_or_default());
+        values.insert(key.to_string(), value);
+    }
+    Ok(ResolvedPack { values, time })
+}
+
+/// Converts a SenML time to a timestamp, relative times are added to `received`. Times out of
+/// the range of timestamps give none.
+pub fn to_timestamp(time: f64, received: &DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if !time.is_finite() {
+        return None;
+    }
+    // the cast saturates, saturated values are out of range below
+    let millis = (time * 1000.) as i64;
+    if time < RELATIVE_TIME_LIMIT {
+        return received.checked_add_signed(TimeDelta::try_milliseconds(millis)?);
+    }
+    DateTime::from_timestamp_millis(millis)
+}
+
+/// Builds the records of a payload, the caller sets base fields on the first one.
+#[cfg(any(feature = "axum", feature = "mqtt_outbound", feature = "reqwest"))]
+pub fn to_records(payload: HashMap<String, Value>, units: &HashMap<String, String>) -> Vec<SenmlRecord> {
+    let mut keys: Vec<&String> = payload.keys().collect();
+    keys.sort();
+    keys.into_iter()
+        .map(|key| {
+            let mut record = SenmlRecord {
+                n: Some(key.clone()),
+                u: units.get(key).cloned(),
+                ..Default::default()
+            };
+            match &payload[key] {
+                Value::Number(n) => record.v = Some(n.clone()),
+                Value::Bool(b) => record.vb = Some(*b),
+                Value::String(s) => record.vs = Some(s.clone()),
+                // structured values have no SenML representation, they are sent as JSON text
+                other => record.vs = Some(other.to_string()),
+            }
+            record
+        })
+        .collect()
+}
@@ -0,0 +1,181 @@
+use std::fmt::Display;
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Constraints a payload field must satisfy on top of its data type.
+/// Values are compared in their JSON form so the same constraints apply to events and actions.
+#[derive(Debug, Clone, Default)]
+pub struct FieldConstraints {
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub allowed_values: Option<Vec<Value>>,
+    pub pattern: Option<Regex>,
+    pub max_length: Option<usize>,
+    pub unit: Option<String>,
+    /// The field may be missing from the payload.
+    pub optional: bool,
+    /// Value used when the field is missing from the payload, implies `optional`.
+    pub default: Option<Value>,
+}
+
+impl FieldConstraints {
+    pub fn is_required(&self) -> bool {
+        !self.optional && self.default.is_none()
+    }
+
+    pub fn check(&self, key: &str, value: &Value) -> Result<(), String> {
+        if let Some(n) = value.as_f64() {
+            if let Some(min) = self.min
+                && n < min
+            {
+                return Err(format!("value {} for key {} is below minimum {}", n, key, min));
+            }
+            if let Some(max) = self.max
+                && n > max
+            {
+                return Err(format!("value {} for key {} is above maximum {}", n, key, max));
+            }
+        }
+        if let Some(s) = value.as_str() {
+            if let Some(max_length) = self.max_length
+                && s.chars().count() > max_length
+            {
+                return Err(format!("value for key {} is longer than {} characters", key, max_length));
+            }
+            if let Some(pattern) = &self.pattern
+                && !pattern.is_match(s)
+            {
+                return Err(format!("value '{}' for key {} does not match pattern {}", s, key, pattern.as_str()));
+            }
+        }
+        if let Some(allowed) = &self.allowed_values
+            && !allowed.iter().any(|a| same_value(a, value))
+        {
+            return Err(format!("value {} for key {} is not one of the allowed values", value, key));
+        }
+        Ok(())
+    }
+}
+
+// 5 and 5.0 are different JSON numbers but the same value for a device
+fn same_value(a: &Value, b: &Value) -> bool {
+    match (a.as_f64(), b.as_f64()) {
+        (Some(x), Some(y)) => x == y,
+        _ => a == b,
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct FieldConstraintsRaw {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    min: Option<f64>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max: Option<f64>,
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    allowed_values: Option<Vec<Value>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pattern: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    max_length: Option<usize>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    unit: Option<String>,
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    optional: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    default: Option<Value>,
+}
+
+impl Serialize for FieldConstraints {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        FieldConstraintsRaw {
+            min: self.min,
+            max: self.max,
+            allowed_values: self.allowed_values.clone(),
+            pattern: self.pattern.as_ref().map(|p| p.as_str().to_string()),
+            max_length: self.max_length,
+            unit: self.unit.clone(),
+            optional: self.optional,
+            default: self.default.clone(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for FieldConstraints {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let raw = FieldConstraintsRaw::deserialize(deserializer)?;
+        let pattern = match raw.pattern {
+            Some(p) => Some(Regex::new(&p).map_err(serde::de::Error::custom)?),
+            None => None,
+        };
+        Ok(FieldConstraints {
+            min: raw.min,
+            max: raw.max,
+            allowed_values: raw.allowed_values,
+            pattern,
+            max_length: raw.max_length,
+            unit: raw.unit,
+            optional: raw.optional,
+            default: raw.default,
+        })
+    }
+}
+
+/// What to do with payload keys that are not declared in the schema.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum UnknownKeyPolicy {
+    #[default]
+    Accept,
+    Drop,
+    Reject,
+}
+
+impl Display for UnknownKeyPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            UnknownKeyPolicy::Accept => write!(f, "accept"),
+            UnknownKeyPolicy::Drop => write!(f, "drop"),
+            UnknownKeyPolicy::Reject => write!(f, "reject"),
+        }
+    }
+}
+
+impl TryFrom<&str> for UnknownKeyPolicy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "accept" => Ok(UnknownKeyPolicy::Accept),
+            "drop" => Ok(UnknownKeyPolicy::Drop),
+            "reject" => Ok(UnknownKeyPolicy::Reject),
+            _ => Err(format!("Unsupported unknown key policy: {}", value)),
+        }
+    }
+}
+
+impl Serialize for UnknownKeyPolicy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for UnknownKeyPolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        UnknownKeyPolicy::try_from(s.as_str()).map_err(serde::de::Error::custom)
+    }
+}
@@ -1,4 +1,15 @@
 pub mod action;
+pub mod binary_layout;
+pub mod connectivity;
+pub mod cron;
 pub mod device;
+pub mod device_filter;
+pub mod device_group;
+pub mod device_model;
+pub mod device_schema;
+pub mod device_shadow;
 pub mod event;
+pub mod field_constraints;
+pub mod protobuf;
+pub mod rule;
 pub mod state;
\ No newline at end of file
@@ -0,0 +1,82 @@
+use std::collections::HashMap;
+
+use prost_reflect::{DescriptorPool, DynamicMessage, MessageDescriptor, SerializeOptions};
+#[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+use prost_reflect::{DeserializeOptions, prost::Message};
+use serde_json::Value;
+
+/// Message type of a protobuf emittable, e.g. `sensors.Reading`.
+/// The descriptor comes from the descriptor set of the device, see `Device::set_descriptor_set`.
+#[derive(Debug, Clone)]
+pub struct ProtobufMessage {
+    name: String,
+    descriptor: Option<MessageDescriptor>,
+}
+
+const FORMAT_PREFIX: &str = "protobuf:";
+
+impl ProtobufMessage {
+    pub fn new(name: &str) -> Self {
+        Self { name: name.to_string(), descriptor: None }
+    }
+
+    /// Message of a `protobuf:<message type>` format name, the prefix is matched in any case
+    /// like the other format names.
+    pub fn from_format_name(value: &str) -> Option<Self> {
+        value
+            .get(..FORMAT_PREFIX.len())
+            .filter(|prefix| prefix.eq_ignore_ascii_case(FORMAT_PREFIX))
+            .map(|_| Self::new(value[FORMAT_PREFIX.len()..].trim()))
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn is_resolved(&self) -> bool {
+        self.descriptor.is_some()
+    }
+
+    pub fn resolve(&mut self, pool: &DescriptorPool) -> Result<(), String> {
+        let descriptor = pool
+            .get_message_by_name(&self.name)
+            .ok_or_else(|| format!("Message {} not found in descriptor set", self.name))?;
+        self.descriptor = Some(descriptor);
+        Ok(())
+    }
+
+    fn descriptor(&self) -> Result<&MessageDescriptor, String> {
+        self.descriptor
+            .as_ref()
+            .ok_or_else(|| format!("No descriptor set registered for message {}", self.name))
+    }
+
+    /// Decodes a message into its fields, keyed by their name in the `.proto` file.
+    pub fn decode(&self, payload: &[u8]) -> Result<HashMap<String, Value>, String> {
+        let message = DynamicMessage::decode(self.descriptor()?.clone(), payload).map_err(|e| e.to_string())?;
+        let options = SerializeOptions::new()
+            .use_proto_field_name(true)
+            .stringify_64_bit_integers(false)
+            .skip_default_fields(false);
+        let value = message
+            .serialize_with_options(serde_json::value::Serializer, &options)
+            .map_err(|e| e.to_string())?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    }
+
+    #[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+    pub fn encode(&self, payload: &HashMap<String, Value>) -> Result<Vec<u8>, String> {
+        let value = serde_json::to_value(payload).map_err(|e| e.to_string())?;
+        let message = DynamicMessage::deserialize_with_options(
+            self.descriptor()?.clone(),
+            value,
+            &DeserializeOptions::new(),
+        )
+        .map_err(|e| e.to_string())?;
+        Ok(message.encode_to_vec())
+    }
+}
+
+pub fn parse_descriptor_set(descriptor_set: &[u8]) -> Result<DescriptorPool, String> {
+    DescriptorPool::decode(descriptor_set).map_err(|e| format!("Invalid descriptor set: {}", e))
+}
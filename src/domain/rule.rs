@@ -0,0 +1,176 @@
+use std::{cmp::Ordering, collections::HashMap};
+
+use chrono::{DateTime, TimeDelta, Utc};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::domain::{event::event::Event, state::DeviceState};
+
+/// Comparison between the value read by a condition and the expected one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuleOperator {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+/// Where a condition reads its value: the payload of the event evaluated, or the
+/// current state of the device once the event is merged in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConditionSource {
+    Event,
+    State,
+}
+
+/// Compares the value of `key` to `value`, e.g. `temperature > 30`. Numbers compare by value,
+/// strings in lexicographic order, other values only support `eq` and `ne`. A missing key
+/// never holds.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleCondition {
+    pub source: ConditionSource,
+    pub key: String,
+    pub operator: RuleOperator,
+    pub value: Value,
+}
+
+impl RuleCondition {
+    pub fn holds(&self, event: &Event, state: Option<&DeviceState>) -> bool {
+        let actual: Option<Value> = match self.source {
+            ConditionSource::Event => event.payload.get(&self.key).cloned().map(Value::from),
+            ConditionSource::State => state
+                .and_then(|state| state.values.get(&self.key))
+                .map(|state_value| state_value.value.clone().into()),
+        };
+        let Some(actual) = actual else {
+            return false;
+        };
+        let ordering = match (&actual, &self.value) {
+            (Value::Number(a), Value::Number(b)) => a.as_f64().zip(b.as_f64()).and_then(|(a, b)| a.partial_cmp(&b)),
+            (Value::String(a), Value::String(b)) => Some(a.cmp(b)),
+            (a, b) if a == b => Some(Ordering::Equal),
+            _ => None,
+        };
+        match self.operator {
+            RuleOperator::Eq => ordering == Some(Ordering::Equal),
+            RuleOperator::Ne => ordering != Some(Ordering::Equal),
+            RuleOperator::Gt => ordering == Some(Ordering::Greater),
+            RuleOperator::Gte => matches!(ordering, Some(Ordering::Greater | Ordering::Equal)),
+            RuleOperator::Lt => ordering == Some(Ordering::Less),
+            RuleOperator::Lte => matches!(ordering, Some(Ordering::Less | Ordering::Equal)),
+        }
+    }
+}
+
+/// Action sent when a rule fires, its payload is validated against the target device when sent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RuleAction {
+    pub device_id: Uuid,
+    pub action_name: String,
+    #[serde(default)]
+    pub payload: HashMap<String, Value>,
+}
+
+/// Sends an action when the events of a device meet all the conditions, e.g. "if device A
+/// temperature > 30 then send `fan_on` to device B".
+#[derive(Debug, Clone, PartialEq)]
+pub struct Rule {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    /// Device whose events are evaluated.
+    pub device_id: Uuid,
+    /// Event evaluated, every event of the device when none.
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    /// Seconds the conditions must keep holding before the rule fires.
+    pub debounce: u64,
+    /// Seconds after firing during which the rule does not fire again.
+    pub cooldown: u64,
+    pub enabled: bool,
+    /// Time the conditions started holding, none while they don't.
+    pub matching_since: Option<DateTime<Utc>>,
+    pub last_triggered_at: Option<DateTime<Utc>>,
+}
+
+impl Rule {
+    /// Enabled rule firing as soon as its conditions hold, it is validated when created.
+    pub fn new(
+        user_id: &Uuid,
+        name: &str,
+        device_id: &Uuid,
+        event_name: Option<String>,
+        conditions: Vec<RuleCondition>,
+        action: RuleAction,
+    ) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            user_id: *user_id,
+            name: name.to_string(),
+            device_id: *device_id,
+            event_name,
+            conditions,
+            action,
+            debounce: 0,
+            cooldown: 0,
+            enabled: true,
+            matching_since: None,
+            last_triggered_at: None,
+        }
+    }
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.is_empty() {
+            return Err("Rule name cannot be empty".to_string());
+        }
+        if self.conditions.is_empty() {
+            return Err(format!("Rule '{}' has no condition", self.name));
+        }
+        if let Some(condition) = self.conditions.iter().find(|condition| condition.key.is_empty()) {
+            return Err(format!("Rule '{}' has a condition without key on {:?}", self.name, condition.source));
+        }
+        if seconds(self.debounce).is_none() || seconds(self.cooldown).is_none() {
+            return Err(format!("Rule '{}' has a debounce or cooldown out of range", self.name));
+        }
+        if self.action.action_name.is_empty() {
+            return Err(format!("Rule '{}' has no action name", self.name));
+        }
+        Ok(())
+    }
+    /// Evaluates the rule on an event of its device and the device state it led to, returns
+    /// whether the rule fires. Tracks since when the conditions hold and when it last fired.
+    pub fn evaluate(&mut self, event: &Event, state: Option<&DeviceState>, now: &DateTime<Utc>) -> bool {
+        if !self.enabled || self.event_name.as_ref().is_some_and(|event_name| *event_name != event.event_name) {
+            return false;
+        }
+        if !self.conditions.iter().all(|condition| condition.holds(event, state)) {
+            self.matching_since = None;
+            return false;
+        }
+        // rules are validated when stored, out of range durations never let them fire
+        let (Some(debounce), Some(cooldown)) = (seconds(self.debounce), seconds(self.cooldown)) else {
+            return false;
+        };
+        let matching_since = *self.matching_since.get_or_insert(*now);
+        if *now - matching_since < debounce {
+            return false;
+        }
+        if self
+            .last_triggered_at
+            .is_some_and(|last_triggered_at| *now - last_triggered_at < cooldown)
+        {
+            return false;
+        }
+        self.last_triggered_at = Some(*now);
+        true
+    }
+}
+
+fn seconds(value: u64) -> Option<TimeDelta> {
+    i64::try_from(value).ok().and_then(TimeDelta::try_seconds)
+}
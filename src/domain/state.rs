@@ -1,6 +1,7 @@
 use uuid::Uuid;
 use chrono::{DateTime, Utc};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::{collections::HashMap, fmt::Display};
 
 use crate::domain::event::event_data_value::EventDataValue;
 
@@ -8,5 +9,158 @@ use crate::domain::event::event_data_value::EventDataValue;
 pub struct DeviceState {
     pub device_id: Uuid,
     pub last_update: DateTime<Utc>,
-    pub values: HashMap<String, EventDataValue>,
+    pub values: HashMap<String, StateValue>,
+    /// Values users want the device to report, actions are sent for it to converge.
+    pub desired: HashMap<String, EventDataValue>,
+    pub desired_update: Option<DateTime<Utc>>,
+    /// Time the reported values reached the desired ones, none while they differ.
+    pub converged_at: Option<DateTime<Utc>>,
+}
+
+/// Last value reported for a key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateValue {
+    pub value: EventDataValue,
+    pub source: ValueSource,
+}
+
+/// When a state value was reported and the event it comes from, values set without
+/// an event have no event ID nor name.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ValueSource {
+    pub timestamp: DateTime<Utc>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_id: Option<Uuid>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+}
+
+/// How values reported for a key replace the stored one.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum StateMergePolicy {
+    /// The value with the latest event timestamp wins, late events do not roll the state back.
+    #[default]
+    EventTime,
+    /// The last value received wins.
+    ArrivalTime,
+}
+
+impl Display for StateMergePolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StateMergePolicy::EventTime => write!(f, "event_time"),
+            StateMergePolicy::ArrivalTime => write!(f, "arrival_time"),
+        }
+    }
+}
+
+impl TryFrom<&str> for StateMergePolicy {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.to_lowercase().as_str() {
+            "event_time" => Ok(StateMergePolicy::EventTime),
+            "arrival_time" => Ok(StateMergePolicy::ArrivalTime),
+            _ => Err(format!("Unsupported state merge policy: {}", value)),
+        }
+    }
+}
+
+impl Serialize for StateMergePolicy {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: serde::Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for StateMergePolicy {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        StateMergePolicy::try_from(s.as_str()).map_err(serde::de::Error::custom)
+    }
+}
+
+impl DeviceState {
+    /// Rebuilds a state stored as values and sources, values without a source
+    /// are dated with `last_update`.
+    pub fn from_parts(device_id: Uuid, last_update: DateTime<Utc>, values: HashMap<String, EventDataValue>, mut sources: HashMap<String, ValueSource>) -> Self {
+        let values = values
+            .into_iter()
+            .map(|(key, value)| {
+                let source = sources.remove(&key).unwrap_or(ValueSource {
+                    timestamp: last_update,
+                    event_id: None,
+                    event_name: None,
+                });
+                (key, StateValue { value, source })
+            })
+            .collect();
+        Self { device_id, last_update, values, desired: HashMap::new(), desired_update: None, converged_at: None }
+    }
+    pub fn raw_values(&self) -> HashMap<String, EventDataValue> {
+        self.values.iter().map(|(k, v)| (k.clone(), v.value.clone())).collect()
+    }
+    pub fn sources(&self) -> HashMap<String, ValueSource> {
+        self.values.iter().map(|(k, v)| (k.clone(), v.source.clone())).collect()
+    }
+    /// Values of the given keys, keys without a value are skipped.
+    pub fn values_of(&self, keys: &[String]) -> HashMap<String, StateValue> {
+        keys.iter()
+            .filter_map(|key| self.values.get(key).map(|value| (key.clone(), value.clone())))
+            .collect()
+    }
+    /// Merges reported values, returns the keys whose value was replaced.
+    pub fn merge(&mut self, values: HashMap<String, StateValue>, policy: StateMergePolicy) -> Vec<String> {
+        let mut updated = Vec::new();
+        for (key, value) in values {
+            let is_older = match (policy, self.values.get(&key)) {
+                (StateMergePolicy::EventTime, Some(current)) => value.source.timestamp < current.source.timestamp,
+                _ => false,
+            };
+            if !is_older {
+                self.values.insert(key.clone(), value);
+                updated.push(key);
+            }
+        }
+        updated
+    }
+    /// Desired values the reported ones do not match yet.
+    pub fn delta(&self) -> HashMap<String, EventDataValue> {
+        self.desired
+            .iter()
+            .filter(|(key, desired)| !self.values.get(*key).is_some_and(|reported| same_value(&reported.value, desired)))
+            .map(|(key, desired)| (key.clone(), desired.clone()))
+            .collect()
+    }
+    /// Replaces the desired values, convergence is tracked again from `now`.
+    pub fn set_desired(&mut self, desired: HashMap<String, EventDataValue>, now: &DateTime<Utc>) {
+        self.desired = desired;
+        self.desired_update = Some(*now);
+        self.converged_at = None;
+        self.refresh_convergence(now);
+    }
+    /// Dates the convergence once reported values match the desired ones.
+    pub fn refresh_convergence(&mut self, now: &DateTime<Utc>) {
+        if self.desired_update.is_none() {
+            return;
+        }
+        if !self.delta().is_empty() {
+            self.converged_at = None;
+        } else if self.converged_at.is_none() {
+            self.converged_at = Some(*now);
+        }
+    }
+}
+
+// devices may report an integer for a float that was desired, and the other way around
+fn same_value(reported: &EventDataValue, desired: &EventDataValue) -> bool {
+    match (reported, desired) {
+        (EventDataValue::Integer(i), EventDataValue::Float(f)) | (EventDataValue::Float(f), EventDataValue::Integer(i)) => *i as f64 == *f,
+        _ => reported == desired,
+    }
 }
@@ -0,0 +1,120 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::Utc;
+use tokio::task::JoinSet;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::{DeviceService, DeviceServiceError}},
+    },
+    domain::{
+        action::{action::Action, action_data_value::ActionDataValue},
+        device::Device,
+        device_filter::DeviceFilter,
+    },
+};
+
+/// Actions sent at the same time during a broadcast, the others wait for one of them to end.
+const MAX_CONCURRENT_SENDS: usize = 16;
+
+/// Devices an action is broadcast to.
+#[derive(Debug, Clone)]
+pub enum BroadcastTarget {
+    /// Devices by physical ID.
+    Devices(Vec<String>),
+    /// Devices of the user matching the filter, of the model when one is given.
+    User {
+        user_id: Uuid,
+        filter: DeviceFilter,
+        model_id: Option<Uuid>,
+    },
+}
+
+/// Outcome of a broadcast for a device, the action sent or why none was.
+#[derive(Debug)]
+pub struct BroadcastResult {
+    pub physical_id: String,
+    pub result: Result<Action, String>,
+}
+
+/// Sends the action to every targeted device, its payload validated against the action of each
+/// device. Returns a result per device in the order of the target, fails if the devices can't be
+/// listed.
+pub async fn broadcast_action<AO: AppOutbound + 'static>(
+    outbound: &AO,
+    target: &BroadcastTarget,
+    action_name: &str,
+    payload: &HashMap<String, ActionDataValue>,
+) -> Result<Vec<BroadcastResult>, String> {
+    let devices = resolve_target(outbound, target).await?;
+    let mut results: Vec<(usize, BroadcastResult)> = Vec::with_capacity(devices.len());
+    let mut sends = JoinSet::new();
+    for (index, (physical_id, device)) in devices.into_iter().enumerate() {
+        if sends.len() >= MAX_CONCURRENT_SENDS
+            && let Some(sent) = sends.join_next().await
+        {
+            results.push(sent.map_err(|e| e.to_string())?);
+        }
+        let outbound = outbound.clone();
+        let action_name = action_name.to_string();
+        let payload = payload.clone();
+        sends.spawn(async move {
+            let result = match device {
+                Some(device) => outbound
+                    .get_action_service()
+                    .send_device_action(&device, &action_name, payload, &Utc::now())
+                    .await
+                    .map_err(|e| e.to_string()),
+                None => Err("Device not found".to_string()),
+            };
+            (index, BroadcastResult { physical_id, result })
+        });
+    }
+    while let Some(sent) = sends.join_next().await {
+        results.push(sent.map_err(|e| e.to_string())?);
+    }
+    results.sort_by_key(|(index, _)| *index);
+    Ok(results.into_iter().map(|(_, result)| result).collect())
+}
+
+/// Targeted devices by physical ID, none for the explicit IDs matching no device.
+async fn resolve_target<AO: AppOutbound>(
+    outbound: &AO,
+    target: &BroadcastTarget,
+) -> Result<Vec<(String, Option<Device>)>, String> {
+    let device_service = outbound.get_device_service();
+    match target {
+        BroadcastTarget::Devices(physical_ids) => {
+            let mut seen = HashSet::new();
+            let mut devices = Vec::new();
+            for physical_id in physical_ids {
+                if !seen.insert(physical_id) {
+                    continue;
+                }
+                // an unknown ID only fails the send to that device
+                let device = match device_service.get_device_by_physical_id(physical_id).await {
+                    Ok(device) => device,
+                    Err(DeviceServiceError::NotFound) => None,
+                    Err(e) => return Err(e.to_string()),
+                };
+                devices.push((physical_id.clone(), device));
+            }
+            Ok(devices)
+        }
+        BroadcastTarget::User { user_id, filter, model_id } => {
+            let devices = if filter.is_empty() {
+                device_service.get_devices_by_user_id(*user_id).await
+            } else {
+                device_service.get_devices_by_filter(*user_id, filter).await
+            };
+            Ok(devices
+                .map_err(|e| e.to_string())?
+                .into_iter()
+                .filter(|device| model_id.is_none() || device.model_id() == model_id.as_ref())
+                .map(|device| (device.physical_id().to_string(), Some(device)))
+                .collect())
+        }
+    }
+}
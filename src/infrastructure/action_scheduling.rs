@@ -0,0 +1,53 @@
+use std::time::Duration;
+
+use chrono::Utc;
+use tracing::{trace, warn};
+
+use crate::application::{ports::app::AppOutbound, usecases::action_scheduler::ActionScheduler};
+
+/// Time between two runs of the scheduler.
+const SCHEDULER_PERIOD: Duration = Duration::from_secs(5);
+
+// with an MQTT outbound, the MQTT inbound storing the actions expires them
+const EXPIRES_ACTIONS: bool = !cfg!(feature = "mqtt_outbound");
+
+// with an MQTT server outbound, the MQTT inbound sharing its database runs the scheduler
+pub const RUNS_SCHEDULER: bool = !cfg!(feature = "mqtt_server_outbound");
+
+/// Sends the scheduled actions and the actions of recurring schedules once due, and expires the overdue ones until the application stops.
+pub async fn run_action_scheduler<AO: AppOutbound>(outbound: AO) {
+    let scheduler = ActionScheduler::new(outbound.get_action_service().clone(), outbound.get_device_service().clone());
+    let mut interval = tokio::time::interval(SCHEDULER_PERIOD);
+    loop {
+        interval.tick().await;
+        let now = Utc::now();
+        match scheduler.dispatch_due_actions(&now).await {
+            Ok(results) => {
+                let mut dispatched = 0;
+                for result in results {
+                    match result {
+                        Ok(_) => dispatched += 1,
+                        Err((action_id, e)) => warn!(result = "warn", details = format!("Failed to dispatch scheduled action {}: {}", action_id, e)),
+                    }
+                }
+                if dispatched > 0 {
+                    trace!(result = "success", details = format!("{} scheduled action(s) dispatched", dispatched));
+                }
+            }
+            Err(e) => warn!(result = "warn", details = format!("Failed to dispatch scheduled actions: {}", e)),
+        }
+        match scheduler.run_due_schedules(&now).await {
+            Ok(runs) if !runs.is_empty() => trace!(result = "success", details = format!("{} schedule(s) ran", runs.len())),
+            Ok(_) => {}
+            Err(e) => warn!(result = "warn", details = format!("Failed to run action schedules: {}", e)),
+        }
+        if !EXPIRES_ACTIONS {
+            continue;
+        }
+        match scheduler.expire_actions(&now).await {
+            Ok(actions) if !actions.is_empty() => trace!(result = "success", details = format!("{} action(s) expired", actions.len())),
+            Ok(_) => {}
+            Err(e) => warn!(result = "warn", details = format!("Failed to expire actions: {}", e)),
+        }
+    }
+}
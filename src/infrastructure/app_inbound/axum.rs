@@ -2,13 +2,13 @@ use std::sync::Arc;
 
 use axum::{
     Router,
-    routing::{get, post},
+    routing::{delete, get, post, put},
 };
 use tower_http::trace::TraceLayer;
 
 use crate::{
     application::ports::app::{AppInbound, AppOutbound}, infrastructure::http::axum::{
-        action_handlers::{create::create_action_handler, get::get_actions_handler}, device_handlers::{create::create_device_handler, delete::delete_device_handler, get::{get_device_by_physical_id, get_device_handler, get_devices_handler}, update::update_device_handler}, device_state_handlers::get_device_state_handler, events_handlers::{create_event_handler, get_event_handler}
+        action_handlers::{broadcast::broadcast_action_handler, create::create_action_handler, get::get_actions_handler, scheduled::{cancel_scheduled_action_handler, get_scheduled_actions_handler}, schedules::{create_action_schedule_handler, delete_action_schedule_handler, get_action_schedules_handler, get_schedule_runs_handler}, status::{acknowledge_action_handler, get_action_handler}}, device_handlers::{create::create_device_handler, delete::delete_device_handler, get::{get_device_by_physical_id, get_device_handler, get_devices_handler}, schemas::{diff_device_schemas_handler, get_device_schemas_handler}, update::update_device_handler}, device_group_handlers::{add_device_to_group_handler, create_device_group_handler, delete_device_group_handler, get_device_group_children_handler, get_device_group_devices_handler, get_device_group_handler, get_device_group_states_handler, get_device_groups_handler, remove_device_from_group_handler, update_device_group_handler}, device_model_handlers::{create_device_model_handler, delete_device_model_handler, get_device_model_handler, get_device_models_handler, update_device_model_handler}, device_state_handlers::{get_device_shadow_handler, get_device_state_handler, set_desired_state_handler}, events_handlers::{create_event_handler, get_event_handler}, rule_handlers::{create_rule_handler, delete_rule_handler, get_rule_handler, get_rules_handler, update_rule_handler}
     }
 };
 
@@ -31,8 +31,33 @@ impl AppInbound for AxumAppInbound {
                     .delete(delete_device_handler)
                     .post(update_device_handler),
             )
+            .route("/devices/{device_id}/schemas", get(get_device_schemas_handler))
+            .route("/devices/{device_id}/schemas/diff", get(diff_device_schemas_handler))
+            .route("/models", post(create_device_model_handler).get(get_device_models_handler))
+            .route(
+                "/models/{model_id}",
+                get(get_device_model_handler)
+                    .delete(delete_device_model_handler)
+                    .post(update_device_model_handler),
+            )
+            .route("/groups", post(create_device_group_handler).get(get_device_groups_handler))
+            .route(
+                "/groups/{group_id}",
+                get(get_device_group_handler)
+                    .delete(delete_device_group_handler)
+                    .post(update_device_group_handler),
+            )
+            .route("/groups/{group_id}/children", get(get_device_group_children_handler))
+            .route("/groups/{group_id}/devices", get(get_device_group_devices_handler))
+            .route(
+                "/groups/{group_id}/devices/{device_id}",
+                put(add_device_to_group_handler).delete(remove_device_from_group_handler),
+            )
+            .route("/groups/{group_id}/states", get(get_device_group_states_handler))
             .route("/physical/{device_physical_id}", get(get_device_by_physical_id))
             .route("/device_states/{device_id}", get(get_device_state_handler))
+            .route("/device_states/{device_id}/shadow", get(get_device_shadow_handler))
+            .route("/device_states/{device_id}/desired", put(set_desired_state_handler))
             .route(
                 "/events/{device_id}",
                 get(get_event_handler),
@@ -40,6 +65,20 @@ impl AppInbound for AxumAppInbound {
             .route("/events/{device_id}/{event_name}", post(create_event_handler))
             .route("/actions/{device_id}", get(get_actions_handler))
             .route("/actions/{device_id}/{action_name}", post(create_action_handler))
+            .route("/broadcast_actions/{action_name}", post(broadcast_action_handler))
+            .route("/scheduled_actions", get(get_scheduled_actions_handler))
+            .route("/scheduled_actions/{action_id}", delete(cancel_scheduled_action_handler))
+            .route("/action_schedules", post(create_action_schedule_handler).get(get_action_schedules_handler))
+            .route("/action_schedules/{schedule_id}", delete(delete_action_schedule_handler))
+            .route("/action_schedules/{schedule_id}/runs", get(get_schedule_runs_handler))
+            .route("/action_status/{action_id}", get(get_action_handler).post(acknowledge_action_handler))
+            .route("/rules", post(create_rule_handler).get(get_rules_handler))
+            .route(
+                "/rules/{rule_id}",
+                get(get_rule_handler)
+                    .delete(delete_rule_handler)
+                    .post(update_rule_handler),
+            )
             .with_state(Arc::new(state))
             .layer(TraceLayer::new_for_http());
         let listener = tokio::net::TcpListener::bind("0.0.0.0:3000").await.unwrap();
@@ -7,10 +7,16 @@ use crate::{
     application::ports::app::{AppInbound, AppOutbound},
     infrastructure::{
         mqtt::inbound::{
-            device_handler::handle_device, device_state_handler::handle_device_state,
+            action_handler::{handle_action, handle_action_ack},
+            device_group_handler::handle_device_group, device_handler::handle_device,
+            device_model_handler::handle_device_model,
+            connectivity_publisher::ConnectivityPublisher,
+            device_shadow_handler::handle_device_shadow,
+            device_state_handler::handle_device_state,
             error::HandlerError, event_handler::handle_event,
+            rule_handler::handle_rule,
         },
-        utils,
+        connectivity, utils,
     },
 };
 
@@ -19,7 +25,14 @@ pub struct MQTTAppInbound {
     mqtt_port: u16,
     event_topic: String,
     device_topic: String,
+    device_model_topic: String,
+    device_group_topic: String,
     device_state_topic: String,
+    device_shadow_topic: String,
+    connectivity_topic: String,
+    action_topic: String,
+    action_ack_topic: String,
+    rule_topic: String,
 }
 
 impl MQTTAppInbound {
@@ -30,20 +43,40 @@ impl MQTTAppInbound {
             mqtt_port: config.mqtt_port,
             event_topic: config.event_topic.to_string(),
             device_topic: config.device_topic.to_string(),
+            device_model_topic: config.device_model_topic.to_string(),
+            device_group_topic: config.device_group_topic.to_string(),
             device_state_topic: config.device_state_topic.to_string(),
+            device_shadow_topic: config.device_shadow_topic.to_string(),
+            connectivity_topic: config.connectivity_topic.to_string(),
+            action_topic: config.action_topic.to_string(),
+            action_ack_topic: config.action_ack_topic.to_string(),
+            rule_topic: config.rule_topic.to_string(),
         }
     }
     pub async fn router<AO: AppOutbound + 'static>(
         &self,
         received: &rumqttc::Publish,
         outbound: &AO,
+        publisher: &ConnectivityPublisher,
     ) -> Result<(), HandlerError> {
         if received.topic == self.event_topic {
-            handle_event(received, outbound).await
+            handle_event(received, outbound, publisher).await
         } else if received.topic == self.device_topic {
             handle_device(received, outbound).await
+        } else if received.topic == self.device_model_topic {
+            handle_device_model(received, outbound).await
+        } else if received.topic == self.device_group_topic {
+            handle_device_group(received, outbound).await
         } else if received.topic == self.device_state_topic {
             handle_device_state(received, outbound).await
+        } else if received.topic == self.device_shadow_topic {
+            handle_device_shadow(received, outbound).await
+        } else if received.topic == self.action_topic {
+            handle_action(received, outbound).await
+        } else if received.topic == self.action_ack_topic {
+            handle_action_ack(received, outbound).await
+        } else if received.topic == self.rule_topic {
+            handle_rule(received, outbound).await
         } else {
             Err(HandlerError::ParsingError(
                 "Topic does not match with any handler error".to_string(),
@@ -68,27 +101,56 @@ impl AppInbound for MQTTAppInbound {
         mqttoptions.set_keep_alive(Duration::from_secs(5));
 
         let (client, mut eventloop) = AsyncClient::new(mqttoptions, 10);
+        let publisher = ConnectivityPublisher::new(client.clone(), &self.connectivity_topic);
+        let monitor_publisher = publisher.clone();
+        tokio::spawn(connectivity::run_heartbeat_monitor(outbound.clone(), move |change| {
+            monitor_publisher.publish(change)
+        }));
 
         // Subscribe to topics and handle incoming messages
         client
             .subscribe(&self.device_topic, rumqttc::QoS::AtMostOnce)
             .await
             .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.device_model_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.device_group_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
         client
             .subscribe(&self.device_state_topic, rumqttc::QoS::AtMostOnce)
             .await
             .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.device_shadow_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
         client
             .subscribe(&self.event_topic, rumqttc::QoS::AtMostOnce)
             .await
             .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.action_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.action_ack_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
+        client
+            .subscribe(&self.rule_topic, rumqttc::QoS::AtMostOnce)
+            .await
+            .map_err(|e| e.to_string())?;
         // Set up message handling logic here
         // ...
         while let Ok(notification) = eventloop.poll().await {
             if let rumqttc::Event::Incoming(Packet::Publish(published)) = notification {
                 let span = span!(Level::TRACE, "Handle MQTT Event");
                 let _enter = span.enter();
-                match self.router(&published, &outbound).await {
+                match self.router(&published, &outbound, &publisher).await {
                     Ok(_) => trace!(result = "success"),
                     Err(HandlerError::ParsingError(err)) => {
                         warn!(result = "warn", details = format!("Parsing error occured: {}", err))
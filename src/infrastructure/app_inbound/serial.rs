@@ -4,7 +4,10 @@ use tokio::io;
 use tracing::warn;
 
 use crate::application::ports::app::{AppInbound, AppOutbound};
-use crate::infrastructure::serial::inbound::handle_event::handle_event;
+use crate::infrastructure::serial::inbound::{
+    handle_action_ack::{ACTION_ACK_NAME, handle_action_ack},
+    handle_event::handle_event,
+};
 pub struct SerialAppInbound {
     port_name: String,
     baud_rate: u32,
@@ -56,7 +59,8 @@ impl AppInbound for SerialAppInbound {
                         }
                         let line_str = String::from_utf8_lossy(&line);
 
-                        let splitted_data: Vec<&str> = line_str.split(";").collect();
+                        // the payload may itself contain the frame separator
+                        let splitted_data: Vec<&str> = line_str.splitn(3, ";").collect();
                         let id = match splitted_data.get(0) {
                             Some(id) => id.to_string(),
                             None => {
@@ -78,7 +82,11 @@ impl AppInbound for SerialAppInbound {
                                 continue;
                             }
                         };
-                        handle_event(outbound.clone(), &id, &event_name, &payload).await;
+                        if event_name == ACTION_ACK_NAME {
+                            handle_action_ack(outbound.clone(), &id, &payload).await;
+                        } else {
+                            handle_event(outbound.clone(), &id, &event_name, &payload).await;
+                        }
                     }
                 }
                 Ok(_) => {
@@ -7,18 +7,31 @@ use crate::{
         ports::{
             app::AppOutbound,
             outbound::{
-                action_repository::{CreateActionRepository, HandleActionRepository}, device_repository::{
+                action_repository::{CreateActionRepository, HandleActionRepository, ScheduledActionRepository, UpdateActionRepository}, device_group_repository::{
+                    CreateDeviceGroupRepository, DeleteDeviceGroupRepository,
+                    GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+                },
+                device_model_repository::{
+                    CreateDeviceModelRepository, DeleteDeviceModelRepository,
+                    GetDeviceModelRepository, UpdateDeviceModelRepository,
+                }, device_repository::{
                     CreateDeviceRepository, DeleteDeviceRepository, GetDeviceRepository,
                     UpdateDeviceRepository,
-                }, device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, GetDeviceStateRepository, UpdateDeviceStateRepository}, event_repository::{CreateEventRepository, GetEventRepository}
+                }, device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, GetDeviceStateRepository, UpdateDeviceStateRepository}, event_repository::{CreateEventRepository, GetEventRepository},
+                rule_repository::{
+                    CreateRuleRepository, DeleteRuleRepository, GetRuleRepository,
+                    UpdateRuleRepository,
+                }
             },
         },
         usecases::{
-            manage_action::ManageActionService, manage_device::ManageDeviceService, manage_device_state::ManageDeviceStateService, manage_event::ManageEventService
+            manage_action::ManageActionService, manage_device::ManageDeviceService, manage_device_group::ManageDeviceGroupService,
+            manage_device_model::ManageDeviceModelService, manage_device_state::ManageDeviceStateService, manage_event::ManageEventService,
+            manage_rule::ManageRuleService
         },
     },
     infrastructure::{db::postgres::{
-        action_repository::PostgresActionRepository, device_repository::PostgresDeviceRepository, device_state_repository::PostgresDeviceStateRepository, event_repository::PostgresEventRepository
+        action_repository::PostgresActionRepository, device_group_repository::PostgresDeviceGroupRepository, device_model_repository::PostgresDeviceModelRepository, device_repository::PostgresDeviceRepository, device_state_repository::PostgresDeviceStateRepository, event_repository::PostgresEventRepository, rule_repository::PostgresRuleRepository
     }, utils},
 };
 
@@ -30,22 +43,52 @@ pub struct FullPostgresAppOutbound {
             PostgresDeviceRepository,
             PostgresDeviceRepository,
             PostgresDeviceRepository,
+            PostgresDeviceModelRepository,
+        >,
+    >,
+    device_model_service: Arc<
+        ManageDeviceModelService<
+            PostgresDeviceModelRepository,
+            PostgresDeviceModelRepository,
+            PostgresDeviceModelRepository,
+            PostgresDeviceModelRepository,
+            PostgresDeviceRepository,
+            PostgresDeviceRepository,
+        >,
+    >,
+    device_group_service: Arc<
+        ManageDeviceGroupService<
+            PostgresDeviceGroupRepository,
+            PostgresDeviceGroupRepository,
+            PostgresDeviceGroupRepository,
+            PostgresDeviceGroupRepository,
         >,
     >,
     device_state_service: Arc<ManageDeviceStateService<PostgresDeviceStateRepository, PostgresDeviceStateRepository, PostgresDeviceStateRepository, PostgresDeviceStateRepository>>,
     device_events_service:
         Arc<ManageEventService<PostgresEventRepository, PostgresEventRepository>>,
     device_actions_service:
-        Arc<ManageActionService<PostgresActionRepository, PostgresActionRepository>>
+        Arc<ManageActionService<PostgresActionRepository, PostgresActionRepository, PostgresActionRepository, PostgresActionRepository>>,
+    rule_service: Arc<
+        ManageRuleService<
+            PostgresRuleRepository,
+            PostgresRuleRepository,
+            PostgresRuleRepository,
+            PostgresRuleRepository,
+        >,
+    >,
 }
 
 impl Clone for FullPostgresAppOutbound {
     fn clone(&self) -> Self {
         FullPostgresAppOutbound {
             device_service: Arc::clone(&self.device_service),
+            device_model_service: Arc::clone(&self.device_model_service),
+            device_group_service: Arc::clone(&self.device_group_service),
             device_state_service: Arc::clone(&self.device_state_service),
             device_events_service: Arc::clone(&self.device_events_service),
-            device_actions_service: Arc::clone(&self.device_actions_service)
+            device_actions_service: Arc::clone(&self.device_actions_service),
+            rule_service: Arc::clone(&self.rule_service),
         }
     }
 }
@@ -58,25 +101,49 @@ impl FullPostgresAppOutbound {
         let postgres_config = utils::load_postgres_config_from_env()?;
         let pool = utils::create_pool(postgres_config).await;
         let device_repo = PostgresDeviceRepository::new(pool.clone()).await;
+        let device_model_repo = PostgresDeviceModelRepository::new(pool.clone()).await;
+        let device_group_repo = PostgresDeviceGroupRepository::new(pool.clone()).await;
         let device_state_repo = PostgresDeviceStateRepository::new(pool.clone()).await;
         let event_repo = PostgresEventRepository::new(pool.clone()).await;
         let action_repo = PostgresActionRepository::new(pool.clone()).await;
+        let rule_repo = PostgresRuleRepository::new(pool.clone()).await;
 
         // Initialize the repositories
         device_repo.init().await;
+        device_model_repo.init().await;
+        device_group_repo.init().await;
         device_state_repo.init().await;
         event_repo.init().await;
         action_repo.init().await;
+        rule_repo.init().await;
 
         let arc_device_repo = Arc::new(device_repo);
+        let arc_device_model_repo = Arc::new(device_model_repo);
+        let arc_device_group_repo = Arc::new(device_group_repo);
         let arc_event_repo = Arc::new(event_repo);
         let arc_device_state_repo = Arc::new(device_state_repo);
         let arc_action_repo = Arc::new(Mutex::new(action_repo));
+        let arc_rule_repo = Arc::new(rule_repo);
         let device_service = Arc::new(ManageDeviceService {
             create_repo: arc_device_repo.clone(),
             get_repo: arc_device_repo.clone(),
             update_repo: arc_device_repo.clone(),
-            delete_repo: arc_device_repo,
+            delete_repo: arc_device_repo.clone(),
+            model_repo: arc_device_model_repo.clone(),
+        });
+        let device_model_service = Arc::new(ManageDeviceModelService {
+            create_repo: arc_device_model_repo.clone(),
+            get_repo: arc_device_model_repo.clone(),
+            update_repo: arc_device_model_repo.clone(),
+            delete_repo: arc_device_model_repo,
+            device_get_repo: arc_device_repo.clone(),
+            device_update_repo: arc_device_repo,
+        });
+        let device_group_service = Arc::new(ManageDeviceGroupService {
+            create_repo: arc_device_group_repo.clone(),
+            get_repo: arc_device_group_repo.clone(),
+            update_repo: arc_device_group_repo.clone(),
+            delete_repo: arc_device_group_repo,
         });
         let device_state_service = Arc::new(ManageDeviceStateService {
             create_repo: arc_device_state_repo.clone(),
@@ -90,14 +157,25 @@ impl FullPostgresAppOutbound {
         });
         let device_actions_service = Arc::new(ManageActionService {
             create_repo: arc_action_repo.clone(),
-            get_repo: arc_action_repo
+            get_repo: arc_action_repo.clone(),
+            update_repo: arc_action_repo.clone(),
+            scheduled_repo: arc_action_repo,
+        });
+        let rule_service = Arc::new(ManageRuleService {
+            create_repo: arc_rule_repo.clone(),
+            get_repo: arc_rule_repo.clone(),
+            update_repo: arc_rule_repo.clone(),
+            delete_repo: arc_rule_repo,
         });
 
         Ok(FullPostgresAppOutbound {
             device_service,
+            device_model_service,
+            device_group_service,
             device_state_service,
             device_events_service,
             device_actions_service,
+            rule_service,
         })
     }
 }
@@ -111,11 +189,40 @@ impl AppOutbound for FullPostgresAppOutbound {
             impl GetDeviceRepository,
             impl UpdateDeviceRepository,
             impl DeleteDeviceRepository,
+            impl GetDeviceModelRepository,
         >,
     > {
         &self.device_service
     }
 
+    fn get_device_model_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceModelService<
+            impl CreateDeviceModelRepository,
+            impl GetDeviceModelRepository,
+            impl UpdateDeviceModelRepository,
+            impl DeleteDeviceModelRepository,
+            impl GetDeviceRepository,
+            impl UpdateDeviceRepository,
+        >,
+    > {
+        &self.device_model_service
+    }
+
+    fn get_device_group_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceGroupService<
+            impl CreateDeviceGroupRepository,
+            impl GetDeviceGroupRepository,
+            impl UpdateDeviceGroupRepository,
+            impl DeleteDeviceGroupRepository,
+        >,
+    > {
+        &self.device_group_service
+    }
+
     fn get_device_state_service(
         &self,
     ) -> &Arc<ManageDeviceStateService<impl CreateDeviceStateRepository, impl GetDeviceStateRepository, impl UpdateDeviceStateRepository, impl DeleteDeviceStateRepository>> {
@@ -130,8 +237,21 @@ impl AppOutbound for FullPostgresAppOutbound {
     
     fn get_action_service(
         &self,
-    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository>> {
+    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository, impl UpdateActionRepository, impl ScheduledActionRepository>> {
         &self.device_actions_service
     }
+
+    fn get_rule_service(
+        &self,
+    ) -> &Arc<
+        ManageRuleService<
+            impl CreateRuleRepository,
+            impl GetRuleRepository,
+            impl UpdateRuleRepository,
+            impl DeleteRuleRepository,
+        >,
+    > {
+        &self.rule_service
+    }
 }
 
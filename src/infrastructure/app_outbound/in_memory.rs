@@ -7,7 +7,15 @@ use crate::{
         ports::{
             app::AppOutbound,
             outbound::{
-                action_repository::{CreateActionRepository, HandleActionRepository},
+                action_repository::{CreateActionRepository, HandleActionRepository, ScheduledActionRepository, UpdateActionRepository},
+                device_group_repository::{
+                    CreateDeviceGroupRepository, DeleteDeviceGroupRepository,
+                    GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+                },
+                device_model_repository::{
+                    CreateDeviceModelRepository, DeleteDeviceModelRepository,
+                    GetDeviceModelRepository, UpdateDeviceModelRepository,
+                },
                 device_repository::{
                     CreateDeviceRepository, DeleteDeviceRepository, GetDeviceRepository,
                     UpdateDeviceRepository,
@@ -17,17 +25,28 @@ use crate::{
                     GetDeviceStateRepository, UpdateDeviceStateRepository,
                 },
                 event_repository::{CreateEventRepository, GetEventRepository},
+                rule_repository::{
+                    CreateRuleRepository, DeleteRuleRepository, GetRuleRepository,
+                    UpdateRuleRepository,
+                },
             },
         },
         usecases::{
             manage_action::ManageActionService, manage_device::ManageDeviceService,
+            manage_device_group::ManageDeviceGroupService,
+            manage_device_model::ManageDeviceModelService,
             manage_device_state::ManageDeviceStateService, manage_event::ManageEventService,
+            manage_rule::ManageRuleService,
         },
     },
     infrastructure::db::memory::{
-        action_repository::InMemoryActionRepository, device_repository::InMemoryDeviceRepository,
+        action_repository::InMemoryActionRepository,
+        device_group_repository::InMemoryDeviceGroupRepository,
+        device_model_repository::InMemoryDeviceModelRepository,
+        device_repository::InMemoryDeviceRepository,
         device_state_repository::InMemoryDeviceStateRepository,
         event_repository::InMemoryEventRepository,
+        rule_repository::InMemoryRuleRepository,
     },
 };
 
@@ -39,6 +58,25 @@ pub struct InMemoryAppOutbound {
             InMemoryDeviceRepository,
             InMemoryDeviceRepository,
             InMemoryDeviceRepository,
+            InMemoryDeviceModelRepository,
+        >,
+    >,
+    device_model_service: Arc<
+        ManageDeviceModelService<
+            InMemoryDeviceModelRepository,
+            InMemoryDeviceModelRepository,
+            InMemoryDeviceModelRepository,
+            InMemoryDeviceModelRepository,
+            InMemoryDeviceRepository,
+            InMemoryDeviceRepository,
+        >,
+    >,
+    device_group_service: Arc<
+        ManageDeviceGroupService<
+            InMemoryDeviceGroupRepository,
+            InMemoryDeviceGroupRepository,
+            InMemoryDeviceGroupRepository,
+            InMemoryDeviceGroupRepository,
         >,
     >,
     device_state_service: Arc<
@@ -52,26 +90,55 @@ pub struct InMemoryAppOutbound {
     device_events_service:
         Arc<ManageEventService<InMemoryEventRepository, InMemoryEventRepository>>,
     device_actions_service:
-        Arc<ManageActionService<InMemoryActionRepository, InMemoryActionRepository>>,
+        Arc<ManageActionService<InMemoryActionRepository, InMemoryActionRepository, InMemoryActionRepository, InMemoryActionRepository>>,
+    rule_service: Arc<
+        ManageRuleService<
+            InMemoryRuleRepository,
+            InMemoryRuleRepository,
+            InMemoryRuleRepository,
+            InMemoryRuleRepository,
+        >,
+    >,
 }
 
 impl InMemoryAppOutbound {
     pub fn new() -> Self {
         let device_repo = InMemoryDeviceRepository::new();
+        let device_model_repo = InMemoryDeviceModelRepository::new();
+        let device_group_repo = InMemoryDeviceGroupRepository::new();
         let device_state_repo = InMemoryDeviceStateRepository::new();
         let event_repo = InMemoryEventRepository::new();
         let action_repo = InMemoryActionRepository::new();
+        let rule_repo = InMemoryRuleRepository::new();
 
         let arc_event_repo = Arc::new(event_repo);
         let arc_device_repo = Arc::new(device_repo);
+        let arc_device_model_repo = Arc::new(device_model_repo);
+        let arc_device_group_repo = Arc::new(device_group_repo);
         let arc_device_state_repo = Arc::new(device_state_repo);
         let arc_action_repo = Arc::new(Mutex::new(action_repo));
+        let arc_rule_repo = Arc::new(rule_repo);
 
         let device_service = Arc::new(ManageDeviceService {
             create_repo: arc_device_repo.clone(),
             get_repo: arc_device_repo.clone(),
             update_repo: arc_device_repo.clone(),
-            delete_repo: arc_device_repo,
+            delete_repo: arc_device_repo.clone(),
+            model_repo: arc_device_model_repo.clone(),
+        });
+        let device_model_service = Arc::new(ManageDeviceModelService {
+            create_repo: arc_device_model_repo.clone(),
+            get_repo: arc_device_model_repo.clone(),
+            update_repo: arc_device_model_repo.clone(),
+            delete_repo: arc_device_model_repo,
+            device_get_repo: arc_device_repo.clone(),
+            device_update_repo: arc_device_repo,
+        });
+        let device_group_service = Arc::new(ManageDeviceGroupService {
+            create_repo: arc_device_group_repo.clone(),
+            get_repo: arc_device_group_repo.clone(),
+            update_repo: arc_device_group_repo.clone(),
+            delete_repo: arc_device_group_repo,
         });
         let device_state_service = Arc::new(ManageDeviceStateService {
             create_repo: arc_device_state_repo.clone(),
@@ -85,13 +152,24 @@ impl InMemoryAppOutbound {
         });
         let device_actions_service = Arc::new(ManageActionService {
             create_repo: arc_action_repo.clone(),
-            get_repo: arc_action_repo,
+            get_repo: arc_action_repo.clone(),
+            update_repo: arc_action_repo.clone(),
+            scheduled_repo: arc_action_repo,
+        });
+        let rule_service = Arc::new(ManageRuleService {
+            create_repo: arc_rule_repo.clone(),
+            get_repo: arc_rule_repo.clone(),
+            update_repo: arc_rule_repo.clone(),
+            delete_repo: arc_rule_repo,
         });
         InMemoryAppOutbound {
             device_service,
+            device_model_service,
+            device_group_service,
             device_state_service,
             device_events_service,
             device_actions_service,
+            rule_service,
         }
     }
 }
@@ -105,11 +183,40 @@ impl AppOutbound for InMemoryAppOutbound {
             impl GetDeviceRepository,
             impl UpdateDeviceRepository,
             impl DeleteDeviceRepository,
+            impl GetDeviceModelRepository,
         >,
     > {
         return &self.device_service;
     }
 
+    fn get_device_model_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceModelService<
+            impl CreateDeviceModelRepository,
+            impl GetDeviceModelRepository,
+            impl UpdateDeviceModelRepository,
+            impl DeleteDeviceModelRepository,
+            impl GetDeviceRepository,
+            impl UpdateDeviceRepository,
+        >,
+    > {
+        &self.device_model_service
+    }
+
+    fn get_device_group_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceGroupService<
+            impl CreateDeviceGroupRepository,
+            impl GetDeviceGroupRepository,
+            impl UpdateDeviceGroupRepository,
+            impl DeleteDeviceGroupRepository,
+        >,
+    > {
+        &self.device_group_service
+    }
+
     fn get_device_state_service(
         &self,
     ) -> &Arc<
@@ -131,7 +238,20 @@ impl AppOutbound for InMemoryAppOutbound {
 
     fn get_action_service(
         &self,
-    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository>> {
+    ) -> &Arc<ManageActionService<impl CreateActionRepository, impl HandleActionRepository, impl UpdateActionRepository, impl ScheduledActionRepository>> {
         return &self.device_actions_service;
     }
+
+    fn get_rule_service(
+        &self,
+    ) -> &Arc<
+        ManageRuleService<
+            impl CreateRuleRepository,
+            impl GetRuleRepository,
+            impl UpdateRuleRepository,
+            impl DeleteRuleRepository,
+        >,
+    > {
+        &self.rule_service
+    }
 }
@@ -1,38 +1,60 @@
-use std::{collections::HashMap, env::VarError, str::FromStr, sync::Arc};
-use chrono::DateTime;
+use std::{collections::{HashMap, VecDeque}, env::VarError, str::FromStr, sync::Arc};
+use chrono::{DateTime, Utc};
 use rumqttc::Packet;
 use serde_json::Value;
 use tokio::sync::Mutex;
 use tracing::error;
+use uuid::Uuid;
 
 use crate::{
     application::{
         ports::{
             app::AppOutbound,
             outbound::{
-                action_repository::{ActionRepositoryError, HandleActionRepository}, device_repository::{
+                action_repository::{ActionRepositoryError, HandleActionRepository, ScheduledActionRepository}, device_group_repository::{
+                    CreateDeviceGroupRepository, DeleteDeviceGroupRepository,
+                    GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+                },
+                device_model_repository::{
+                    CreateDeviceModelRepository, DeleteDeviceModelRepository,
+                    GetDeviceModelRepository, UpdateDeviceModelRepository,
+                }, device_repository::{
                     CreateDeviceRepository, DeleteDeviceRepository, GetDeviceRepository,
                     UpdateDeviceRepository,
                 }, device_state_repository::{
                     CreateDeviceStateRepository, DeleteDeviceStateRepository,
                     GetDeviceStateRepository, UpdateDeviceStateRepository,
-                }, event_repository::{CreateEventRepository, GetEventRepository}
+                }, event_repository::{CreateEventRepository, GetEventRepository},
+                rule_repository::{
+                    CreateRuleRepository, DeleteRuleRepository, GetRuleRepository,
+                    UpdateRuleRepository,
+                }
             },
         },
         usecases::{
             manage_action::ManageActionService, manage_device::ManageDeviceService,
+            manage_device_group::ManageDeviceGroupService,
+            manage_device_model::ManageDeviceModelService,
             manage_device_state::ManageDeviceStateService, manage_event::ManageEventService,
+            manage_rule::ManageRuleService,
         },
-    }, domain::action::{action::Action, action_format::ActionFormat}, infrastructure::{
+    }, domain::action::{action::Action, action_format::ActionFormat, action_schedule::{ActionSchedule, ScheduleRun}}, infrastructure::{
         http::reqwest::{
+            device_group_repository::ReqwestDeviceGroupRepository,
+            device_model_repository::ReqwestDeviceModelRepository,
             device_repository::ReqwestDeviceRepository,
             device_state_repository::ReqwestDeviceStateRepository,
             event_repository::ReqwestEventRepository,
+            rule_repository::ReqwestRuleRepository,
         },
         mqtt::{mqtt_messages::{CreateActionPayload, MqttActionType, MqttMessage}, outbound::{
-            action_repository::MqttActionRepository, device_repository::MqttDeviceRepository,
+            action_repository::MqttActionRepository,
+            device_group_repository::MqttDeviceGroupRepository,
+            device_model_repository::MqttDeviceModelRepository,
+            device_repository::MqttDeviceRepository,
             device_state_repository::MqttDeviceStateRepository,
             event_repository::MqttEventRepository,
+            rule_repository::MqttRuleRepository,
         }},
         utils::{self},
     }
@@ -46,6 +68,25 @@ pub struct MqttHttpAppOutbound {
             ReqwestDeviceRepository,
             MqttDeviceRepository,
             MqttDeviceRepository,
+            ReqwestDeviceModelRepository,
+        >,
+    >,
+    device_model_service: Arc<
+        ManageDeviceModelService<
+            MqttDeviceModelRepository,
+            ReqwestDeviceModelRepository,
+            MqttDeviceModelRepository,
+            MqttDeviceModelRepository,
+            ReqwestDeviceRepository,
+            MqttDeviceRepository,
+        >,
+    >,
+    device_group_service: Arc<
+        ManageDeviceGroupService<
+            MqttDeviceGroupRepository,
+            ReqwestDeviceGroupRepository,
+            MqttDeviceGroupRepository,
+            MqttDeviceGroupRepository,
         >,
     >,
     device_state_service: Arc<
@@ -57,16 +98,27 @@ pub struct MqttHttpAppOutbound {
         >,
     >,
     device_events_service: Arc<ManageEventService<MqttEventRepository, ReqwestEventRepository>>,
-    device_actions_service: Arc<ManageActionService<MqttActionRepository, LocalActionRepository>>,
+    device_actions_service: Arc<ManageActionService<MqttActionRepository, LocalActionRepository, MqttActionRepository, LocalActionRepository>>,
+    rule_service: Arc<
+        ManageRuleService<
+            MqttRuleRepository,
+            ReqwestRuleRepository,
+            MqttRuleRepository,
+            MqttRuleRepository,
+        >,
+    >,
 }
 
 impl Clone for MqttHttpAppOutbound {
     fn clone(&self) -> Self {
         Self {
             device_service: Arc::clone(&self.device_service),
+            device_model_service: Arc::clone(&self.device_model_service),
+            device_group_service: Arc::clone(&self.device_group_service),
             device_state_service: Arc::clone(&self.device_state_service),
             device_events_service: Arc::clone(&self.device_events_service),
             device_actions_service: Arc::clone(&self.device_actions_service),
+            rule_service: Arc::clone(&self.rule_service),
         }
     }
 }
@@ -82,10 +134,16 @@ impl MqttHttpAppOutbound {
 
         let mqtt_device_repo =
             MqttDeviceRepository::new(mqtt_client.clone(), &mqtt_config.device_topic);
+        let mqtt_device_model_repo =
+            MqttDeviceModelRepository::new(mqtt_client.clone(), &mqtt_config.device_model_topic);
+        let mqtt_device_group_repo =
+            MqttDeviceGroupRepository::new(mqtt_client.clone(), &mqtt_config.device_group_topic);
         let mqtt_device_state_repo =
             MqttDeviceStateRepository::new(mqtt_client.clone(), &mqtt_config.device_state_topic);
         let mqtt_event_repo =
             MqttEventRepository::new(mqtt_client.clone(), &mqtt_config.event_topic);
+        let mqtt_rule_repo =
+            MqttRuleRepository::new(mqtt_client.clone(), &mqtt_config.rule_topic);
         let mqtt_action_repo =
             MqttActionRepository::new(mqtt_client, &mqtt_config.action_topic);
         let local_action_repo = LocalActionRepository::new();
@@ -100,6 +158,14 @@ impl MqttHttpAppOutbound {
             &http_config.device_update_path.unwrap_or_default(),
             &http_config.device_delete_path.unwrap_or_default(),
         );
+        let http_device_model_repo = ReqwestDeviceModelRepository::new(
+            &http_config.base_url,
+            &http_config.device_model_get_path.unwrap_or_default(),
+        );
+        let http_device_group_repo = ReqwestDeviceGroupRepository::new(
+            &http_config.base_url,
+            &http_config.device_group_get_path.unwrap_or_default(),
+        );
         let http_device_state_repo = ReqwestDeviceStateRepository::new(
             &http_config.base_url,
             &http_config.device_state_create_path.unwrap_or_default(),
@@ -107,6 +173,10 @@ impl MqttHttpAppOutbound {
             &http_config.device_state_update_path.unwrap_or_default(),
             &http_config.device_state_delete_path.unwrap_or_default(),
         );
+        let http_rule_repo = ReqwestRuleRepository::new(
+            &http_config.base_url,
+            &http_config.rule_get_path.unwrap_or_default(),
+        );
         let http_event_repo = ReqwestEventRepository::new(
             &http_config.base_url,
             &http_config.event_create_path.unwrap_or_default(),
@@ -114,18 +184,39 @@ impl MqttHttpAppOutbound {
         );
 
         let arc_mqtt_device_repo = Arc::new(mqtt_device_repo);
+        let arc_mqtt_device_model_repo = Arc::new(mqtt_device_model_repo);
+        let arc_mqtt_device_group_repo = Arc::new(mqtt_device_group_repo);
         let arc_mqtt_event_repo = Arc::new(mqtt_event_repo);
         let arc_mqtt_device_state_repo = Arc::new(mqtt_device_state_repo);
         let arc_mqtt_action_repo = Arc::new(Mutex::new(mqtt_action_repo));
+        let arc_mqtt_rule_repo = Arc::new(mqtt_rule_repo);
         let arc_local_action_repo = Arc::new(Mutex::new(local_action_repo));
         let arc_http_device_repo = Arc::new(http_device_repo);
+        let arc_http_device_model_repo = Arc::new(http_device_model_repo);
+        let arc_http_device_group_repo = Arc::new(http_device_group_repo);
         let arc_http_event_repo = Arc::new(http_event_repo);
         let arc_http_device_state_repo = Arc::new(http_device_state_repo);
+        let arc_http_rule_repo = Arc::new(http_rule_repo);
         let device_service = Arc::new(ManageDeviceService {
             create_repo: arc_mqtt_device_repo.clone(),
-            get_repo: arc_http_device_repo,
+            get_repo: arc_http_device_repo.clone(),
             update_repo: arc_mqtt_device_repo.clone(),
-            delete_repo: arc_mqtt_device_repo,
+            delete_repo: arc_mqtt_device_repo.clone(),
+            model_repo: arc_http_device_model_repo.clone(),
+        });
+        let device_model_service = Arc::new(ManageDeviceModelService {
+            create_repo: arc_mqtt_device_model_repo.clone(),
+            get_repo: arc_http_device_model_repo,
+            update_repo: arc_mqtt_device_model_repo.clone(),
+            delete_repo: arc_mqtt_device_model_repo,
+            device_get_repo: arc_http_device_repo,
+            device_update_repo: arc_mqtt_device_repo,
+        });
+        let device_group_service = Arc::new(ManageDeviceGroupService {
+            create_repo: arc_mqtt_device_group_repo.clone(),
+            get_repo: arc_http_device_group_repo,
+            update_repo: arc_mqtt_device_group_repo.clone(),
+            delete_repo: arc_mqtt_device_group_repo,
         });
         let device_state_service = Arc::new(ManageDeviceStateService {
             create_repo: arc_mqtt_device_state_repo.clone(),
@@ -138,8 +229,16 @@ impl MqttHttpAppOutbound {
             get_repo: arc_http_event_repo,
         });
         let device_actions_service = Arc::new(ManageActionService {
-            create_repo: arc_mqtt_action_repo,
+            create_repo: arc_mqtt_action_repo.clone(),
             get_repo: arc_local_action_repo.clone(),
+            update_repo: arc_mqtt_action_repo,
+            scheduled_repo: arc_local_action_repo.clone(),
+        });
+        let rule_service = Arc::new(ManageRuleService {
+            create_repo: arc_mqtt_rule_repo.clone(),
+            get_repo: arc_http_rule_repo,
+            update_repo: arc_mqtt_rule_repo.clone(),
+            delete_repo: arc_mqtt_rule_repo,
         });
         let action_topic_cloned = mqtt_config.action_topic.clone();
         let device_id_cloned = "abc".to_string();
@@ -182,24 +281,36 @@ impl MqttHttpAppOutbound {
                                         continue;
                                     }
                                 };
-                                let action_data = match ActionFormat::Json
-                                    .decode_action(payload.action_data.as_bytes())
+                                let action_format = match ActionFormat::from_parts(payload.action_format.as_deref().unwrap_or("json"), payload.action_binary_layout.clone()) {
+                                    Ok(f) => f,
+                                    Err(e) => {
+                                        error!(result = "error", details = e);
+                                        continue;
+                                    }
+                                };
+                                let action_data = match utils::payload_from_text(&payload.action_data, action_format.is_binary())
+                                    .map_err(|e| e.to_string())
+                                    .and_then(|data| action_format.decode_action(&data).map_err(|e| e.to_string()))
                                 {
                                     Ok(a) => a,
-                                    Err(_) => {
+                                    Err(e) => {
                                         error!(
                                             result = "error",
-                                            details = "invalid timestamp format".to_string()
+                                            details = format!("invalid action data: {}", e)
                                         );
                                         continue;
                                     }
                                 };
-                                let action = Action::new(
+                                let mut action = Action::new(
                                     payload.device_id.clone(),
-                                    &payload.action_data,
+                                    &payload.device_action_name,
                                     &timestamp,
                                     action_data,
                                 );
+                                if let Some(action_id) = payload.action_id.as_deref().and_then(|id| Uuid::from_str(id).ok()) {
+                                    action.id = action_id;
+                                }
+                                action.expires_at = payload.expires_at.as_deref().and_then(|expires_at| DateTime::from_str(expires_at).ok());
                                 let mut local_action_repo = arc_local_action_repo.lock().await;
                                 local_action_repo.add_pending_action(&payload.device_id, action).await;
                             }
@@ -212,9 +323,12 @@ impl MqttHttpAppOutbound {
 
         Ok(MqttHttpAppOutbound {
             device_service,
+            device_model_service,
+            device_group_service,
             device_state_service,
             device_events_service,
             device_actions_service,
+            rule_service,
         })
     }
 }
@@ -247,32 +361,87 @@ impl AppOutbound for MqttHttpAppOutbound {
             impl GetDeviceRepository,
             impl UpdateDeviceRepository,
             impl DeleteDeviceRepository,
+            impl GetDeviceModelRepository,
         >,
     > {
         &self.device_service
     }
 
+    fn get_device_model_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceModelService<
+            impl CreateDeviceModelRepository,
+            impl GetDeviceModelRepository,
+            impl UpdateDeviceModelRepository,
+            impl DeleteDeviceModelRepository,
+            impl GetDeviceRepository,
+            impl UpdateDeviceRepository,
+        >,
+    > {
+        &self.device_model_service
+    }
+
+    fn get_device_group_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceGroupService<
+            impl CreateDeviceGroupRepository,
+            impl GetDeviceGroupRepository,
+            impl UpdateDeviceGroupRepository,
+            impl DeleteDeviceGroupRepository,
+        >,
+    > {
+        &self.device_group_service
+    }
+
     fn get_action_service(
         &self,
     ) -> &Arc<
         crate::application::usecases::manage_action::ManageActionService<
             impl crate::application::ports::outbound::action_repository::CreateActionRepository,
             impl crate::application::ports::outbound::action_repository::HandleActionRepository,
+            impl crate::application::ports::outbound::action_repository::UpdateActionRepository,
+            impl crate::application::ports::outbound::action_repository::ScheduledActionRepository,
         >,
     > {
         &self.device_actions_service
     }
+
+    fn get_rule_service(
+        &self,
+    ) -> &Arc<
+        ManageRuleService<
+            impl CreateRuleRepository,
+            impl GetRuleRepository,
+            impl UpdateRuleRepository,
+            impl DeleteRuleRepository,
+        >,
+    > {
+        &self.rule_service
+    }
 }
 
+// delivered actions kept for their acknowledgements
+const MAX_DELIVERED_ACTIONS: usize = 1024;
+
 #[derive(Debug)]
 struct LocalActionRepository {
-    pending_actions: HashMap<String, Vec<Action>>
+    pending_actions: HashMap<String, Vec<Action>>,
+    delivered_actions: VecDeque<Action>,
+    scheduled_actions: std::sync::Mutex<HashMap<Uuid, (Action, ActionFormat)>>,
+    schedules: std::sync::Mutex<HashMap<Uuid, (ActionSchedule, ActionFormat)>>,
+    schedule_runs: std::sync::Mutex<HashMap<Uuid, Vec<ScheduleRun>>>,
 }
 
 impl LocalActionRepository {
     fn new() -> Self {
         Self {
-            pending_actions: HashMap::new()
+            pending_actions: HashMap::new(),
+            delivered_actions: VecDeque::new(),
+            scheduled_actions: std::sync::Mutex::new(HashMap::new()),
+            schedules: std::sync::Mutex::new(HashMap::new()),
+            schedule_runs: std::sync::Mutex::new(HashMap::new()),
         }
     }
     async fn add_pending_action(&mut self, device_id: &str, action: Action) {
@@ -289,7 +458,136 @@ impl HandleActionRepository for LocalActionRepository {
         &mut self,
         device_id: &str,
     ) -> Result<Vec<Action>, ActionRepositoryError> {
-        let actions = self.pending_actions.remove(device_id).unwrap_or_default();
+        let mut actions = self.pending_actions.remove(device_id).unwrap_or_default();
+        // devices waking up late must not run stale actions
+        let now = Utc::now();
+        actions.retain(|action| !action.is_expired(&now));
+        self.delivered_actions.extend(actions.iter().cloned());
+        while self.delivered_actions.len() > MAX_DELIVERED_ACTIONS {
+            self.delivered_actions.pop_front();
+        }
+        Ok(actions)
+    }
+
+    async fn get_action(&mut self, action_id: Uuid) -> Result<Option<Action>, ActionRepositoryError> {
+        let action = self
+            .pending_actions
+            .values()
+            .flatten()
+            .chain(self.delivered_actions.iter())
+            .find(|action| action.id == action_id);
+        Ok(action.cloned())
+    }
+
+    async fn get_expired_actions(&mut self, now: &DateTime<Utc>) -> Result<Vec<Action>, ActionRepositoryError> {
+        Ok(self
+            .pending_actions
+            .values()
+            .flatten()
+            .filter(|action| action.is_overdue(now))
+            .cloned()
+            .collect())
+    }
+}
+
+// actions and schedules are held until due, then published
+impl ScheduledActionRepository for LocalActionRepository {
+    async fn schedule_action(&self, action: Action, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let mut scheduled = self.scheduled_actions.lock().unwrap();
+        scheduled.insert(action.id, (action, action_format.clone()));
+        Ok(())
+    }
+
+    async fn get_scheduled_actions(&self, device_id: Option<&str>) -> Result<Vec<Action>, ActionRepositoryError> {
+        let scheduled = self.scheduled_actions.lock().unwrap();
+        let mut actions: Vec<Action> = scheduled
+            .values()
+            .map(|(action, _)| action)
+            .filter(|action| device_id.is_none_or(|device_id| action.device_id == device_id))
+            .cloned()
+            .collect();
+        actions.sort_by_key(|action| action.scheduled_at);
         Ok(actions)
     }
-}
\ No newline at end of file
+
+    async fn get_due_actions(&self, now: &DateTime<Utc>) -> Result<Vec<(Action, ActionFormat)>, ActionRepositoryError> {
+        let scheduled = self.scheduled_actions.lock().unwrap();
+        Ok(scheduled
+            .values()
+            .filter(|(action, _)| action.scheduled_at.is_none_or(|scheduled_at| scheduled_at <= *now))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_scheduled_action(&self, action_id: Uuid) -> Result<Action, ActionRepositoryError> {
+        let mut scheduled = self.scheduled_actions.lock().unwrap();
+        scheduled
+            .remove(&action_id)
+            .map(|(action, _)| action)
+            .ok_or(ActionRepositoryError::NotFound)
+    }
+
+    // actions only reach the server once published, those not sent are dropped
+    async fn retire_scheduled_action(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        let mut scheduled = self.scheduled_actions.lock().unwrap();
+        scheduled
+            .remove(&action.id)
+            .map(|_| ())
+            .ok_or(ActionRepositoryError::NotFound)
+    }
+
+    async fn create_action_schedule(&self, schedule: ActionSchedule, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.insert(schedule.id, (schedule, action_format.clone()));
+        Ok(())
+    }
+
+    async fn get_action_schedules(&self, device_id: Option<&str>) -> Result<Vec<ActionSchedule>, ActionRepositoryError> {
+        let schedules = self.schedules.lock().unwrap();
+        let mut result: Vec<ActionSchedule> = schedules
+            .values()
+            .map(|(schedule, _)| schedule)
+            .filter(|schedule| device_id.is_none_or(|device_id| schedule.device_id == device_id))
+            .cloned()
+            .collect();
+        result.sort_by_key(|schedule| schedule.created_at);
+        Ok(result)
+    }
+
+    async fn get_due_action_schedules(&self, now: &DateTime<Utc>) -> Result<Vec<(ActionSchedule, ActionFormat)>, ActionRepositoryError> {
+        let schedules = self.schedules.lock().unwrap();
+        Ok(schedules
+            .values()
+            .filter(|(schedule, _)| schedule.next_run_at.is_some_and(|next_run_at| next_run_at <= *now))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_action_schedule_next_run(&self, schedule_id: Uuid, next_run_at: Option<DateTime<Utc>>) -> Result<(), ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let (schedule, _) = schedules.get_mut(&schedule_id).ok_or(ActionRepositoryError::NotFound)?;
+        schedule.next_run_at = next_run_at;
+        Ok(())
+    }
+
+    async fn delete_action_schedule(&self, schedule_id: Uuid) -> Result<ActionSchedule, ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let (schedule, _) = schedules.remove(&schedule_id).ok_or(ActionRepositoryError::NotFound)?;
+        self.schedule_runs.lock().unwrap().remove(&schedule_id);
+        Ok(schedule)
+    }
+
+    async fn add_schedule_run(&self, run: ScheduleRun) -> Result<(), ActionRepositoryError> {
+        let mut runs = self.schedule_runs.lock().unwrap();
+        runs.entry(run.schedule_id).or_default().push(run);
+        Ok(())
+    }
+
+    async fn get_schedule_runs(&self, schedule_id: Uuid) -> Result<Vec<ScheduleRun>, ActionRepositoryError> {
+        if !self.schedules.lock().unwrap().contains_key(&schedule_id) {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        let runs = self.schedule_runs.lock().unwrap();
+        Ok(runs.get(&schedule_id).map(|runs| runs.iter().rev().cloned().collect()).unwrap_or_default())
+    }
+}
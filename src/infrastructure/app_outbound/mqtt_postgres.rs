@@ -6,6 +6,14 @@ use crate::{
         ports::{
             app::AppOutbound,
             outbound::{
+                device_group_repository::{
+                    CreateDeviceGroupRepository, DeleteDeviceGroupRepository,
+                    GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+                },
+                device_model_repository::{
+                    CreateDeviceModelRepository, DeleteDeviceModelRepository,
+                    GetDeviceModelRepository, UpdateDeviceModelRepository,
+                },
                 device_repository::{
                     CreateDeviceRepository, DeleteDeviceRepository, GetDeviceRepository,
                     UpdateDeviceRepository,
@@ -15,24 +23,38 @@ use crate::{
                     GetDeviceStateRepository, UpdateDeviceStateRepository,
                 },
                 event_repository::{CreateEventRepository, GetEventRepository},
+                rule_repository::{
+                    CreateRuleRepository, DeleteRuleRepository, GetRuleRepository,
+                    UpdateRuleRepository,
+                },
             },
         },
         usecases::{
             manage_action::ManageActionService, manage_device::ManageDeviceService,
+            manage_device_group::ManageDeviceGroupService,
+            manage_device_model::ManageDeviceModelService,
             manage_device_state::ManageDeviceStateService, manage_event::ManageEventService,
+            manage_rule::ManageRuleService,
         },
     },
     infrastructure::{
         db::postgres::{
             action_repository::PostgresActionRepository,
+            device_group_repository::PostgresDeviceGroupRepository,
+            device_model_repository::PostgresDeviceModelRepository,
             device_repository::PostgresDeviceRepository,
             device_state_repository::PostgresDeviceStateRepository,
             event_repository::PostgresEventRepository,
+            rule_repository::PostgresRuleRepository,
         },
         mqtt::outbound::{
-            action_repository::MqttActionRepository, device_repository::MqttDeviceRepository,
+            action_repository::MqttActionRepository,
+            device_group_repository::MqttDeviceGroupRepository,
+            device_model_repository::MqttDeviceModelRepository,
+            device_repository::MqttDeviceRepository,
             device_state_repository::MqttDeviceStateRepository,
             event_repository::MqttEventRepository,
+            rule_repository::MqttRuleRepository,
         },
         utils,
     },
@@ -46,6 +68,25 @@ pub struct MqttAppOutbound {
             PostgresDeviceRepository,
             MqttDeviceRepository,
             MqttDeviceRepository,
+            PostgresDeviceModelRepository,
+        >,
+    >,
+    device_model_service: Arc<
+        ManageDeviceModelService<
+            MqttDeviceModelRepository,
+            PostgresDeviceModelRepository,
+            MqttDeviceModelRepository,
+            MqttDeviceModelRepository,
+            PostgresDeviceRepository,
+            MqttDeviceRepository,
+        >,
+    >,
+    device_group_service: Arc<
+        ManageDeviceGroupService<
+            MqttDeviceGroupRepository,
+            PostgresDeviceGroupRepository,
+            MqttDeviceGroupRepository,
+            MqttDeviceGroupRepository,
         >,
     >,
     device_state_service: Arc<
@@ -58,16 +99,27 @@ pub struct MqttAppOutbound {
     >,
     device_events_service: Arc<ManageEventService<MqttEventRepository, PostgresEventRepository>>,
     device_actions_service:
-        Arc<ManageActionService<MqttActionRepository, PostgresActionRepository>>,
+        Arc<ManageActionService<MqttActionRepository, PostgresActionRepository, MqttActionRepository, PostgresActionRepository>>,
+    rule_service: Arc<
+        ManageRuleService<
+            MqttRuleRepository,
+            PostgresRuleRepository,
+            MqttRuleRepository,
+            MqttRuleRepository,
+        >,
+    >,
 }
 
 impl Clone for MqttAppOutbound {
     fn clone(&self) -> Self {
         Self {
             device_service: Arc::clone(&self.device_service),
+            device_model_service: Arc::clone(&self.device_model_service),
+            device_group_service: Arc::clone(&self.device_group_service),
             device_state_service: Arc::clone(&self.device_state_service),
             device_events_service: Arc::clone(&self.device_events_service),
             device_actions_service: Arc::clone(&self.device_actions_service),
+            rule_service: Arc::clone(&self.rule_service),
         }
     }
 }
@@ -84,36 +136,69 @@ impl MqttAppOutbound {
 
         let mqtt_device_repo =
             MqttDeviceRepository::new(mqtt_client.clone(), &mqtt_config.device_topic);
+        let mqtt_device_model_repo =
+            MqttDeviceModelRepository::new(mqtt_client.clone(), &mqtt_config.device_model_topic);
+        let mqtt_device_group_repo =
+            MqttDeviceGroupRepository::new(mqtt_client.clone(), &mqtt_config.device_group_topic);
         let mqtt_device_state_repo =
             MqttDeviceStateRepository::new(mqtt_client.clone(), &mqtt_config.device_state_topic);
         let mqtt_event_repo =
             MqttEventRepository::new(mqtt_client.clone(), &mqtt_config.event_topic);
+        let mqtt_rule_repo =
+            MqttRuleRepository::new(mqtt_client.clone(), &mqtt_config.rule_topic);
         let mqtt_action_repo = MqttActionRepository::new(mqtt_client, &mqtt_config.action_topic);
 
         let postgres_device_repo = PostgresDeviceRepository::new(pool.clone()).await;
+        let postgres_device_model_repo = PostgresDeviceModelRepository::new(pool.clone()).await;
+        let postgres_device_group_repo = PostgresDeviceGroupRepository::new(pool.clone()).await;
         let postgres_device_state_repo = PostgresDeviceStateRepository::new(pool.clone()).await;
         let postgres_event_repo = PostgresEventRepository::new(pool.clone()).await;
         let postgres_action_repo = PostgresActionRepository::new(pool.clone()).await;
+        let postgres_rule_repo = PostgresRuleRepository::new(pool.clone()).await;
 
         // Initialize the repositories
         postgres_device_repo.init().await;
+        postgres_device_model_repo.init().await;
+        postgres_device_group_repo.init().await;
         postgres_device_state_repo.init().await;
         postgres_event_repo.init().await;
         postgres_action_repo.init().await;
+        postgres_rule_repo.init().await;
 
         let arc_mqtt_device_repo = Arc::new(mqtt_device_repo);
+        let arc_mqtt_device_model_repo = Arc::new(mqtt_device_model_repo);
+        let arc_mqtt_device_group_repo = Arc::new(mqtt_device_group_repo);
         let arc_mqtt_event_repo = Arc::new(mqtt_event_repo);
         let arc_mqtt_device_state_repo = Arc::new(mqtt_device_state_repo);
         let arc_mqtt_action_repo = Arc::new(Mutex::new(mqtt_action_repo));
+        let arc_mqtt_rule_repo = Arc::new(mqtt_rule_repo);
         let arc_postgres_device_repo = Arc::new(postgres_device_repo);
+        let arc_postgres_device_model_repo = Arc::new(postgres_device_model_repo);
+        let arc_postgres_device_group_repo = Arc::new(postgres_device_group_repo);
         let arc_postgres_event_repo = Arc::new(postgres_event_repo);
         let arc_postgres_device_state_repo = Arc::new(postgres_device_state_repo);
         let arc_postgres_action_repo = Arc::new(Mutex::new(postgres_action_repo));
+        let arc_postgres_rule_repo = Arc::new(postgres_rule_repo);
         let device_service = Arc::new(ManageDeviceService {
             create_repo: arc_mqtt_device_repo.clone(),
-            get_repo: arc_postgres_device_repo,
+            get_repo: arc_postgres_device_repo.clone(),
             update_repo: arc_mqtt_device_repo.clone(),
-            delete_repo: arc_mqtt_device_repo,
+            delete_repo: arc_mqtt_device_repo.clone(),
+            model_repo: arc_postgres_device_model_repo.clone(),
+        });
+        let device_model_service = Arc::new(ManageDeviceModelService {
+            create_repo: arc_mqtt_device_model_repo.clone(),
+            get_repo: arc_postgres_device_model_repo,
+            update_repo: arc_mqtt_device_model_repo.clone(),
+            delete_repo: arc_mqtt_device_model_repo,
+            device_get_repo: arc_postgres_device_repo,
+            device_update_repo: arc_mqtt_device_repo,
+        });
+        let device_group_service = Arc::new(ManageDeviceGroupService {
+            create_repo: arc_mqtt_device_group_repo.clone(),
+            get_repo: arc_postgres_device_group_repo,
+            update_repo: arc_mqtt_device_group_repo.clone(),
+            delete_repo: arc_mqtt_device_group_repo,
         });
         let device_state_service = Arc::new(ManageDeviceStateService {
             create_repo: arc_mqtt_device_state_repo.clone(),
@@ -126,16 +211,28 @@ impl MqttAppOutbound {
             get_repo: arc_postgres_event_repo,
         });
         let device_actions_service = Arc::new(ManageActionService {
-            create_repo: arc_mqtt_action_repo,
-            get_repo: arc_postgres_action_repo,
+            create_repo: arc_mqtt_action_repo.clone(),
+            get_repo: arc_postgres_action_repo.clone(),
+            update_repo: arc_mqtt_action_repo,
+            // actions are held by the client until due, then published
+            scheduled_repo: arc_postgres_action_repo,
+        });
+        let rule_service = Arc::new(ManageRuleService {
+            create_repo: arc_mqtt_rule_repo.clone(),
+            get_repo: arc_postgres_rule_repo,
+            update_repo: arc_mqtt_rule_repo.clone(),
+            delete_repo: arc_mqtt_rule_repo,
         });
         task::spawn(async move { while let Ok(_) = event_loop.poll().await {} });
 
         Ok(MqttAppOutbound {
             device_service,
+            device_model_service,
+            device_group_service,
             device_state_service,
             device_events_service,
             device_actions_service,
+            rule_service,
         })
     }
 }
@@ -168,18 +265,62 @@ impl AppOutbound for MqttAppOutbound {
             impl GetDeviceRepository,
             impl UpdateDeviceRepository,
             impl DeleteDeviceRepository,
+            impl GetDeviceModelRepository,
         >,
     > {
         &self.device_service
     }
+
+    fn get_device_model_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceModelService<
+            impl CreateDeviceModelRepository,
+            impl GetDeviceModelRepository,
+            impl UpdateDeviceModelRepository,
+            impl DeleteDeviceModelRepository,
+            impl GetDeviceRepository,
+            impl UpdateDeviceRepository,
+        >,
+    > {
+        &self.device_model_service
+    }
+
+    fn get_device_group_service(
+        &self,
+    ) -> &Arc<
+        ManageDeviceGroupService<
+            impl CreateDeviceGroupRepository,
+            impl GetDeviceGroupRepository,
+            impl UpdateDeviceGroupRepository,
+            impl DeleteDeviceGroupRepository,
+        >,
+    > {
+        &self.device_group_service
+    }
     fn get_action_service(
         &self,
     ) -> &Arc<
         crate::application::usecases::manage_action::ManageActionService<
             impl crate::application::ports::outbound::action_repository::CreateActionRepository,
             impl crate::application::ports::outbound::action_repository::HandleActionRepository,
+            impl crate::application::ports::outbound::action_repository::UpdateActionRepository,
+            impl crate::application::ports::outbound::action_repository::ScheduledActionRepository,
         >,
     > {
         &self.device_actions_service
     }
+
+    fn get_rule_service(
+        &self,
+    ) -> &Arc<
+        ManageRuleService<
+            impl CreateRuleRepository,
+            impl GetRuleRepository,
+            impl UpdateRuleRepository,
+            impl DeleteRuleRepository,
+        >,
+    > {
+        &self.rule_service
+    }
 }
@@ -0,0 +1,115 @@
+use std::time::Duration;
+
+use chrono::{DateTime, Utc};
+use tracing::warn;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{
+            device_service::DeviceService,
+            device_state_service::{DeviceStateService, DeviceStateServiceError},
+            event_service::EventService,
+        },
+    },
+    domain::{
+        connectivity::{self, Connectivity, ConnectivityChange},
+        device::Device,
+        event::event_format::EventFormat,
+    },
+};
+
+/// Time between two checks of the heartbeats.
+const CHECK_PERIOD: Duration = Duration::from_secs(5);
+
+// with an MQTT outbound, events are published and the MQTT inbound receiving them tracks connectivity
+const TRACKS_CONNECTIVITY: bool = !cfg!(feature = "mqtt_outbound");
+
+/// Marks the device online when something is received from it, returns the change if it was not.
+pub async fn record_activity<AO: AppOutbound>(
+    outbound: &AO,
+    device: &Device,
+) -> Result<Option<ConnectivityChange>, String> {
+    if !TRACKS_CONNECTIVITY || device.connectivity() == Connectivity::Online {
+        return Ok(None);
+    }
+    change_connectivity(outbound, device, Connectivity::Online, &Utc::now())
+        .await
+        .map(Some)
+}
+
+/// Marks offline the monitored devices nothing was received from within their heartbeat interval.
+pub async fn check_heartbeats<AO: AppOutbound>(
+    outbound: &AO,
+    now: &DateTime<Utc>,
+) -> Result<Vec<ConnectivityChange>, String> {
+    let devices = outbound
+        .get_device_service()
+        .get_monitored_devices()
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut changes = Vec::new();
+    for device in devices {
+        if device.connectivity() == Connectivity::Offline {
+            continue;
+        }
+        let last_update = match outbound.get_device_state_service().get_device_state(*device.id()).await {
+            Ok(Some(state)) => state.last_update,
+            // nothing was ever received from the device
+            Ok(None) | Err(DeviceStateServiceError::DeviceStateNotFound) => continue,
+            Err(e) => return Err(e.to_string()),
+        };
+        if !connectivity::is_timed_out(&device, &last_update, now) {
+            continue;
+        }
+        // events leaving the state unchanged do not move its last update
+        let last_event = outbound
+            .get_event_service()
+            .get_last_event_time(device.physical_id())
+            .await
+            .map_err(|e| e.to_string())?;
+        if last_event.is_some_and(|last_event| !connectivity::is_timed_out(&device, &last_event, now)) {
+            continue;
+        }
+        changes.push(change_connectivity(outbound, &device, Connectivity::Offline, now).await?);
+    }
+    Ok(changes)
+}
+
+/// Checks the heartbeats until the application stops, `on_change` receives every change.
+pub async fn run_heartbeat_monitor<AO: AppOutbound>(
+    outbound: AO,
+    on_change: impl Fn(&ConnectivityChange) + Send + Sync + 'static,
+) {
+    if !TRACKS_CONNECTIVITY {
+        return;
+    }
+    let mut interval = tokio::time::interval(CHECK_PERIOD);
+    loop {
+        interval.tick().await;
+        match check_heartbeats(&outbound, &Utc::now()).await {
+            Ok(changes) => changes.iter().for_each(&on_change),
+            Err(e) => warn!(result = "warn", details = format!("Failed to check heartbeats: {}", e)),
+        }
+    }
+}
+
+async fn change_connectivity<AO: AppOutbound>(
+    outbound: &AO,
+    device: &Device,
+    connectivity: Connectivity,
+    timestamp: &DateTime<Utc>,
+) -> Result<ConnectivityChange, String> {
+    outbound
+        .get_device_service()
+        .set_connectivity(*device.id(), connectivity)
+        .await
+        .map_err(|e| e.to_string())?;
+    let change = ConnectivityChange::new(device, connectivity, timestamp);
+    outbound
+        .get_event_service()
+        .handle_event(change.to_event(), &EventFormat::Json)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(change)
+}
@@ -1,18 +1,27 @@
 use std::{collections::HashMap, sync::Mutex};
 
-use crate::{application::ports::outbound::action_repository::{ActionRepositoryError, CreateActionRepository, HandleActionRepository}, domain::action::{action::Action, action_format::ActionFormat}};
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+use crate::{application::ports::outbound::action_repository::{ActionRepositoryError, CreateActionRepository, HandleActionRepository, ScheduledActionRepository, UpdateActionRepository}, domain::action::{action::Action, action_format::ActionFormat, action_schedule::{ActionSchedule, ScheduleRun}}};
 
 
 
 #[derive(Debug)]
 pub struct InMemoryActionRepository {
     actions: Mutex<HashMap<String, Vec<Action>>>,
+    scheduled: Mutex<HashMap<Uuid, (Action, ActionFormat)>>,
+    schedules: Mutex<HashMap<Uuid, (ActionSchedule, ActionFormat)>>,
+    schedule_runs: Mutex<HashMap<Uuid, Vec<ScheduleRun>>>,
 }
 
 impl InMemoryActionRepository {
     pub fn new() -> Self {
         return InMemoryActionRepository {
             actions: Mutex::new(HashMap::new()),
+            scheduled: Mutex::new(HashMap::new()),
+            schedules: Mutex::new(HashMap::new()),
+            schedule_runs: Mutex::new(HashMap::new()),
         };
     }
 }
@@ -45,4 +54,131 @@ impl HandleActionRepository for InMemoryActionRepository {
         };
         return Ok(actions_found.clone());
     }
+
+    async fn get_action(&mut self, action_id: Uuid) -> Result<Option<Action>, ActionRepositoryError> {
+        let actions = self.actions.lock().unwrap();
+        let action = actions.values().flatten().find(|action| action.id == action_id);
+        Ok(action.cloned())
+    }
+
+    async fn get_expired_actions(&mut self, now: &DateTime<Utc>) -> Result<Vec<Action>, ActionRepositoryError> {
+        let actions = self.actions.lock().unwrap();
+        Ok(actions.values().flatten().filter(|action| action.is_overdue(now)).cloned().collect())
+    }
+}
+
+impl UpdateActionRepository for InMemoryActionRepository {
+    async fn update_action_status(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        let mut actions = self.actions.lock().unwrap();
+        let stored = actions
+            .get_mut(&action.device_id)
+            .and_then(|device_actions| device_actions.iter_mut().find(|stored| stored.id == action.id))
+            .ok_or(ActionRepositoryError::NotFound)?;
+        stored.status = action.status;
+        stored.transitions = action.transitions.clone();
+        Ok(())
+    }
+}
+
+impl ScheduledActionRepository for InMemoryActionRepository {
+    async fn schedule_action(&self, action: Action, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let mut scheduled = self.scheduled.lock().unwrap();
+        scheduled.insert(action.id, (action, action_format.clone()));
+        Ok(())
+    }
+
+    async fn get_scheduled_actions(&self, device_id: Option<&str>) -> Result<Vec<Action>, ActionRepositoryError> {
+        let scheduled = self.scheduled.lock().unwrap();
+        let mut actions: Vec<Action> = scheduled
+            .values()
+            .map(|(action, _)| action)
+            .filter(|action| device_id.is_none_or(|device_id| action.device_id == device_id))
+            .cloned()
+            .collect();
+        actions.sort_by_key(|action| action.scheduled_at);
+        Ok(actions)
+    }
+
+    async fn get_due_actions(&self, now: &DateTime<Utc>) -> Result<Vec<(Action, ActionFormat)>, ActionRepositoryError> {
+        let scheduled = self.scheduled.lock().unwrap();
+        Ok(scheduled
+            .values()
+            .filter(|(action, _)| action.scheduled_at.is_none_or(|scheduled_at| scheduled_at <= *now))
+            .cloned()
+            .collect())
+    }
+
+    async fn delete_scheduled_action(&self, action_id: Uuid) -> Result<Action, ActionRepositoryError> {
+        let mut scheduled = self.scheduled.lock().unwrap();
+        scheduled
+            .remove(&action_id)
+            .map(|(action, _)| action)
+            .ok_or(ActionRepositoryError::NotFound)
+    }
+
+    async fn retire_scheduled_action(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        self.scheduled
+            .lock()
+            .unwrap()
+            .remove(&action.id)
+            .ok_or(ActionRepositoryError::NotFound)?;
+        let mut actions = self.actions.lock().unwrap();
+        actions.entry(action.device_id.clone()).or_default().push(action.clone());
+        Ok(())
+    }
+
+    async fn create_action_schedule(&self, schedule: ActionSchedule, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        schedules.insert(schedule.id, (schedule, action_format.clone()));
+        Ok(())
+    }
+
+    async fn get_action_schedules(&self, device_id: Option<&str>) -> Result<Vec<ActionSchedule>, ActionRepositoryError> {
+        let schedules = self.schedules.lock().unwrap();
+        let mut result: Vec<ActionSchedule> = schedules
+            .values()
+            .map(|(schedule, _)| schedule)
+            .filter(|schedule| device_id.is_none_or(|device_id| schedule.device_id == device_id))
+            .cloned()
+            .collect();
+        result.sort_by_key(|schedule| schedule.created_at);
+        Ok(result)
+    }
+
+    async fn get_due_action_schedules(&self, now: &DateTime<Utc>) -> Result<Vec<(ActionSchedule, ActionFormat)>, ActionRepositoryError> {
+        let schedules = self.schedules.lock().unwrap();
+        Ok(schedules
+            .values()
+            .filter(|(schedule, _)| schedule.next_run_at.is_some_and(|next_run_at| next_run_at <= *now))
+            .cloned()
+            .collect())
+    }
+
+    async fn update_action_schedule_next_run(&self, schedule_id: Uuid, next_run_at: Option<DateTime<Utc>>) -> Result<(), ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let (schedule, _) = schedules.get_mut(&schedule_id).ok_or(ActionRepositoryError::NotFound)?;
+        schedule.next_run_at = next_run_at;
+        Ok(())
+    }
+
+    async fn delete_action_schedule(&self, schedule_id: Uuid) -> Result<ActionSchedule, ActionRepositoryError> {
+        let mut schedules = self.schedules.lock().unwrap();
+        let (schedule, _) = schedules.remove(&schedule_id).ok_or(ActionRepositoryError::NotFound)?;
+        self.schedule_runs.lock().unwrap().remove(&schedule_id);
+        Ok(schedule)
+    }
+
+    async fn add_schedule_run(&self, run: ScheduleRun) -> Result<(), ActionRepositoryError> {
+        let mut runs = self.schedule_runs.lock().unwrap();
+        runs.entry(run.schedule_id).or_default().push(run);
+        Ok(())
+    }
+
+    async fn get_schedule_runs(&self, schedule_id: Uuid) -> Result<Vec<ScheduleRun>, ActionRepositoryError> {
+        if !self.schedules.lock().unwrap().contains_key(&schedule_id) {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        let runs = self.schedule_runs.lock().unwrap();
+        Ok(runs.get(&schedule_id).map(|runs| runs.iter().rev().cloned().collect()).unwrap_or_default())
+    }
 }
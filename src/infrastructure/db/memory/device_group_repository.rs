@@ -0,0 +1,67 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::application::ports::outbound::device_group_repository::{CreateDeviceGroupRepository, DeleteDeviceGroupRepository, DeviceGroupRepositoryError, GetDeviceGroupRepository, UpdateDeviceGroupRepository};
+use crate::domain::device_group::DeviceGroup;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct InMemoryDeviceGroupRepository {
+    store: Mutex<HashMap<Uuid, DeviceGroup>>,
+}
+
+impl InMemoryDeviceGroupRepository {
+    pub fn new() -> Self {
+        Self { store: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl CreateDeviceGroupRepository for InMemoryDeviceGroupRepository {
+    async fn create(&self, group: &DeviceGroup) -> Result<(), DeviceGroupRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.contains_key(group.id()) {
+            return Err(DeviceGroupRepositoryError::Conflict);
+        }
+        map.insert(*group.id(), group.clone());
+        Ok(())
+    }
+}
+
+impl GetDeviceGroupRepository for InMemoryDeviceGroupRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceGroup>, DeviceGroupRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.get(&id).cloned())
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.values().filter(|group| group.user_id() == &user_id).cloned().collect())
+    }
+
+    async fn get_children(&self, parent_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.values().filter(|group| group.parent_id() == Some(&parent_id)).cloned().collect())
+    }
+}
+
+impl UpdateDeviceGroupRepository for InMemoryDeviceGroupRepository {
+    async fn update(&self, group: &DeviceGroup) -> Result<(), DeviceGroupRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.contains_key(group.id()) {
+            map.insert(*group.id(), group.clone());
+            Ok(())
+        } else {
+            Err(DeviceGroupRepositoryError::NotFound)
+        }
+    }
+}
+
+impl DeleteDeviceGroupRepository for InMemoryDeviceGroupRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceGroupRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.remove(&id).is_some() {
+            Ok(())
+        } else {
+            Err(DeviceGroupRepositoryError::NotFound)
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::application::ports::outbound::device_model_repository::{CreateDeviceModelRepository, DeleteDeviceModelRepository, DeviceModelRepositoryError, GetDeviceModelRepository, UpdateDeviceModelRepository};
+use crate::domain::device_model::DeviceModel;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct InMemoryDeviceModelRepository {
+    store: Mutex<HashMap<Uuid, DeviceModel>>,
+}
+
+impl InMemoryDeviceModelRepository {
+    pub fn new() -> Self {
+        Self { store: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl CreateDeviceModelRepository for InMemoryDeviceModelRepository {
+    async fn create(&self, model: &DeviceModel) -> Result<(), DeviceModelRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.contains_key(model.id()) {
+            return Err(DeviceModelRepositoryError::Conflict);
+        }
+        map.insert(*model.id(), model.clone());
+        Ok(())
+    }
+}
+
+impl GetDeviceModelRepository for InMemoryDeviceModelRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceModel>, DeviceModelRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.get(&id).cloned())
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceModel>, DeviceModelRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.values().filter(|model| model.user_id() == &user_id).cloned().collect())
+    }
+}
+
+impl UpdateDeviceModelRepository for InMemoryDeviceModelRepository {
+    async fn update(&self, model: &DeviceModel) -> Result<(), DeviceModelRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.contains_key(model.id()) {
+            map.insert(*model.id(), model.clone());
+            Ok(())
+        } else {
+            Err(DeviceModelRepositoryError::NotFound)
+        }
+    }
+}
+
+impl DeleteDeviceModelRepository for InMemoryDeviceModelRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceModelRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.remove(&id).is_some() {
+            Ok(())
+        } else {
+            Err(DeviceModelRepositoryError::NotFound)
+        }
+    }
+}
@@ -1,17 +1,27 @@
 use std::collections::HashMap;
 use std::sync::Mutex;
 use crate::application::ports::outbound::device_repository::{CreateDeviceRepository, DeleteDeviceRepository, DeviceRepositoryError, GetDeviceRepository, UpdateDeviceRepository};
-use crate::domain::device::Device;
+use crate::domain::{connectivity::Connectivity, device::Device, device_filter::DeviceFilter, device_schema::DeviceSchema};
+use chrono::Utc;
 use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct InMemoryDeviceRepository {
     pub store: Mutex<HashMap<Uuid, Device>>,
+    schemas: Mutex<HashMap<Uuid, Vec<DeviceSchema>>>,
 }
 
 impl InMemoryDeviceRepository {
     pub fn new() -> Self {
-        Self { store: Mutex::new(HashMap::new()) }
+        Self { store: Mutex::new(HashMap::new()), schemas: Mutex::new(HashMap::new()) }
+    }
+
+    fn record_schema(&self, device: &Device) {
+        let mut schemas = self.schemas.lock().unwrap();
+        let device_schemas = schemas.entry(*device.id()).or_default();
+        if !device_schemas.iter().any(|s| s.version() == device.schema_version()) {
+            device_schemas.push(device.schema(&Utc::now()));
+        }
     }
 }
 
@@ -19,6 +29,7 @@ impl CreateDeviceRepository for InMemoryDeviceRepository{
     async fn create(&self, device: &Device) -> Result<(), DeviceRepositoryError> {
         let mut map = self.store.lock().unwrap();
         map.insert(*device.id(), device.clone());
+        self.record_schema(device);
         Ok(())
     }
 }
@@ -51,6 +62,38 @@ impl GetDeviceRepository for InMemoryDeviceRepository {
             None => Err(DeviceRepositoryError::NotFound),
         }
     }
+
+    async fn get_by_filter(&self, user_id: Uuid, filter: &DeviceFilter) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let map = self.store.lock().unwrap();
+        let devices: Vec<Device> = map.values()
+            .filter(|device| device.user_id() == &user_id && filter.matches(device))
+            .cloned()
+            .collect();
+        Ok(devices)
+    }
+
+    async fn get_monitored(&self) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let map = self.store.lock().unwrap();
+        let devices: Vec<Device> = map.values()
+            .filter(|device| device.heartbeat_interval().is_some())
+            .cloned()
+            .collect();
+        Ok(devices)
+    }
+
+    async fn get_by_model_id(&self, model_id: Uuid) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let map = self.store.lock().unwrap();
+        let devices: Vec<Device> = map.values()
+            .filter(|device| device.model_id() == Some(&model_id))
+            .cloned()
+            .collect();
+        Ok(devices)
+    }
+
+    async fn get_schemas(&self, device_id: Uuid) -> Result<Vec<DeviceSchema>, DeviceRepositoryError> {
+        let schemas = self.schemas.lock().unwrap();
+        Ok(schemas.get(&device_id).cloned().unwrap_or_default())
+    }
 }
 
 impl UpdateDeviceRepository for InMemoryDeviceRepository {
@@ -58,17 +101,30 @@ impl UpdateDeviceRepository for InMemoryDeviceRepository {
         let mut map = self.store.lock().unwrap();
         if map.contains_key(device.id()) {
             map.insert(*device.id(), device.clone());
+            self.record_schema(device);
             Ok(())
         } else {
             Err(DeviceRepositoryError::NotFound)
         }
     }
+
+    async fn update_connectivity(&self, id: Uuid, connectivity: Connectivity) -> Result<(), DeviceRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        match map.get_mut(&id) {
+            Some(device) => {
+                device.set_connectivity(connectivity);
+                Ok(())
+            }
+            None => Err(DeviceRepositoryError::NotFound),
+        }
+    }
 }
 
 impl DeleteDeviceRepository for InMemoryDeviceRepository {
     async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceRepositoryError> {
         let mut map = self.store.lock().unwrap();
         if map.remove(&id).is_some() {
+            self.schemas.lock().unwrap().remove(&id);
             Ok(())
         } else {
             Err(DeviceRepositoryError::NotFound)
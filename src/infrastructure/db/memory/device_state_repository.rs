@@ -2,7 +2,7 @@ use std::{collections::HashMap, sync::Mutex};
 
 use uuid::Uuid;
 
-use crate::{application::ports::outbound::device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError, GetDeviceStateRepository, UpdateDeviceStateRepository}, domain::state::DeviceState};
+use crate::{application::ports::outbound::device_state_repository::{CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError, GetDeviceStateRepository, UpdateDeviceStateRepository}, domain::state::{DeviceState, StateMergePolicy}};
 
 #[derive(Debug)]
 pub struct InMemoryDeviceStateRepository {
@@ -20,7 +20,16 @@ impl InMemoryDeviceStateRepository {
 impl CreateDeviceStateRepository for InMemoryDeviceStateRepository {
     async fn create(&self, device_state: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
         let mut map = self.device_states.lock().unwrap();
-        map.insert(device_state.device_id, device_state.clone());
+        let mut created = device_state.clone();
+        // desired values are only set through `set_desired`
+        if let Some(stored) = map.get(&device_state.device_id) {
+            created.desired = stored.desired.clone();
+            created.desired_update = stored.desired_update;
+            created.converged_at = stored.converged_at;
+            let now = created.last_update;
+            created.refresh_convergence(&now);
+        }
+        map.insert(device_state.device_id, created);
         Ok(())
     }
 }
@@ -36,14 +45,26 @@ impl GetDeviceStateRepository for InMemoryDeviceStateRepository {
 }
 
 impl UpdateDeviceStateRepository for InMemoryDeviceStateRepository {
-    async fn update(&self, device_state: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
+    async fn set_desired(&self, device_state: &DeviceState) -> Result<DeviceState, DeviceStateRepositoryError> {
         let mut map = self.device_states.lock().unwrap();
-        if map.contains_key(&device_state.device_id) {
-            map.insert(device_state.device_id, device_state.clone());
-            Ok(())
-        } else {
-            Err(DeviceStateRepositoryError::DeviceNotFound)
+        let stored = map
+            .entry(device_state.device_id)
+            .or_insert_with(|| DeviceState::from_parts(device_state.device_id, device_state.last_update, HashMap::new(), HashMap::new()));
+        let now = device_state.desired_update.unwrap_or(device_state.last_update);
+        stored.set_desired(device_state.desired.clone(), &now);
+        Ok(stored.clone())
+    }
+
+    async fn merge_values(&self, device_state: &DeviceState, keys: &[String], policy: StateMergePolicy) -> Result<DeviceState, DeviceStateRepositoryError> {
+        let mut map = self.device_states.lock().unwrap();
+        let stored = map
+            .entry(device_state.device_id)
+            .or_insert_with(|| DeviceState::from_parts(device_state.device_id, device_state.last_update, HashMap::new(), HashMap::new()));
+        if !stored.merge(device_state.values_of(keys), policy).is_empty() {
+            stored.last_update = device_state.last_update;
+            stored.refresh_convergence(&device_state.last_update);
         }
+        Ok(stored.clone())
     }
 }
 
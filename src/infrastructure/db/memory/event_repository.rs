@@ -1,5 +1,7 @@
 use std::{collections::HashMap, sync::Mutex};
 
+use chrono::{DateTime, Utc};
+
 use crate::{application::ports::outbound::event_repository::{
     CreateEventRepository, EventRepositoryError, GetEventRepository,
 }, domain::event::{event::Event, event_format::EventFormat}};
@@ -45,4 +47,14 @@ impl GetEventRepository for InMemoryEventRepository {
         };
         return Ok(events_found.clone());
     }
+
+    async fn get_last_event_time(
+        &self,
+        device_physical_id: &str,
+    ) -> Result<Option<DateTime<Utc>>, EventRepositoryError> {
+        let events = self.events.lock().unwrap();
+        Ok(events
+            .get(device_physical_id)
+            .and_then(|device_events| device_events.iter().map(|event| event.timestamp).max()))
+    }
 }
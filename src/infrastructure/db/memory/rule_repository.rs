@@ -0,0 +1,64 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use crate::application::ports::outbound::rule_repository::{CreateRuleRepository, DeleteRuleRepository, GetRuleRepository, RuleRepositoryError, UpdateRuleRepository};
+use crate::domain::rule::Rule;
+use uuid::Uuid;
+
+#[derive(Debug)]
+pub struct InMemoryRuleRepository {
+    store: Mutex<HashMap<Uuid, Rule>>,
+}
+
+impl InMemoryRuleRepository {
+    pub fn new() -> Self {
+        Self { store: Mutex::new(HashMap::new()) }
+    }
+}
+
+impl CreateRuleRepository for InMemoryRuleRepository {
+    async fn create(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.contains_key(&rule.id) {
+            return Err(RuleRepositoryError::Conflict);
+        }
+        map.insert(rule.id, rule.clone());
+        Ok(())
+    }
+}
+
+impl GetRuleRepository for InMemoryRuleRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<Rule>, RuleRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.get(&id).cloned())
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.values().filter(|rule| rule.user_id == user_id).cloned().collect())
+    }
+
+    async fn get_by_device_id(&self, device_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        let map = self.store.lock().unwrap();
+        Ok(map.values().filter(|rule| rule.device_id == device_id).cloned().collect())
+    }
+}
+
+impl UpdateRuleRepository for InMemoryRuleRepository {
+    async fn update(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        let stored = map.get_mut(&rule.id).ok_or(RuleRepositoryError::NotFound)?;
+        *stored = rule.clone();
+        Ok(())
+    }
+}
+
+impl DeleteRuleRepository for InMemoryRuleRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), RuleRepositoryError> {
+        let mut map = self.store.lock().unwrap();
+        if map.remove(&id).is_some() {
+            Ok(())
+        } else {
+            Err(RuleRepositoryError::NotFound)
+        }
+    }
+}
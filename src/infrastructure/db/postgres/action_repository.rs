@@ -1,17 +1,33 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, TimeDelta, Utc};
+use chrono_tz::Tz;
 use serde_json::Value;
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Row, postgres::PgRow};
+use uuid::Uuid;
 
 use crate::{
     application::ports::outbound::action_repository::{
         ActionRepositoryError, CreateActionRepository, HandleActionRepository,
+        ScheduledActionRepository, UpdateActionRepository,
     },
-    domain::action::{
-        action::Action, action_data_value::ActionDataValue, action_format::ActionFormat,
+    domain::{
+        action::{
+            action::Action, action_data_value::ActionDataValue, action_format::ActionFormat,
+            action_schedule::{ActionSchedule, ScheduleRun},
+            action_status::{ActionStatus, ActionTransition},
+        },
+        binary_layout::BinaryLayout,
+        cron::CronExpression,
     },
 };
 
+const ACTION_COLUMNS: &str = "id, device_id, action_name, timestamp, payload, schema_version, status, transitions, scheduled_at, expires_at";
+const SCHEDULED_ACTION_COLUMNS: &str = "id, device_id, action_name, timestamp, payload, schema_version, scheduled_at, expires_at, action_format, action_binary_layout";
+const ACTION_SCHEDULE_COLUMNS: &str = "id, device_id, action_name, payload, schema_version, cron, timezone, created_at, next_run_at, action_format, action_binary_layout";
+/// Time a process has to hand over the due rows it claimed before others may take them.
+const CLAIM_DURATION: TimeDelta = TimeDelta::minutes(1);
+
 #[derive(Debug)]
 pub struct PostgresActionRepository {
     pool: sqlx::PgPool,
@@ -35,6 +51,81 @@ impl PostgresActionRepository {
         .execute(&self.pool)
         .await
         .expect("Failed to create actions table");
+        sqlx::query("ALTER TABLE actions ADD COLUMN IF NOT EXISTS schema_version INTEGER")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add schema_version column to actions table");
+        sqlx::query("ALTER TABLE actions ADD COLUMN IF NOT EXISTS status TEXT NOT NULL DEFAULT 'pending'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add status column to actions table");
+        sqlx::query("ALTER TABLE actions ADD COLUMN IF NOT EXISTS transitions JSONB NOT NULL DEFAULT '[]'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add transitions column to actions table");
+        sqlx::query("ALTER TABLE actions ADD COLUMN IF NOT EXISTS scheduled_at TIMESTAMPTZ")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add scheduled_at column to actions table");
+        sqlx::query("ALTER TABLE actions ADD COLUMN IF NOT EXISTS expires_at TIMESTAMPTZ")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add expires_at column to actions table");
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS scheduled_actions (
+                id UUID PRIMARY KEY,
+                device_id TEXT NOT NULL,
+                action_name TEXT NOT NULL,
+                timestamp TIMESTAMPTZ NOT NULL,
+                payload JSONB NOT NULL,
+                schema_version INTEGER,
+                scheduled_at TIMESTAMPTZ NOT NULL,
+                expires_at TIMESTAMPTZ,
+                action_format TEXT NOT NULL,
+                action_binary_layout JSONB
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create scheduled_actions table");
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS action_schedules (
+                id UUID PRIMARY KEY,
+                device_id TEXT NOT NULL,
+                action_name TEXT NOT NULL,
+                payload JSONB NOT NULL,
+                schema_version INTEGER,
+                cron TEXT NOT NULL,
+                timezone TEXT NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                next_run_at TIMESTAMPTZ,
+                action_format TEXT NOT NULL,
+                action_binary_layout JSONB
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create action_schedules table");
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS action_schedule_runs (
+                schedule_id UUID NOT NULL REFERENCES action_schedules (id) ON DELETE CASCADE,
+                scheduled_for TIMESTAMPTZ NOT NULL,
+                ran_at TIMESTAMPTZ NOT NULL,
+                action_id UUID,
+                error TEXT
+            )",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create action_schedule_runs table");
+        sqlx::query("ALTER TABLE scheduled_actions ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMPTZ")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add claimed_until column to scheduled_actions table");
+        sqlx::query("ALTER TABLE action_schedules ADD COLUMN IF NOT EXISTS claimed_until TIMESTAMPTZ")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add claimed_until column to action_schedules table");
     }
 }
 
@@ -44,7 +135,7 @@ impl CreateActionRepository for PostgresActionRepository {
         action: Action,
         _: &ActionFormat,
     ) -> Result<(), ActionRepositoryError> {
-        let query = "INSERT INTO actions (id, device_id, action_name, timestamp, payload) VALUES ($1, $2, $3, $4, $5)
+        let query = "INSERT INTO actions (id, device_id, action_name, timestamp, payload, schema_version, status, transitions, scheduled_at, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
                      ON CONFLICT (id, device_id) DO NOTHING";
         let event_data: HashMap<String, Value> = action
             .payload
@@ -57,6 +148,11 @@ impl CreateActionRepository for PostgresActionRepository {
             .bind(action.action_name)
             .bind(action.timestamp)
             .bind(sqlx::types::Json::from(event_data))
+            .bind(action.schema_version.map(|v| v as i32))
+            .bind(action.status.to_string())
+            .bind(sqlx::types::Json::from(action.transitions))
+            .bind(action.scheduled_at)
+            .bind(action.expires_at)
             .execute(&self.pool)
             .await
             .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
@@ -67,37 +163,409 @@ impl CreateActionRepository for PostgresActionRepository {
 
 impl HandleActionRepository for PostgresActionRepository {
     async fn get_actions(&mut self, device_id: &str) -> Result<Vec<Action>, ActionRepositoryError> {
-        let query = "SELECT id, device_id, action_name, timestamp, payload FROM events WHERE device_id = $1";
-        let rows = sqlx::query(query)
+        let query = format!("SELECT {} FROM actions WHERE device_id = $1", ACTION_COLUMNS);
+        let rows = sqlx::query(&query)
+            .bind(device_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.into_iter().map(row_to_action).collect()
+    }
+
+    async fn get_action(&mut self, action_id: Uuid) -> Result<Option<Action>, ActionRepositoryError> {
+        let query = format!("SELECT {} FROM actions WHERE id = $1", ACTION_COLUMNS);
+        let row = sqlx::query(&query)
+            .bind(action_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        row.map(row_to_action).transpose()
+    }
+
+    async fn get_expired_actions(&mut self, now: &DateTime<Utc>) -> Result<Vec<Action>, ActionRepositoryError> {
+        let query = format!(
+            "SELECT {} FROM actions WHERE expires_at <= $1 AND status IN ('pending', 'sent')",
+            ACTION_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(now)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.into_iter().map(row_to_action).collect()
+    }
+}
+
+impl ScheduledActionRepository for PostgresActionRepository {
+    async fn schedule_action(&self, action: Action, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let scheduled_at = action.scheduled_at.ok_or_else(|| {
+            ActionRepositoryError::ValidationError(format!("Action {} is not scheduled", action.id))
+        })?;
+        let query = "INSERT INTO scheduled_actions (id, device_id, action_name, timestamp, payload, schema_version, scheduled_at, expires_at, action_format, action_binary_layout)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)";
+        let payload: HashMap<String, Value> = action
+            .payload
+            .into_iter()
+            .map(|(k, v)| (k, v.into()))
+            .collect();
+        sqlx::query(query)
+            .bind(action.id)
+            .bind(action.device_id)
+            .bind(action.action_name)
+            .bind(action.timestamp)
+            .bind(sqlx::types::Json::from(payload))
+            .bind(action.schema_version.map(|v| v as i32))
+            .bind(scheduled_at)
+            .bind(action.expires_at)
+            .bind(action_format.to_string())
+            .bind(action_format.binary_layout().map(sqlx::types::Json::from))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_scheduled_actions(&self, device_id: Option<&str>) -> Result<Vec<Action>, ActionRepositoryError> {
+        let query = format!(
+            "SELECT {} FROM scheduled_actions WHERE $1::TEXT IS NULL OR device_id = $1 ORDER BY scheduled_at",
+            SCHEDULED_ACTION_COLUMNS
+        );
+        let rows = sqlx::query(&query)
             .bind(device_id)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
 
-        let actions: Result<Vec<Action>, ActionRepositoryError> = rows
+        rows.into_iter()
+            .map(|row| row_to_scheduled_action(row).map(|(action, _)| action))
+            .collect()
+    }
+
+    async fn get_due_actions(&self, now: &DateTime<Utc>) -> Result<Vec<(Action, ActionFormat)>, ActionRepositoryError> {
+        // claimed rows are skipped by the other processes, those left once the claim lapses are taken again
+        let query = format!(
+            "UPDATE scheduled_actions SET claimed_until = $2 WHERE id IN (
+                SELECT id FROM scheduled_actions
+                WHERE scheduled_at <= $1 AND (claimed_until IS NULL OR claimed_until <= $1)
+                FOR UPDATE SKIP LOCKED
+            ) RETURNING {}",
+            SCHEDULED_ACTION_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(now)
+            .bind(*now + CLAIM_DURATION)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        let mut due = rows
             .into_iter()
-            .map(|row| {
-                let payload_db: sqlx::types::Json<HashMap<String, Value>> = row.get("payload");
-                let mut payload = HashMap::new();
-                for (k, v) in payload_db.0 {
-                    let val = ActionDataValue::try_from(v).map_err(|_| {
-                        ActionRepositoryError::RepositoryError(format!(
-                            "Invalid data stored for key {}",
-                            k
-                        ))
-                    })?;
-                    payload.insert(k, val);
-                }
-                Ok(Action {
-                    id: row.get("id"),
-                    device_id: row.get("device_id"),
-                    action_name: row.get("action_name"),
-                    timestamp: row.get("timestamp"),
-                    payload,
-                })
-            })
+            .map(row_to_scheduled_action)
+            .collect::<Result<Vec<_>, _>>()?;
+        due.sort_by_key(|(action, _)| action.scheduled_at);
+        Ok(due)
+    }
+
+    async fn delete_scheduled_action(&self, action_id: Uuid) -> Result<Action, ActionRepositoryError> {
+        let query = format!(
+            "DELETE FROM scheduled_actions WHERE id = $1 RETURNING {}",
+            SCHEDULED_ACTION_COLUMNS
+        );
+        let row = sqlx::query(&query)
+            .bind(action_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?
+            .ok_or(ActionRepositoryError::NotFound)?;
+
+        row_to_scheduled_action(row).map(|(action, _)| action)
+    }
+
+    async fn retire_scheduled_action(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        let result = sqlx::query("DELETE FROM scheduled_actions WHERE id = $1")
+            .bind(action.id)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        let query = "INSERT INTO actions (id, device_id, action_name, timestamp, payload, schema_version, status, transitions, scheduled_at, expires_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
+                     ON CONFLICT (id, device_id) DO NOTHING";
+        let payload: HashMap<String, Value> = action
+            .payload
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone().into()))
+            .collect();
+        sqlx::query(query)
+            .bind(action.id)
+            .bind(&action.device_id)
+            .bind(&action.action_name)
+            .bind(action.timestamp)
+            .bind(sqlx::types::Json::from(payload))
+            .bind(action.schema_version.map(|v| v as i32))
+            .bind(action.status.to_string())
+            .bind(sqlx::types::Json::from(&action.transitions))
+            .bind(action.scheduled_at)
+            .bind(action.expires_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn create_action_schedule(&self, schedule: ActionSchedule, action_format: &ActionFormat) -> Result<(), ActionRepositoryError> {
+        let query = "INSERT INTO action_schedules (id, device_id, action_name, payload, schema_version, cron, timezone, created_at, next_run_at, action_format, action_binary_layout)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)";
+        let payload: HashMap<String, Value> = schedule
+            .payload
+            .into_iter()
+            .map(|(k, v)| (k, v.into()))
             .collect();
+        sqlx::query(query)
+            .bind(schedule.id)
+            .bind(schedule.device_id)
+            .bind(schedule.action_name)
+            .bind(sqlx::types::Json::from(payload))
+            .bind(schedule.schema_version.map(|v| v as i32))
+            .bind(schedule.cron.to_string())
+            .bind(schedule.timezone.name())
+            .bind(schedule.created_at)
+            .bind(schedule.next_run_at)
+            .bind(action_format.to_string())
+            .bind(action_format.binary_layout().map(sqlx::types::Json::from))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_action_schedules(&self, device_id: Option<&str>) -> Result<Vec<ActionSchedule>, ActionRepositoryError> {
+        let query = format!(
+            "SELECT {} FROM action_schedules WHERE $1::TEXT IS NULL OR device_id = $1 ORDER BY created_at",
+            ACTION_SCHEDULE_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(device_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        rows.into_iter()
+            .map(|row| row_to_action_schedule(row).map(|(schedule, _)| schedule))
+            .collect()
+    }
+
+    async fn get_due_action_schedules(&self, now: &DateTime<Utc>) -> Result<Vec<(ActionSchedule, ActionFormat)>, ActionRepositoryError> {
+        // claimed like the scheduled actions, the claim is released once the next run is set
+        let query = format!(
+            "UPDATE action_schedules SET claimed_until = $2 WHERE id IN (
+                SELECT id FROM action_schedules
+                WHERE next_run_at <= $1 AND (claimed_until IS NULL OR claimed_until <= $1)
+                FOR UPDATE SKIP LOCKED
+            ) RETURNING {}",
+            ACTION_SCHEDULE_COLUMNS
+        );
+        let rows = sqlx::query(&query)
+            .bind(now)
+            .bind(*now + CLAIM_DURATION)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        let mut due = rows
+            .into_iter()
+            .map(row_to_action_schedule)
+            .collect::<Result<Vec<_>, _>>()?;
+        due.sort_by_key(|(schedule, _)| schedule.next_run_at);
+        Ok(due)
+    }
+
+    async fn update_action_schedule_next_run(&self, schedule_id: Uuid, next_run_at: Option<DateTime<Utc>>) -> Result<(), ActionRepositoryError> {
+        let result = sqlx::query("UPDATE action_schedules SET next_run_at = $1, claimed_until = NULL WHERE id = $2")
+            .bind(next_run_at)
+            .bind(schedule_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+
+    async fn delete_action_schedule(&self, schedule_id: Uuid) -> Result<ActionSchedule, ActionRepositoryError> {
+        // runs are removed along by the foreign key
+        let query = format!(
+            "DELETE FROM action_schedules WHERE id = $1 RETURNING {}",
+            ACTION_SCHEDULE_COLUMNS
+        );
+        let row = sqlx::query(&query)
+            .bind(schedule_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?
+            .ok_or(ActionRepositoryError::NotFound)?;
+
+        row_to_action_schedule(row).map(|(schedule, _)| schedule)
+    }
+
+    async fn add_schedule_run(&self, run: ScheduleRun) -> Result<(), ActionRepositoryError> {
+        sqlx::query("INSERT INTO action_schedule_runs (schedule_id, scheduled_for, ran_at, action_id, error) VALUES ($1, $2, $3, $4, $5)")
+            .bind(run.schedule_id)
+            .bind(run.scheduled_for)
+            .bind(run.ran_at)
+            .bind(run.action_id)
+            .bind(run.error)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        Ok(())
+    }
+
+    async fn get_schedule_runs(&self, schedule_id: Uuid) -> Result<Vec<ScheduleRun>, ActionRepositoryError> {
+        let exists = sqlx::query("SELECT 1 FROM action_schedules WHERE id = $1")
+            .bind(schedule_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        if exists.is_none() {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        let rows = sqlx::query(
+            "SELECT schedule_id, scheduled_for, ran_at, action_id, error FROM action_schedule_runs WHERE schedule_id = $1 ORDER BY ran_at DESC",
+        )
+        .bind(schedule_id)
+        .fetch_all(&self.pool)
+        .await
+        .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ScheduleRun {
+                schedule_id: row.get("schedule_id"),
+                scheduled_for: row.get("scheduled_for"),
+                ran_at: row.get("ran_at"),
+                action_id: row.get("action_id"),
+                error: row.get("error"),
+            })
+            .collect())
+    }
+}
 
-        actions
+impl UpdateActionRepository for PostgresActionRepository {
+    async fn update_action_status(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        let result = sqlx::query("UPDATE actions SET status = $1, transitions = $2 WHERE id = $3")
+            .bind(action.status.to_string())
+            .bind(sqlx::types::Json::from(&action.transitions))
+            .bind(action.id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(ActionRepositoryError::NotFound);
+        }
+        Ok(())
     }
 }
+
+fn parse_payload(row: &PgRow) -> Result<HashMap<String, ActionDataValue>, ActionRepositoryError> {
+    let payload_db: sqlx::types::Json<HashMap<String, Value>> = row.get("payload");
+    let mut payload = HashMap::new();
+    for (k, v) in payload_db.0 {
+        let val = ActionDataValue::try_from(v).map_err(|_| {
+            ActionRepositoryError::RepositoryError(format!(
+                "Invalid data stored for key {}",
+                k
+            ))
+        })?;
+        payload.insert(k, val);
+    }
+    Ok(payload)
+}
+
+fn row_to_action(row: PgRow) -> Result<Action, ActionRepositoryError> {
+    let payload = parse_payload(&row)?;
+    let status = ActionStatus::try_from(row.get::<String, _>("status").as_str())
+        .map_err(ActionRepositoryError::RepositoryError)?;
+    let transitions: sqlx::types::Json<Vec<ActionTransition>> = row.get("transitions");
+    let mut transitions = transitions.0;
+    let timestamp = row.get("timestamp");
+    // actions stored before their lifecycle was tracked
+    if transitions.is_empty() {
+        transitions.push(ActionTransition { status, timestamp });
+    }
+    Ok(Action {
+        id: row.get("id"),
+        device_id: row.get("device_id"),
+        action_name: row.get("action_name"),
+        timestamp,
+        payload,
+        schema_version: row.get::<Option<i32>, _>("schema_version").map(|v| v as u32),
+        status,
+        transitions,
+        scheduled_at: row.get("scheduled_at"),
+        expires_at: row.get("expires_at"),
+    })
+}
+
+fn row_to_scheduled_action(row: PgRow) -> Result<(Action, ActionFormat), ActionRepositoryError> {
+    let binary_layout: Option<sqlx::types::Json<BinaryLayout>> = row.get("action_binary_layout");
+    let format = ActionFormat::from_parts(
+        &row.get::<String, _>("action_format"),
+        binary_layout.map(|layout| layout.0),
+    )
+    .map_err(ActionRepositoryError::RepositoryError)?;
+    let timestamp = row.get("timestamp");
+    let action = Action {
+        id: row.get("id"),
+        device_id: row.get("device_id"),
+        action_name: row.get("action_name"),
+        timestamp,
+        payload: parse_payload(&row)?,
+        schema_version: row.get::<Option<i32>, _>("schema_version").map(|v| v as u32),
+        status: ActionStatus::Pending,
+        transitions: vec![ActionTransition { status: ActionStatus::Pending, timestamp }],
+        scheduled_at: row.get("scheduled_at"),
+        expires_at: row.get("expires_at"),
+    };
+    Ok((action, format))
+}
+
+fn row_to_action_schedule(row: PgRow) -> Result<(ActionSchedule, ActionFormat), ActionRepositoryError> {
+    let binary_layout: Option<sqlx::types::Json<BinaryLayout>> = row.get("action_binary_layout");
+    let format = ActionFormat::from_parts(
+        &row.get::<String, _>("action_format"),
+        binary_layout.map(|layout| layout.0),
+    )
+    .map_err(ActionRepositoryError::RepositoryError)?;
+    let cron = CronExpression::parse(&row.get::<String, _>("cron"))
+        .map_err(ActionRepositoryError::RepositoryError)?;
+    let timezone = row
+        .get::<String, _>("timezone")
+        .parse::<Tz>()
+        .map_err(|e| ActionRepositoryError::RepositoryError(e.to_string()))?;
+    let schedule = ActionSchedule {
+        id: row.get("id"),
+        device_id: row.get("device_id"),
+        action_name: row.get("action_name"),
+        payload: parse_payload(&row)?,
+        schema_version: row.get::<Option<i32>, _>("schema_version").map(|v| v as u32),
+        cron,
+        timezone,
+        created_at: row.get("created_at"),
+        next_run_at: row.get("next_run_at"),
+    };
+    Ok((schedule, format))
+}
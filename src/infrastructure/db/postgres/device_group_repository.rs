@@ -0,0 +1,150 @@
+use sqlx::{PgPool, Row, postgres::{PgQueryResult, PgRow}};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_group_repository::{
+        CreateDeviceGroupRepository, DeleteDeviceGroupRepository, DeviceGroupRepositoryError,
+        GetDeviceGroupRepository, UpdateDeviceGroupRepository,
+    },
+    domain::device_group::DeviceGroup,
+};
+
+/// Selects groups along with the IDs of their devices, to be completed with a WHERE clause on `g`.
+const SELECT_GROUPS: &str = "SELECT g.id, g.user_id, g.name, g.parent_id, COALESCE(array_agg(m.device_id) FILTER (WHERE m.device_id IS NOT NULL), '{}') AS device_ids FROM device_groups g LEFT JOIN device_group_members m ON m.group_id = g.id";
+
+#[derive(Debug)]
+pub struct PostgresDeviceGroupRepository {
+    pool: PgPool,
+}
+
+impl PostgresDeviceGroupRepository {
+    pub async fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    /// To be called after the devices table is created, members reference it.
+    pub async fn init(&self) {
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS device_groups (
+                id UUID PRIMARY KEY,
+                user_id UUID NOT NULL,
+                name TEXT NOT NULL,
+                parent_id UUID REFERENCES device_groups (id)
+            )
+        ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create device_groups table");
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS device_group_members (
+                group_id UUID NOT NULL REFERENCES device_groups (id) ON DELETE CASCADE,
+                device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+                PRIMARY KEY (group_id, device_id)
+            )
+        ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create device_group_members table");
+    }
+
+    async fn fetch_groups(&self, filter: &str, id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let query = format!("{} WHERE {} GROUP BY g.id", SELECT_GROUPS, filter);
+        let rows = sqlx::query(&query)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        Ok(rows.iter().map(group_from_row).collect())
+    }
+}
+
+fn group_from_row(row: &PgRow) -> DeviceGroup {
+    let id: Uuid = row.get("id");
+    let user_id: Uuid = row.get("user_id");
+    let name: String = row.get("name");
+    let device_ids: Vec<Uuid> = row.get("device_ids");
+    let mut group = DeviceGroup::new(&id, &user_id, &name, row.get("parent_id"));
+    group.set_device_ids(device_ids.into_iter().collect());
+    group
+}
+
+impl CreateDeviceGroupRepository for PostgresDeviceGroupRepository {
+    async fn create(&self, group: &DeviceGroup) -> Result<(), DeviceGroupRepositoryError> {
+        let mut tx = self
+            .pool
+            .begin()
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        let query = "INSERT INTO device_groups (id, user_id, name, parent_id) VALUES ($1, $2, $3, $4) ON CONFLICT (id) DO UPDATE SET name = $3, parent_id = $4";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(*group.id())
+            .bind(*group.user_id())
+            .bind(group.name())
+            .bind(group.parent_id().cloned())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(DeviceGroupRepositoryError::Conflict);
+        }
+
+        // members are replaced as a whole
+        sqlx::query("DELETE FROM device_group_members WHERE group_id = $1")
+            .bind(*group.id())
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        let device_ids: Vec<Uuid> = group.device_ids().iter().cloned().collect();
+        sqlx::query("INSERT INTO device_group_members (group_id, device_id) SELECT $1, unnest($2::uuid[])")
+            .bind(*group.id())
+            .bind(device_ids)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        tx.commit()
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))
+    }
+}
+
+impl GetDeviceGroupRepository for PostgresDeviceGroupRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceGroup>, DeviceGroupRepositoryError> {
+        Ok(self.fetch_groups("g.id = $1", id).await?.into_iter().next())
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        self.fetch_groups("g.user_id = $1", user_id).await
+    }
+
+    async fn get_children(&self, parent_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        self.fetch_groups("g.parent_id = $1", parent_id).await
+    }
+}
+
+impl DeleteDeviceGroupRepository for PostgresDeviceGroupRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceGroupRepositoryError> {
+        let query = "DELETE FROM device_groups WHERE id = $1";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeviceGroupRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl UpdateDeviceGroupRepository for PostgresDeviceGroupRepository {
+    fn update(
+        &self,
+        group: &DeviceGroup,
+    ) -> impl Future<Output = Result<(), DeviceGroupRepositoryError>> + Send {
+        self.create(group)
+    }
+}
@@ -0,0 +1,122 @@
+use sqlx::{PgPool, Row, postgres::{PgQueryResult, PgRow}};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_model_repository::{
+        CreateDeviceModelRepository, DeleteDeviceModelRepository, DeviceModelRepositoryError,
+        GetDeviceModelRepository, UpdateDeviceModelRepository,
+    },
+    domain::device_model::DeviceModel,
+    infrastructure::db::postgres::utils::{
+        emittables_from_row, serialize_action_data, serialize_event_data,
+    },
+};
+
+#[derive(Debug)]
+pub struct PostgresDeviceModelRepository {
+    pool: PgPool,
+}
+
+impl PostgresDeviceModelRepository {
+    pub async fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    pub async fn init(&self) {
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS device_models (
+                id UUID PRIMARY KEY,
+                user_id UUID NOT NULL,
+                name TEXT NOT NULL,
+                events JSONB NOT NULL DEFAULT '{}',
+                actions JSONB NOT NULL DEFAULT '{}'
+            )
+        ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create device_models table");
+    }
+}
+
+fn model_from_row(row: &PgRow) -> Result<DeviceModel, DeviceModelRepositoryError> {
+    let id: Uuid = row.get("id");
+    let user_id: Uuid = row.get("user_id");
+    let name: String = row.get("name");
+    let (events, actions) = emittables_from_row(row).map_err(DeviceModelRepositoryError::InternalError)?;
+    Ok(DeviceModel::new(&id, &user_id, &name, events, actions))
+}
+
+impl CreateDeviceModelRepository for PostgresDeviceModelRepository {
+    async fn create(&self, model: &DeviceModel) -> Result<(), DeviceModelRepositoryError> {
+        let query = "INSERT INTO device_models (id, user_id, name, events, actions) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (id) DO UPDATE SET name = $3, events = $4, actions = $5";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(*model.id())
+            .bind(*model.user_id())
+            .bind(model.name())
+            .bind(sqlx::types::Json::from(serialize_event_data(model.events())))
+            .bind(sqlx::types::Json::from(serialize_action_data(model.actions())))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeviceModelRepositoryError::Conflict);
+        }
+        Ok(())
+    }
+}
+
+impl GetDeviceModelRepository for PostgresDeviceModelRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceModel>, DeviceModelRepositoryError> {
+        let query = "SELECT id, user_id, name, events, actions FROM device_models WHERE id = $1";
+        let row = sqlx::query(query)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        match row {
+            Some(row) => Ok(Some(model_from_row(&row)?)),
+            None => Ok(None),
+        }
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceModel>, DeviceModelRepositoryError> {
+        let query = "SELECT id, user_id, name, events, actions FROM device_models WHERE user_id = $1";
+        let rows = sqlx::query(query)
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        let mut models = Vec::new();
+        for row in rows {
+            models.push(model_from_row(&row)?);
+        }
+        Ok(models)
+    }
+}
+
+impl DeleteDeviceModelRepository for PostgresDeviceModelRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceModelRepositoryError> {
+        let query = "DELETE FROM device_models WHERE id = $1";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DeviceModelRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl UpdateDeviceModelRepository for PostgresDeviceModelRepository {
+    fn update(
+        &self,
+        model: &DeviceModel,
+    ) -> impl Future<Output = Result<(), DeviceModelRepositoryError>> + Send {
+        self.create(model)
+    }
+}
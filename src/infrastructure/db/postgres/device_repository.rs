@@ -1,6 +1,7 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use sqlx::{PgPool, Row, postgres::PgQueryResult};
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row, postgres::{PgQueryResult, PgRow}};
 use uuid::Uuid;
 
 use crate::{
@@ -9,11 +10,13 @@ use crate::{
         UpdateDeviceRepository,
     },
     domain::{
-        action::action_emittable::ActionEmittable, device::Device,
-        event::event_emittable::EventEmittable,
+        device::Device, device_filter::DeviceFilter,
+        device_schema::DeviceSchema,
+        state::StateMergePolicy,
+        connectivity::Connectivity,
     },
     infrastructure::db::postgres::utils::{
-        serialize_action_data, serialize_event_data, ActionEmittableDb, EventEmittableDb
+        emittables_from_row, serialize_action_data, serialize_event_data
     },
 };
 
@@ -44,12 +47,84 @@ impl PostgresDeviceRepository {
         .execute(&self.pool)
         .await
         .expect("Failed to create devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS descriptor_set BYTEA")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add descriptor_set column to devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS schema_version INTEGER NOT NULL DEFAULT 1")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add schema_version column to devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS model_id UUID")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add model_id column to devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS labels JSONB NOT NULL DEFAULT '{}'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add labels column to devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS tags JSONB NOT NULL DEFAULT '[]'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add tags column to devices table");
+        sqlx::query("ALTER TABLE devices ADD COLUMN IF NOT EXISTS state_merge_policy TEXT NOT NULL DEFAULT 'event_time'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add state_merge_policy column to devices table");
+        sqlx::query(
+            "ALTER TABLE devices
+            ADD COLUMN IF NOT EXISTS heartbeat_interval BIGINT,
+            ADD COLUMN IF NOT EXISTS connectivity TEXT NOT NULL DEFAULT 'unknown'",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to add connectivity columns to devices table");
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS device_schemas (
+                device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+                version INTEGER NOT NULL,
+                created_at TIMESTAMPTZ NOT NULL,
+                events JSONB NOT NULL DEFAULT '{}',
+                actions JSONB NOT NULL DEFAULT '{}',
+                PRIMARY KEY (device_id, version)
+            )
+        ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create device_schemas table");
     }
 }
 
+fn device_from_row(row: &PgRow) -> Result<Device, DeviceRepositoryError> {
+    let id: Uuid = row.get("id");
+    let user_id: Uuid = row.get("user_id");
+    let physical_id: String = row.get("physical_id");
+    let name: String = row.get("name");
+    let (events, actions) = emittables_from_row(row).map_err(DeviceRepositoryError::InternalError)?;
+    let mut device = Device::new(&id, &physical_id, &user_id, &name, events, actions);
+    device.set_descriptor_set(row.get("descriptor_set"));
+    device.set_schema_version(row.get::<i32, _>("schema_version") as u32);
+    device.set_model_id(row.get("model_id"));
+    device.set_labels(row.get::<sqlx::types::Json<HashMap<String, String>>, _>("labels").0);
+    device.set_tags(row.get::<sqlx::types::Json<BTreeSet<String>>, _>("tags").0);
+    device.set_state_merge_policy(
+        StateMergePolicy::try_from(row.get::<&str, _>("state_merge_policy"))
+            .map_err(DeviceRepositoryError::InternalError)?,
+    );
+    device.set_heartbeat_interval(row.get::<Option<i64>, _>("heartbeat_interval").map(|v| v as u64));
+    device.set_connectivity(
+        Connectivity::try_from(row.get::<&str, _>("connectivity"))
+            .map_err(DeviceRepositoryError::InternalError)?,
+    );
+    // protobuf messages are resolved by the device service, once the model is applied
+    Ok(device)
+}
+
 impl CreateDeviceRepository for PostgresDeviceRepository {
     async fn create(&self, device: &Device) -> Result<(), DeviceRepositoryError> {
-        let query = "INSERT INTO devices (id, user_id, physical_id, name, events, actions) VALUES ($1, $2, $3, $4, $5, $6) ON CONFLICT (id) DO UPDATE SET physical_id = $3, name = $4, events = $5, actions = $6";
+        let query = "INSERT INTO devices (id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14) ON CONFLICT (id) DO UPDATE SET physical_id = $3, name = $4, events = $5, actions = $6, descriptor_set = $7, schema_version = $8, model_id = $9, labels = $10, tags = $11, state_merge_policy = $12, heartbeat_interval = $13, connectivity = $14";
         let result: PgQueryResult = sqlx::query(query)
             .bind(sqlx::types::Uuid::from(*device.id()))
             .bind(sqlx::types::Uuid::from(*device.user_id()))
@@ -61,6 +136,14 @@ impl CreateDeviceRepository for PostgresDeviceRepository {
             .bind(sqlx::types::Json::from(serialize_action_data(
                 &device.actions(),
             )))
+            .bind(device.descriptor_set())
+            .bind(device.schema_version() as i32)
+            .bind(device.model_id().cloned())
+            .bind(sqlx::types::Json::from(device.labels()))
+            .bind(sqlx::types::Json::from(device.tags()))
+            .bind(device.state_merge_policy().to_string())
+            .bind(device.heartbeat_interval().map(|v| v as i64))
+            .bind(device.connectivity().to_string())
             .execute(&self.pool)
             .await
             .map_err(|e| {
@@ -72,6 +155,19 @@ impl CreateDeviceRepository for PostgresDeviceRepository {
             return Err(crate::application::ports::outbound::device_repository::DeviceRepositoryError::Conflict);
         }
 
+        // versions are immutable, an update keeping the version leaves its schema untouched
+        let query = "INSERT INTO device_schemas (device_id, version, created_at, events, actions) VALUES ($1, $2, $3, $4, $5) ON CONFLICT (device_id, version) DO NOTHING";
+        let schema = device.schema(&Utc::now());
+        sqlx::query(query)
+            .bind(*device.id())
+            .bind(schema.version() as i32)
+            .bind(schema.created_at())
+            .bind(sqlx::types::Json::from(serialize_event_data(schema.events())))
+            .bind(sqlx::types::Json::from(serialize_action_data(schema.actions())))
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
         Ok(())
     }
 }
@@ -79,7 +175,7 @@ impl CreateDeviceRepository for PostgresDeviceRepository {
 impl GetDeviceRepository for PostgresDeviceRepository {
     async fn get_by_id(&self, id: Uuid) -> Result<Option<Device>, DeviceRepositoryError> {
         // Query to find a device by its ID
-        let query = "SELECT id, user_id, physical_id, name, events, actions FROM devices WHERE id = $1";
+        let query = "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE id = $1";
         let row = sqlx::query(query)
             .bind(sqlx::types::Uuid::from(id))
             .fetch_optional(&self.pool)
@@ -92,35 +188,11 @@ impl GetDeviceRepository for PostgresDeviceRepository {
             Some(row) => row,
             None => return Ok(None), // Device not found
         };
-        // Extracting the fields from the row
-        let id: Uuid = row.get("id");
-        let user_id: Uuid = row.get("user_id");
-        let physical_id: String = row.get("physical_id");
-        let name: String = row.get("name");
-        let event_data_raw: sqlx::types::Json<HashMap<String, EventEmittableDb>> =
-            row.get("events");
-
-        let mut events = HashMap::new();
-        for (key, value) in event_data_raw.0 {
-            let event_data_type = EventEmittable::try_from(value)
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            events.insert(key, event_data_type);
-        }
-
-        let actions_raw: sqlx::types::Json<HashMap<String, ActionEmittableDb>> = row.get("actions");
-        let mut actions = HashMap::new();
-        for (key, value) in actions_raw.0 {
-            let action = ActionEmittable::try_from(value)
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            actions.insert(key, action);
-        }
-        // Creating the Device instance
-        let device = Device::new(&id, &physical_id, &user_id, &name, events, actions);
-        Ok(Some(device))
+        Ok(Some(device_from_row(&row)?))
     }
 
     async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<Device>, DeviceRepositoryError> {
-        let query = "SELECT id, user_id, physical_id, name, events, actions FROM devices WHERE user_id = $1";
+        let query = "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE user_id = $1";
         let rows = sqlx::query(query)
             .bind(sqlx::types::Uuid::from(user_id))
             .fetch_all(&self.pool)
@@ -132,36 +204,7 @@ impl GetDeviceRepository for PostgresDeviceRepository {
 
         let mut devices = Vec::new();
         for row in rows {
-            let id: Uuid = row.get("id");
-            let user_id: Uuid = row.get("user_id");
-            let physical_id: String = row.get("physical_id");
-            let name: String = row.get("name");
-            let events_raw: sqlx::types::Json<HashMap<String, EventEmittableDb>> =
-                row.get("events");
-            let mut events = HashMap::new();
-            for (key, value) in events_raw.0 {
-                let event = EventEmittable::try_from(value)
-                    .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-                events.insert(key, event);
-            }
-
-            let actions_raw: sqlx::types::Json<HashMap<String, ActionEmittableDb>> =
-                row.get("actions");
-            let mut actions = HashMap::new();
-            for (key, value) in actions_raw.0 {
-                let action = ActionEmittable::try_from(value)
-                    .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-                actions.insert(key, action);
-            }
-
-            devices.push(Device::new(
-                &id,
-                &physical_id,
-                &user_id,
-                &name,
-                events,
-                actions,
-            ))
+            devices.push(device_from_row(&row)?);
         }
         Ok(devices)
     }
@@ -171,7 +214,7 @@ impl GetDeviceRepository for PostgresDeviceRepository {
         physical_id: &str,
     ) -> Result<Option<Device>, DeviceRepositoryError> {
         let query =
-            "SELECT id, user_id, physical_id, name, events, actions FROM devices WHERE physical_id = $1";
+            "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE physical_id = $1";
         let row = sqlx::query(query)
             .bind(physical_id)
             .fetch_optional(&self.pool)
@@ -187,30 +230,74 @@ impl GetDeviceRepository for PostgresDeviceRepository {
             Some(row) => row,
             None => return Ok(None), // Device not found
         };
-        // Extracting the fields from the row
-        let id: Uuid = row.get("id");
-        let user_id: Uuid = row.get("user_id");
-        let physical_id: String = row.get("physical_id");
-        let name: String = row.get("name");
-        let event_data_raw: sqlx::types::Json<HashMap<String, EventEmittableDb>> =
-            row.get("events");
-
-        let mut events = HashMap::new();
-        for (key, value) in event_data_raw.0 {
-            let event = EventEmittable::try_from(value)
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            events.insert(key, event);
+        Ok(Some(device_from_row(&row)?))
+    }
+
+    async fn get_by_filter(
+        &self,
+        user_id: Uuid,
+        filter: &DeviceFilter,
+    ) -> Result<Vec<Device>, DeviceRepositoryError> {
+        // jsonb containment, a device matches when its labels and tags include those of the filter
+        let query = "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE user_id = $1 AND labels @> $2 AND tags @> $3";
+        let rows = sqlx::query(query)
+            .bind(user_id)
+            .bind(sqlx::types::Json::from(filter.labels()))
+            .bind(sqlx::types::Json::from(filter.tags()))
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        let mut devices = Vec::new();
+        for row in rows {
+            devices.push(device_from_row(&row)?);
         }
-        let actions_raw: sqlx::types::Json<HashMap<String, ActionEmittableDb>> = row.get("actions");
-        let mut actions = HashMap::new();
-        for (key, value) in actions_raw.0 {
-            let action = ActionEmittable::try_from(value)
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            actions.insert(key, action);
+        Ok(devices)
+    }
+
+    async fn get_monitored(&self) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let query = "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE heartbeat_interval IS NOT NULL";
+        let rows = sqlx::query(query)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        let mut devices = Vec::new();
+        for row in rows {
+            devices.push(device_from_row(&row)?);
         }
-        // Creating the Device instance
-        let device = Device::new(&id, &physical_id, &user_id, &name, events, actions);
-        Ok(Some(device))
+        Ok(devices)
+    }
+
+    async fn get_by_model_id(&self, model_id: Uuid) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let query = "SELECT id, user_id, physical_id, name, events, actions, descriptor_set, schema_version, model_id, labels, tags, state_merge_policy, heartbeat_interval, connectivity FROM devices WHERE model_id = $1";
+        let rows = sqlx::query(query)
+            .bind(model_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        let mut devices = Vec::new();
+        for row in rows {
+            devices.push(device_from_row(&row)?);
+        }
+        Ok(devices)
+    }
+
+    async fn get_schemas(&self, device_id: Uuid) -> Result<Vec<DeviceSchema>, DeviceRepositoryError> {
+        let query = "SELECT version, created_at, events, actions FROM device_schemas WHERE device_id = $1 ORDER BY version";
+        let rows = sqlx::query(query)
+            .bind(device_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+        let mut schemas = Vec::new();
+        for row in rows {
+            let (events, actions) = emittables_from_row(&row).map_err(DeviceRepositoryError::InternalError)?;
+            let created_at: DateTime<Utc> = row.get("created_at");
+            schemas.push(DeviceSchema::new(row.get::<i32, _>("version") as u32, &created_at, events, actions));
+        }
+        Ok(schemas)
     }
 }
 
@@ -238,4 +325,18 @@ impl UpdateDeviceRepository for PostgresDeviceRepository {
     ) -> impl Future<Output = Result<(), DeviceRepositoryError>> + Send {
         self.create(device)
     }
+
+    async fn update_connectivity(&self, id: Uuid, connectivity: Connectivity) -> Result<(), DeviceRepositoryError> {
+        let query = "UPDATE devices SET connectivity = $1 WHERE id = $2";
+        let result = sqlx::query(query)
+            .bind(connectivity.to_string())
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(DeviceRepositoryError::NotFound);
+        }
+        Ok(())
+    }
 }
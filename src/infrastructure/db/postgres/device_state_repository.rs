@@ -1,14 +1,14 @@
 use std::collections::HashMap;
 
 use serde_json::Value;
-use sqlx::{PgPool, Row};
+use sqlx::{PgPool, Postgres, Row, Transaction, postgres::PgRow};
 
 use crate::{
     application::ports::outbound::device_state_repository::{
         CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError,
         GetDeviceStateRepository, UpdateDeviceStateRepository,
     },
-    domain::{event::event_data_value::EventDataValue, state::DeviceState},
+    domain::{event::event_data_value::EventDataValue, state::{DeviceState, StateMergePolicy, ValueSource}},
 };
 
 #[derive(Debug)]
@@ -33,26 +33,48 @@ impl PostgresDeviceStateRepository {
         .execute(&self.pool)
         .await
         .expect("Failed to create device_states table");
+        sqlx::query("ALTER TABLE device_states ADD COLUMN IF NOT EXISTS sources JSONB NOT NULL DEFAULT '{}'")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add sources column to device_states table");
+        sqlx::query(
+            "ALTER TABLE device_states
+            ADD COLUMN IF NOT EXISTS desired JSONB NOT NULL DEFAULT '{}',
+            ADD COLUMN IF NOT EXISTS desired_update TIMESTAMPTZ,
+            ADD COLUMN IF NOT EXISTS converged_at TIMESTAMPTZ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to add shadow columns to device_states table");
     }
 }
 
 impl CreateDeviceStateRepository for PostgresDeviceStateRepository {
     async fn create(&self, device_state: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
-        let query = "INSERT INTO device_states (device_id, last_update, values) VALUES ($1, $2, $3)
-                     ON CONFLICT (device_id) DO UPDATE SET last_update = $2, values = $3";
+        let query = "INSERT INTO device_states (device_id, last_update, values, sources, desired, desired_update, converged_at)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7)
+                     ON CONFLICT (device_id) DO UPDATE SET last_update = $2, values = $3, sources = $4";
 
         let values: HashMap<String, Value> = device_state
-            .values
-            .clone()
+            .raw_values()
             .into_iter()
             .map(|(k, v)| (k, v.into()))
             .collect();
+        let desired: HashMap<String, Value> = device_state
+            .desired
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone().into()))
+            .collect();
         sqlx::query(query)
             .bind(sqlx::types::Uuid::from(device_state.device_id))
             .bind(chrono::DateTime::<chrono::Utc>::from(
                 device_state.last_update,
             ))
             .bind(sqlx::types::Json::from(values))
+            .bind(sqlx::types::Json::from(device_state.sources()))
+            .bind(sqlx::types::Json::from(desired))
+            .bind(device_state.desired_update)
+            .bind(device_state.converged_at)
             .execute(&self.pool)
             .await
             .map_err(|e: sqlx::Error| {
@@ -68,7 +90,7 @@ impl GetDeviceStateRepository for PostgresDeviceStateRepository {
         &self,
         id: uuid::Uuid,
     ) -> Result<Option<DeviceState>, DeviceStateRepositoryError> {
-        let query = "SELECT device_id, last_update, values FROM device_states WHERE device_id = $1";
+        let query = "SELECT device_id, last_update, values, sources, desired, desired_update, converged_at FROM device_states WHERE device_id = $1";
         let row = sqlx::query(query)
             .bind(sqlx::types::Uuid::from(id))
             .fetch_optional(&self.pool)
@@ -79,26 +101,7 @@ impl GetDeviceStateRepository for PostgresDeviceStateRepository {
             })?;
 
         match row {
-            Some(row) => {
-                let device_id: uuid::Uuid = row.get("device_id");
-                let last_update: chrono::DateTime<chrono::Utc> = row.get("last_update");
-                let values_db: sqlx::types::Json<HashMap<String, Value>> = row.get("values");
-                let mut values = HashMap::new();
-                for (k, v) in values_db.0 {
-                    let val = EventDataValue::try_from(v).map_err(|_| {
-                        DeviceStateRepositoryError::InternalError(format!(
-                            "Invalid data stored for key {}",
-                            k
-                        ))
-                    })?;
-                    values.insert(k, val);
-                }
-                Ok(Some(DeviceState {
-                    device_id,
-                    last_update: last_update.into(),
-                    values,
-                }))
-            }
+            Some(row) => Ok(Some(row_to_device_state(&row)?)),
             None => Ok(None),
         }
     }
@@ -120,7 +123,123 @@ impl DeleteDeviceStateRepository for PostgresDeviceStateRepository {
 }
 
 impl UpdateDeviceStateRepository for PostgresDeviceStateRepository {
-    async fn update(&self, device_state: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
-        self.create(device_state).await
+    async fn merge_values(&self, device_state: &DeviceState, keys: &[String], policy: StateMergePolicy) -> Result<DeviceState, DeviceStateRepositoryError> {
+        let mut tx = self.begin().await?;
+        let mut stored = lock_device_state(&mut tx, device_state).await?;
+        if !stored.merge(device_state.values_of(keys), policy).is_empty() {
+            stored.last_update = device_state.last_update;
+            stored.refresh_convergence(&device_state.last_update);
+            let values: HashMap<String, Value> = stored
+                .raw_values()
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect();
+            sqlx::query("UPDATE device_states SET last_update = $2, values = $3, sources = $4, converged_at = $5 WHERE device_id = $1")
+                .bind(stored.device_id)
+                .bind(stored.last_update)
+                .bind(sqlx::types::Json::from(values))
+                .bind(sqlx::types::Json::from(stored.sources()))
+                .bind(stored.converged_at)
+                .execute(&mut *tx)
+                .await
+                .map_err(|e: sqlx::Error| {
+                    println!("Error merging device state {}: {:?}", stored.device_id, e);
+                    DeviceStateRepositoryError::InternalError(e.to_string())
+                })?;
+        }
+        commit(tx).await?;
+        Ok(stored)
+    }
+
+    async fn set_desired(&self, device_state: &DeviceState) -> Result<DeviceState, DeviceStateRepositoryError> {
+        let mut tx = self.begin().await?;
+        let mut stored = lock_device_state(&mut tx, device_state).await?;
+        let now = device_state.desired_update.unwrap_or(device_state.last_update);
+        stored.set_desired(device_state.desired.clone(), &now);
+        let desired: HashMap<String, Value> = stored
+            .desired
+            .iter()
+            .map(|(k, v)| (k.clone(), v.clone().into()))
+            .collect();
+        sqlx::query("UPDATE device_states SET desired = $2, desired_update = $3, converged_at = $4 WHERE device_id = $1")
+            .bind(stored.device_id)
+            .bind(sqlx::types::Json::from(desired))
+            .bind(stored.desired_update)
+            .bind(stored.converged_at)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e: sqlx::Error| {
+                println!("Error setting desired state {}: {:?}", stored.device_id, e);
+                DeviceStateRepositoryError::InternalError(e.to_string())
+            })?;
+        commit(tx).await?;
+        Ok(stored)
+    }
+}
+
+impl PostgresDeviceStateRepository {
+    async fn begin(&self) -> Result<Transaction<'static, Postgres>, DeviceStateRepositoryError> {
+        self.pool.begin().await.map_err(|e: sqlx::Error| {
+            println!("Error starting device state transaction: {:?}", e);
+            DeviceStateRepositoryError::InternalError(e.to_string())
+        })
+    }
+}
+
+async fn commit(tx: Transaction<'static, Postgres>) -> Result<(), DeviceStateRepositoryError> {
+    tx.commit().await.map_err(|e: sqlx::Error| {
+        println!("Error committing device state transaction: {:?}", e);
+        DeviceStateRepositoryError::InternalError(e.to_string())
+    })
+}
+
+/// Reads the stored state locked until the end of the transaction, the row is created
+/// first so that concurrent writes of a new state wait on its lock.
+async fn lock_device_state(tx: &mut Transaction<'static, Postgres>, device_state: &DeviceState) -> Result<DeviceState, DeviceStateRepositoryError> {
+    sqlx::query("INSERT INTO device_states (device_id, last_update, values) VALUES ($1, $2, '{}') ON CONFLICT (device_id) DO NOTHING")
+        .bind(device_state.device_id)
+        .bind(device_state.last_update)
+        .execute(&mut **tx)
+        .await
+        .map_err(|e: sqlx::Error| {
+            println!("Error creating device state {}: {:?}", device_state.device_id, e);
+            DeviceStateRepositoryError::InternalError(e.to_string())
+        })?;
+    let row = sqlx::query("SELECT device_id, last_update, values, sources, desired, desired_update, converged_at FROM device_states WHERE device_id = $1 FOR UPDATE")
+        .bind(device_state.device_id)
+        .fetch_one(&mut **tx)
+        .await
+        .map_err(|e: sqlx::Error| {
+            println!("Error locking device state {}: {:?}", device_state.device_id, e);
+            DeviceStateRepositoryError::InternalError(e.to_string())
+        })?;
+    row_to_device_state(&row)
+}
+
+fn row_to_device_state(row: &PgRow) -> Result<DeviceState, DeviceStateRepositoryError> {
+    let device_id: uuid::Uuid = row.get("device_id");
+    let last_update: chrono::DateTime<chrono::Utc> = row.get("last_update");
+    let values_db: sqlx::types::Json<HashMap<String, Value>> = row.get("values");
+    let sources: sqlx::types::Json<HashMap<String, ValueSource>> = row.get("sources");
+    let desired_db: sqlx::types::Json<HashMap<String, Value>> = row.get("desired");
+    let values = parse_values(values_db.0)?;
+    let mut device_state = DeviceState::from_parts(device_id, last_update, values, sources.0);
+    device_state.desired = parse_values(desired_db.0)?;
+    device_state.desired_update = row.get("desired_update");
+    device_state.converged_at = row.get("converged_at");
+    Ok(device_state)
+}
+
+fn parse_values(values_db: HashMap<String, Value>) -> Result<HashMap<String, EventDataValue>, DeviceStateRepositoryError> {
+    let mut values = HashMap::new();
+    for (k, v) in values_db {
+        let val = EventDataValue::try_from(v).map_err(|_| {
+            DeviceStateRepositoryError::InternalError(format!(
+                "Invalid data stored for key {}",
+                k
+            ))
+        })?;
+        values.insert(k, val);
     }
+    Ok(values)
 }
@@ -1,5 +1,6 @@
 use std::collections::HashMap;
 
+use chrono::{DateTime, Utc};
 use serde_json::Value;
 use sqlx::{PgPool, Row};
 
@@ -32,12 +33,16 @@ impl PostgresEventRepository {
         .execute(&self.pool)
         .await
         .expect("Failed to create events table");
+        sqlx::query("ALTER TABLE events ADD COLUMN IF NOT EXISTS schema_version INTEGER")
+            .execute(&self.pool)
+            .await
+            .expect("Failed to add schema_version column to events table");
     }
 }
 
 impl CreateEventRepository for PostgresEventRepository {
     async fn create_event(&self, evt: Event, _: &EventFormat) -> Result<(), EventRepositoryError> {
-        let query = "INSERT INTO events (id, device_physical_id, event_name, timestamp, payload) VALUES ($1, $2, $3, $4, $5)
+        let query = "INSERT INTO events (id, device_physical_id, event_name, timestamp, payload, schema_version) VALUES ($1, $2, $3, $4, $5, $6)
                      ON CONFLICT (id, device_physical_id) DO NOTHING";
         let event_data: HashMap<String, Value> = evt
             .payload
@@ -50,6 +55,7 @@ impl CreateEventRepository for PostgresEventRepository {
             .bind(evt.event_name)
             .bind(evt.timestamp)
             .bind(sqlx::types::Json::from(event_data))
+            .bind(evt.schema_version.map(|v| v as i32))
             .execute(&self.pool)
             .await
             .map_err(|e| EventRepositoryError::RepositoryError(e.to_string()))?;
@@ -63,7 +69,7 @@ impl GetEventRepository for PostgresEventRepository {
         &self,
         device_physical_id: &str,
     ) -> Result<Vec<Event>, EventRepositoryError> {
-        let query = "SELECT id, device_physical_id, event_name, timestamp, payload FROM events WHERE device_physical_id = $1";
+        let query = "SELECT id, device_physical_id, event_name, timestamp, payload, schema_version FROM events WHERE device_physical_id = $1";
         let rows = sqlx::query(query)
             .bind(device_physical_id)
             .fetch_all(&self.pool)
@@ -90,10 +96,24 @@ impl GetEventRepository for PostgresEventRepository {
                     event_name: row.get("event_name"),
                     timestamp: row.get("timestamp"),
                     payload,
+                    schema_version: row.get::<Option<i32>, _>("schema_version").map(|v| v as u32),
                 })
             })
             .collect();
 
         events
     }
+
+    async fn get_last_event_time(
+        &self,
+        device_physical_id: &str,
+    ) -> Result<Option<DateTime<Utc>>, EventRepositoryError> {
+        let query = "SELECT MAX(timestamp) AS last_timestamp FROM events WHERE device_physical_id = $1";
+        let row = sqlx::query(query)
+            .bind(device_physical_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| EventRepositoryError::RepositoryError(e.to_string()))?;
+        Ok(row.get("last_timestamp"))
+    }
 }
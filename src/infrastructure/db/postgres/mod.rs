@@ -1,5 +1,8 @@
 pub mod action_repository;
+pub mod device_group_repository;
+pub mod device_model_repository;
 pub mod device_repository;
 pub mod device_state_repository;
 pub mod event_repository;
-pub mod utils;
+pub mod rule_repository;
+pub mod utils;
\ No newline at end of file
@@ -0,0 +1,158 @@
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row, postgres::{PgQueryResult, PgRow}};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::rule_repository::{
+        CreateRuleRepository, DeleteRuleRepository, GetRuleRepository, RuleRepositoryError,
+        UpdateRuleRepository,
+    },
+    domain::rule::{Rule, RuleAction, RuleCondition},
+};
+
+const SELECT_RULES: &str = "SELECT id, user_id, name, device_id, event_name, conditions, action, debounce, cooldown, enabled, matching_since, last_triggered_at FROM rules";
+
+#[derive(Debug)]
+pub struct PostgresRuleRepository {
+    pool: PgPool,
+}
+
+impl PostgresRuleRepository {
+    pub async fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+    /// To be called after the devices table is created, rules reference it.
+    pub async fn init(&self) {
+        sqlx::query(
+            "
+            CREATE TABLE IF NOT EXISTS rules (
+                id UUID PRIMARY KEY,
+                user_id UUID NOT NULL,
+                name TEXT NOT NULL,
+                device_id UUID NOT NULL REFERENCES devices (id) ON DELETE CASCADE,
+                event_name TEXT,
+                conditions JSONB NOT NULL,
+                action JSONB NOT NULL,
+                debounce BIGINT NOT NULL,
+                cooldown BIGINT NOT NULL,
+                enabled BOOLEAN NOT NULL,
+                matching_since TIMESTAMPTZ,
+                last_triggered_at TIMESTAMPTZ
+            )
+        ",
+        )
+        .execute(&self.pool)
+        .await
+        .expect("Failed to create rules table");
+    }
+
+    async fn fetch_rules(&self, filter: &str, id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        let query = format!("{} WHERE {}", SELECT_RULES, filter);
+        let rows = sqlx::query(&query)
+            .bind(id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+        Ok(rows.iter().map(rule_from_row).collect())
+    }
+}
+
+fn rule_from_row(row: &PgRow) -> Rule {
+    let debounce: i64 = row.get("debounce");
+    let cooldown: i64 = row.get("cooldown");
+    Rule {
+        id: row.get("id"),
+        user_id: row.get("user_id"),
+        name: row.get("name"),
+        device_id: row.get("device_id"),
+        event_name: row.get("event_name"),
+        conditions: row.get::<sqlx::types::Json<Vec<RuleCondition>>, _>("conditions").0,
+        action: row.get::<sqlx::types::Json<RuleAction>, _>("action").0,
+        debounce: debounce as u64,
+        cooldown: cooldown as u64,
+        enabled: row.get("enabled"),
+        matching_since: row.get::<Option<DateTime<Utc>>, _>("matching_since"),
+        last_triggered_at: row.get::<Option<DateTime<Utc>>, _>("last_triggered_at"),
+    }
+}
+
+impl CreateRuleRepository for PostgresRuleRepository {
+    async fn create(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let query = "INSERT INTO rules (id, user_id, name, device_id, event_name, conditions, action, debounce, cooldown, enabled, matching_since, last_triggered_at) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12) ON CONFLICT (id) DO NOTHING";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(rule.id)
+            .bind(rule.user_id)
+            .bind(&rule.name)
+            .bind(rule.device_id)
+            .bind(&rule.event_name)
+            .bind(sqlx::types::Json::from(&rule.conditions))
+            .bind(sqlx::types::Json::from(&rule.action))
+            .bind(rule.debounce as i64)
+            .bind(rule.cooldown as i64)
+            .bind(rule.enabled)
+            .bind(rule.matching_since)
+            .bind(rule.last_triggered_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+        if result.rows_affected() == 0 {
+            return Err(RuleRepositoryError::Conflict);
+        }
+        Ok(())
+    }
+}
+
+impl GetRuleRepository for PostgresRuleRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<Rule>, RuleRepositoryError> {
+        Ok(self.fetch_rules("id = $1", id).await?.into_iter().next())
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        self.fetch_rules("user_id = $1", user_id).await
+    }
+
+    async fn get_by_device_id(&self, device_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        self.fetch_rules("device_id = $1", device_id).await
+    }
+}
+
+impl UpdateRuleRepository for PostgresRuleRepository {
+    async fn update(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let query = "UPDATE rules SET name = $2, event_name = $3, conditions = $4, action = $5, debounce = $6, cooldown = $7, enabled = $8, matching_since = $9, last_triggered_at = $10 WHERE id = $1";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(rule.id)
+            .bind(&rule.name)
+            .bind(&rule.event_name)
+            .bind(sqlx::types::Json::from(&rule.conditions))
+            .bind(sqlx::types::Json::from(&rule.action))
+            .bind(rule.debounce as i64)
+            .bind(rule.cooldown as i64)
+            .bind(rule.enabled)
+            .bind(rule.matching_since)
+            .bind(rule.last_triggered_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RuleRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
+
+impl DeleteRuleRepository for PostgresRuleRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), RuleRepositoryError> {
+        let query = "DELETE FROM rules WHERE id = $1";
+        let result: PgQueryResult = sqlx::query(query)
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(RuleRepositoryError::NotFound);
+        }
+        Ok(())
+    }
+}
@@ -1,8 +1,27 @@
 use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
+use sqlx::{Row, postgres::PgRow};
 
-use crate::domain::{action::{action_data_type::ActionDataType, action_emittable::ActionEmittable, action_format::ActionFormat}, event::{event_data_type::EventDataType, event_emittable::EventEmittable, event_format::EventFormat}};
+use crate::domain::{binary_layout::BinaryLayout, action::{action_data_type::ActionDataType, action_emittable::ActionEmittable, action_format::ActionFormat}, event::{event_data_type::EventDataType, event_emittable::EventEmittable, event_format::EventFormat, event_timestamp::TimestampField, computed_field::Expression}, field_constraints::{FieldConstraints, UnknownKeyPolicy}};
+
+/// Events and actions of a device, keyed by their name.
+pub type Emittables = (HashMap<String, EventEmittable>, HashMap<String, ActionEmittable>);
+
+/// Reads the `events` and `actions` columns, shared by devices, their schemas and models.
+pub fn emittables_from_row(row: &PgRow) -> Result<Emittables, String> {
+    let events_raw: sqlx::types::Json<HashMap<String, EventEmittableDb>> = row.get("events");
+    let mut events = HashMap::new();
+    for (key, value) in events_raw.0 {
+        events.insert(key, EventEmittable::try_from(value)?);
+    }
+    let actions_raw: sqlx::types::Json<HashMap<String, ActionEmittableDb>> = row.get("actions");
+    let mut actions = HashMap::new();
+    for (key, value) in actions_raw.0 {
+        actions.insert(key, ActionEmittable::try_from(value)?);
+    }
+    Ok((events, actions))
+}
 
 pub fn serialize_event_data(event_data: &HashMap<String, EventEmittable>) -> HashMap<String, EventEmittableDb> {
     event_data.iter().map(|(k, v)| {
@@ -21,14 +40,29 @@ pub fn serialize_action_data(action_data: &HashMap<String, ActionEmittable>) ->
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct EventEmittableDb {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_field: Option<TimestampField>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub computed: HashMap<String, Expression>,
 }
 
 impl From<&EventEmittable> for EventEmittableDb {
     fn from(event: &EventEmittable) -> Self {
         Self {
             format: event.format().to_string(),
+            binary_layout: event.format().binary_layout().cloned(),
             payload: event.payload().clone().into_iter().map(|(k, v)| (k, v.to_string())).collect(),
+            constraints: event.constraints().clone(),
+            unknown_keys: *event.unknown_keys(),
+            timestamp_field: event.timestamp_field().cloned(),
+            computed: event.computed().clone(),
         }
     }
 }
@@ -37,13 +71,18 @@ impl TryFrom<EventEmittableDb> for EventEmittable {
     type Error = String;
 
     fn try_from(value: EventEmittableDb) -> Result<Self, Self::Error> {
-        let format = EventFormat::try_from(value.format.as_str())?;
+        let format = EventFormat::from_parts(&value.format, value.binary_layout)?;
         let mut payload = HashMap::new();
         for (key, data_type) in value.payload.iter() {
             let data_type = EventDataType::from_str(&data_type)?;
             payload.insert(key.clone(), data_type);
         }
-        Ok(EventEmittable::new(format, payload))
+        let mut emittable = EventEmittable::new(format, payload);
+        emittable.set_constraints(value.constraints);
+        emittable.set_unknown_keys(value.unknown_keys);
+        emittable.set_timestamp_field(value.timestamp_field);
+        emittable.set_computed(value.computed);
+        Ok(emittable)
     }
 }
 
@@ -51,14 +90,23 @@ impl TryFrom<EventEmittableDb> for EventEmittable {
 #[derive(Debug, Serialize, Deserialize)]
 pub(crate) struct ActionEmittableDb {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
 }
 
 impl From<&ActionEmittable> for ActionEmittableDb {
     fn from(action: &ActionEmittable) -> Self {
         Self {
             format: action.format().to_string(),
+            binary_layout: action.format().binary_layout().cloned(),
             payload: action.payload().clone().into_iter().map(|(k, v)| (k, v.to_string())).collect(),
+            constraints: action.constraints().clone(),
+            unknown_keys: *action.unknown_keys(),
         }
     }
 }
@@ -67,12 +115,15 @@ impl TryFrom<ActionEmittableDb> for ActionEmittable {
     type Error = String;
 
     fn try_from(value: ActionEmittableDb) -> Result<Self, Self::Error> {
-        let format = ActionFormat::try_from(value.format.as_str())?;
+        let format = ActionFormat::from_parts(&value.format, value.binary_layout)?;
         let mut payload = HashMap::new();
         for (key, data_type) in value.payload.iter() {
             let data_type = ActionDataType::from_str(&data_type)?;
             payload.insert(key.clone(), data_type);
         }
-        Ok(ActionEmittable::new(format, payload))
+        let mut emittable = ActionEmittable::new(format, payload);
+        emittable.set_constraints(value.constraints);
+        emittable.set_unknown_keys(value.unknown_keys);
+        Ok(emittable)
     }
 }
\ No newline at end of file
@@ -0,0 +1,75 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use tracing::{error, instrument, trace, warn};
+
+use crate::{
+    application::ports::app::AppOutbound,
+    domain::{action::action_data_value::ActionDataValue, device_filter::DeviceFilter},
+    infrastructure::{
+        action_broadcast::{BroadcastTarget, broadcast_action},
+        http::axum::{
+            action_handlers::types::{BroadcastActionRequest, BroadcastActionResponse, BroadcastResultResponse},
+            error::ErrorResponse,
+        },
+    },
+};
+
+/// Sends the action to a set of devices, reports the outcome for each of them.
+#[instrument]
+pub async fn broadcast_action_handler<AO: AppOutbound + 'static>(
+    State(services): State<Arc<AO>>,
+    Path(action_name): Path<String>,
+    Json(request): Json<BroadcastActionRequest>,
+) -> Result<Json<BroadcastActionResponse>, Response> {
+    let target = match (request.device_ids.is_empty(), request.user_id) {
+        (false, None) => BroadcastTarget::Devices(request.device_ids),
+        (true, Some(user_id)) => BroadcastTarget::User {
+            user_id,
+            filter: DeviceFilter::new(request.labels, request.tags),
+            model_id: request.model_id,
+        },
+        _ => {
+            warn!(result = "warn", details = "Invalid broadcast target");
+            return Err(ErrorResponse {
+                status: 400,
+                message: "Either device_ids or user_id must be given".to_string(),
+            }
+            .into_response());
+        }
+    };
+    let mut payload = HashMap::new();
+    for (key, value) in request.payload {
+        match ActionDataValue::try_from(value) {
+            Ok(value) => {
+                payload.insert(key, value);
+            }
+            Err(_) => {
+                warn!(result = "warn", details = format!("Invalid value for key {}", key));
+                return Err(ErrorResponse {
+                    status: 400,
+                    message: format!("Invalid value for key {}", key),
+                }
+                .into_response());
+            }
+        }
+    }
+    let results = match broadcast_action(services.as_ref(), &target, &action_name, &payload).await {
+        Ok(results) => results,
+        Err(err) => {
+            error!(result = "error", details = format!("Failed to broadcast action: {}", err));
+            return Err(ErrorResponse::internal_error().into_response());
+        }
+    };
+    let sent = results.iter().filter(|result| result.result.is_ok()).count();
+    trace!(result = "success", details = format!("Action sent to {}/{} device(s)", sent, results.len()));
+    Ok(Json(BroadcastActionResponse {
+        sent,
+        failed: results.len() - sent,
+        results: results.into_iter().map(BroadcastResultResponse::from).collect(),
+    }))
+}
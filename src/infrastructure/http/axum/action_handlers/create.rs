@@ -2,10 +2,10 @@ use std::{sync::Arc, usize};
 
 use axum::{
     Json, body,
-    extract::{Path, Request, State},
+    extract::{Path, Query, Request, State},
     response::{IntoResponse, Response},
 };
-use chrono::Utc;
+use chrono::{TimeDelta, Utc};
 use tracing::{instrument, trace, warn};
 
 use crate::{
@@ -18,7 +18,7 @@ use crate::{
     },
     domain::{action::action::Action},
     infrastructure::http::axum::{
-        action_handlers::types::{ActionResponse, log_and_return_response},
+        action_handlers::types::{ActionResponse, ScheduleActionQuery, log_and_return_response},
         error::ErrorResponse,
     },
 };
@@ -27,6 +27,7 @@ use crate::{
 pub async fn create_action_handler<AO: AppOutbound>(
     State(services): State<Arc<AO>>,
     Path((device_physical_id, action_name)): Path<(String, String)>,
+    Query(schedule): Query<ScheduleActionQuery>,
     r: Request,
 ) -> Result<Json<ActionResponse>, Response> {
     let request_body = r.into_body();
@@ -62,14 +63,38 @@ pub async fn create_action_handler<AO: AppOutbound>(
         Err(err) => return Err(ErrorResponse::from(err).into_response()),
     };
 
-    let action = match Action::new_checked(&device, &Utc::now(), &action_name, &body_bytes) {
+    let now = Utc::now();
+    let mut action = match Action::new_checked(&device, &now, &action_name, &body_bytes) {
         Ok(event) => event,
         Err(err) => {
             warn!(result = "warn", details = %err);
             return Err(ErrorResponse::from(err).into_response());
         }
     };
-    let action_concerned = match device.actions().get(&action_name) {
+    let sent_at = schedule.scheduled_at.unwrap_or(now).max(now);
+    let expires_at = match schedule.ttl {
+        Some(ttl) => match TimeDelta::try_seconds(ttl as i64).filter(|ttl| *ttl > TimeDelta::zero()).and_then(|ttl| sent_at.checked_add_signed(ttl)) {
+            Some(expires_at) => Some(expires_at),
+            None => {
+                warn!(result = "warn", details = format!("Invalid ttl {}", ttl));
+                return Err(ErrorResponse {
+                    status: 400,
+                    message: "ttl must be a positive number of seconds".to_string(),
+                }
+                .into_response());
+            }
+        },
+        None => None,
+    };
+    if let Err(err) = action.schedule(schedule.scheduled_at, expires_at, &now) {
+        warn!(result = "warn", details = %err);
+        return Err(ErrorResponse {
+            status: 400,
+            message: err,
+        }
+        .into_response());
+    }
+    let action_concerned = match device.action(&action_name) {
         Some(action) => action,
         None => return Err(ErrorResponse::internal_error().into_response()),
     };
@@ -1,3 +1,7 @@
+pub mod broadcast;
 pub mod get;
+pub mod scheduled;
+pub mod schedules;
+pub mod status;
 pub mod create;
 pub mod types;
\ No newline at end of file
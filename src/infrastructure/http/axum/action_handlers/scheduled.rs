@@ -0,0 +1,62 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use tracing::{instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{app::AppOutbound, inbound::action_service::ActionService},
+    infrastructure::http::axum::{
+        action_handlers::types::{ActionResponse, ScheduledActionsQuery, log_and_return_response},
+        error::ErrorResponse,
+    },
+};
+
+#[instrument]
+pub async fn get_scheduled_actions_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Query(query): Query<ScheduledActionsQuery>,
+) -> Result<Json<Vec<ActionResponse>>, Response> {
+    let device_id = query.device_id.map(|id| id.to_string());
+    match services
+        .get_action_service()
+        .get_scheduled_actions(device_id.as_deref())
+        .await
+    {
+        Ok(actions) => {
+            trace!(result = "success");
+            Ok(Json(actions.into_iter().map(ActionResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+/// Cancels an action not sent yet, returns it.
+#[instrument]
+pub async fn cancel_scheduled_action_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(action_id): Path<String>,
+) -> Result<Json<ActionResponse>, Response> {
+    let action_id = match Uuid::parse_str(&action_id) {
+        Ok(id) => id,
+        Err(err) => {
+            warn!(result = "warn", details = %err);
+            return Err(ErrorResponse {
+                status: 400,
+                message: "Invalid action_id format".to_string(),
+            }
+            .into_response());
+        }
+    };
+    match services.get_action_service().cancel_scheduled_action(action_id).await {
+        Ok(action) => {
+            trace!(result = "success");
+            Ok(Json(ActionResponse::from(action)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
@@ -0,0 +1,178 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use chrono_tz::Tz;
+use tracing::{instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::DeviceService},
+    },
+    domain::action::{
+        action::Action,
+        action_data_value::ActionDataValue,
+        action_schedule::{ActionSchedule, ScheduleRun},
+    },
+    infrastructure::http::axum::{
+        action_handlers::types::{
+            ActionScheduleResponse, ActionSchedulesQuery, CreateActionScheduleRequest,
+            log_and_return_response,
+        },
+        error::ErrorResponse,
+    },
+};
+
+#[instrument]
+pub async fn create_action_schedule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Json(request): Json<CreateActionScheduleRequest>,
+) -> Result<Json<ActionScheduleResponse>, Response> {
+    let timezone = match request.timezone.parse::<Tz>() {
+        Ok(timezone) => timezone,
+        Err(err) => {
+            warn!(result = "warn", details = %err);
+            return Err(ErrorResponse {
+                status: 400,
+                message: format!("Unknown time zone '{}'", request.timezone),
+            }
+            .into_response());
+        }
+    };
+    let device = match services
+        .get_device_service()
+        .get_device_by_physical_id(&request.device_id)
+        .await
+    {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            warn!(
+                result = "warn",
+                details = format!("Device with ID {} not found in DB", &request.device_id)
+            );
+            return Err(ErrorResponse {
+                status: 404,
+                message: "Device not found".to_string(),
+            }
+            .into_response());
+        }
+        Err(err) => return Err(ErrorResponse::from(err).into_response()),
+    };
+    let mut payload = HashMap::new();
+    for (key, value) in request.payload {
+        match ActionDataValue::try_from(value) {
+            Ok(value) => {
+                payload.insert(key, value);
+            }
+            Err(_) => {
+                warn!(result = "warn", details = format!("Invalid value for key {}", key));
+                return Err(ErrorResponse {
+                    status: 400,
+                    message: format!("Invalid value for key {}", key),
+                }
+                .into_response());
+            }
+        }
+    }
+    // the payload is validated once, each run sends it as is
+    let now = Utc::now();
+    let action = match Action::from_values(&device, &now, &request.action_name, payload) {
+        Ok(action) => action,
+        Err(err) => {
+            warn!(result = "warn", details = %err);
+            return Err(ErrorResponse::from(err).into_response());
+        }
+    };
+    let schedule = match ActionSchedule::new(&action, request.cron, timezone, &now) {
+        Ok(schedule) => schedule,
+        Err(err) => {
+            warn!(result = "warn", details = %err);
+            return Err(ErrorResponse {
+                status: 400,
+                message: err,
+            }
+            .into_response());
+        }
+    };
+    let action_concerned = match device.action(&request.action_name) {
+        Some(action) => action,
+        None => return Err(ErrorResponse::internal_error().into_response()),
+    };
+    match services
+        .get_action_service()
+        .create_action_schedule(schedule.clone(), action_concerned.format())
+        .await
+    {
+        Ok(_) => {
+            trace!(result = "success");
+            Ok(Json(ActionScheduleResponse::from(schedule)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_action_schedules_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Query(query): Query<ActionSchedulesQuery>,
+) -> Result<Json<Vec<ActionScheduleResponse>>, Response> {
+    let device_id = query.device_id.map(|id| id.to_string());
+    match services
+        .get_action_service()
+        .get_action_schedules(device_id.as_deref())
+        .await
+    {
+        Ok(schedules) => {
+            trace!(result = "success");
+            Ok(Json(schedules.into_iter().map(ActionScheduleResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn delete_action_schedule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<ActionScheduleResponse>, Response> {
+    let schedule_id = parse_schedule_id(&schedule_id)?;
+    match services.get_action_service().delete_action_schedule(schedule_id).await {
+        Ok(schedule) => {
+            trace!(result = "success");
+            Ok(Json(ActionScheduleResponse::from(schedule)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+/// Run history of the schedule, latest first.
+#[instrument]
+pub async fn get_schedule_runs_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(schedule_id): Path<String>,
+) -> Result<Json<Vec<ScheduleRun>>, Response> {
+    let schedule_id = parse_schedule_id(&schedule_id)?;
+    match services.get_action_service().get_schedule_runs(schedule_id).await {
+        Ok(runs) => {
+            trace!(result = "success");
+            Ok(Json(runs))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+fn parse_schedule_id(schedule_id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(schedule_id).map_err(|err| {
+        warn!(result = "warn", details = %err);
+        ErrorResponse {
+            status: 400,
+            message: "Invalid schedule_id format".to_string(),
+        }
+    })
+}
@@ -0,0 +1,95 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, State},
+    response::{IntoResponse, Response},
+};
+use chrono::Utc;
+use tracing::{instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::DeviceService},
+    },
+    infrastructure::http::axum::{
+        action_handlers::types::{AcknowledgeActionRequest, ActionResponse, log_and_return_response},
+        error::ErrorResponse,
+    },
+};
+
+#[instrument]
+pub async fn get_action_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(action_id): Path<String>,
+) -> Result<Json<ActionResponse>, Response> {
+    let action_id = parse_action_id(&action_id)?;
+    match services.get_action_service().get_action(action_id).await {
+        Ok(action) => {
+            trace!(result = "success");
+            Ok(Json(ActionResponse::from(action)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+/// Acknowledgement of an action by the device it was sent to.
+#[instrument(skip(payload))]
+pub async fn acknowledge_action_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(action_id): Path<String>,
+    Json(payload): Json<AcknowledgeActionRequest>,
+) -> Result<Json<ActionResponse>, Response> {
+    let action_id = parse_action_id(&action_id)?;
+    if !payload.status.is_acknowledgement() {
+        warn!(result = "warn", details = format!("Devices cannot report the {} status", payload.status));
+        return Err(ErrorResponse {
+            status: 400,
+            message: format!("Devices cannot report the {} status", payload.status),
+        }
+        .into_response());
+    }
+    let device = match services
+        .get_device_service()
+        .get_device_by_physical_id(&payload.device_id)
+        .await
+    {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            warn!(
+                result = "warn",
+                details = format!("Device with ID {} not found in DB", &payload.device_id)
+            );
+            return Err(ErrorResponse {
+                status: 404,
+                message: "Device not found".to_string(),
+            }
+            .into_response());
+        }
+        Err(err) => return Err(ErrorResponse::from(err).into_response()),
+    };
+    let timestamp = payload.timestamp.unwrap_or_else(Utc::now);
+    match services
+        .get_action_service()
+        .update_action_status(&device.id().to_string(), action_id, payload.status, &timestamp)
+        .await
+    {
+        Ok(action) => {
+            trace!(result = "success");
+            Ok(Json(ActionResponse::from(action)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+fn parse_action_id(action_id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(action_id).map_err(|err| {
+        warn!(result = "warn", details = %err);
+        ErrorResponse {
+            status: 400,
+            message: "Invalid action_id format".to_string(),
+        }
+    })
+}
@@ -1,21 +1,22 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 use axum::response::{IntoResponse, Response};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{error, warn};
 use uuid::Uuid;
 
 use crate::application::ports::inbound::{action_service::ActionServiceError};
-use crate::domain::action::action::Action;
-use crate::infrastructure::http::axum::error::ErrorResponse;
+use crate::domain::{action::{action::Action, action_schedule::ActionSchedule, action_status::{ActionStatus, ActionTransition}}, cron::CronExpression};
+use crate::infrastructure::{action_broadcast::BroadcastResult, http::axum::error::ErrorResponse};
 
 
 
 
 pub fn log_and_return_response(err: ActionServiceError) -> Response {
     match &err {
+        ActionServiceError::NotFound => warn!(result = "warn", details = "action not found"),
         ActionServiceError::InvalidInput(err) => warn!(result = "warn", details = format!("invalid input: {}", err)),
         ActionServiceError::InternalError(err) => error!(result = "error", details = format!("internal error: {}", err)),
     };
@@ -29,6 +30,14 @@ pub struct ActionResponse {
     pub action_name: String,
     pub timestamp: DateTime<Utc>,
     pub payload: HashMap<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    pub status: ActionStatus,
+    pub transitions: Vec<ActionTransition>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub scheduled_at: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
 }
 
 impl From<Action> for ActionResponse {
@@ -40,6 +49,128 @@ impl From<Action> for ActionResponse {
             action_name: event.action_name,
             timestamp: event.timestamp,
             payload,
+            schema_version: event.schema_version,
+            status: event.status,
+            transitions: event.transitions,
+            scheduled_at: event.scheduled_at,
+            expires_at: event.expires_at,
         }
     }
-}
\ No newline at end of file
+}
+
+/// When to send an action and how long it stays valid, in seconds from its sending.
+#[derive(Debug, Deserialize)]
+pub struct ScheduleActionQuery {
+    pub scheduled_at: Option<DateTime<Utc>>,
+    pub ttl: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledActionsQuery {
+    pub device_id: Option<Uuid>,
+}
+
+/// Recurring action, `cron` is evaluated in the `timezone` named in the IANA database.
+#[derive(Debug, Deserialize)]
+pub struct CreateActionScheduleRequest {
+    /// Physical ID of the device the actions are sent to.
+    pub device_id: String,
+    pub action_name: String,
+    #[serde(default)]
+    pub payload: HashMap<String, Value>,
+    pub cron: CronExpression,
+    pub timezone: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ActionSchedulesQuery {
+    pub device_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct ActionScheduleResponse {
+    pub id: Uuid,
+    pub device_id: String,
+    pub action_name: String,
+    pub payload: HashMap<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
+    pub cron: CronExpression,
+    pub timezone: String,
+    pub created_at: DateTime<Utc>,
+    pub next_run_at: Option<DateTime<Utc>>,
+}
+
+impl From<ActionSchedule> for ActionScheduleResponse {
+    fn from(schedule: ActionSchedule) -> Self {
+        let payload = schedule.payload.into_iter().map(|(k, v)| (k, v.into())).collect();
+        ActionScheduleResponse {
+            id: schedule.id,
+            device_id: schedule.device_id,
+            action_name: schedule.action_name,
+            payload,
+            schema_version: schedule.schema_version,
+            cron: schedule.cron,
+            timezone: schedule.timezone.name().to_string(),
+            created_at: schedule.created_at,
+            next_run_at: schedule.next_run_at,
+        }
+    }
+}
+
+/// Action sent to the devices listed by physical ID, or to the devices of the user having
+/// all the labels and tags, of the model when one is given.
+#[derive(Debug, Deserialize)]
+pub struct BroadcastActionRequest {
+    #[serde(default)]
+    pub device_ids: Vec<String>,
+    pub user_id: Option<Uuid>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    pub model_id: Option<Uuid>,
+    #[serde(default)]
+    pub payload: HashMap<String, Value>,
+}
+
+#[derive(Serialize)]
+pub struct BroadcastResultResponse {
+    /// Physical ID of the device.
+    pub device_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<ActionResponse>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl From<BroadcastResult> for BroadcastResultResponse {
+    fn from(result: BroadcastResult) -> Self {
+        let (action, error) = match result.result {
+            Ok(action) => (Some(ActionResponse::from(action)), None),
+            Err(err) => (None, Some(err)),
+        };
+        BroadcastResultResponse {
+            device_id: result.physical_id,
+            action,
+            error,
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct BroadcastActionResponse {
+    pub sent: usize,
+    pub failed: usize,
+    pub results: Vec<BroadcastResultResponse>,
+}
+
+#[derive(Deserialize)]
+pub struct AcknowledgeActionRequest {
+    /// Physical ID of the device the action was sent to.
+    pub device_id: String,
+    pub status: ActionStatus,
+    /// Time of the transition, the reception time when missing.
+    #[serde(default)]
+    pub timestamp: Option<DateTime<Utc>>,
+}
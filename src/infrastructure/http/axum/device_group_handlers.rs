@@ -0,0 +1,281 @@
+use std::{collections::BTreeSet, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::{error, instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{
+            device_group_service::{DeviceGroupService, DeviceGroupServiceError},
+            device_service::{DeviceService, DeviceServiceError},
+            device_state_service::DeviceStateService,
+        },
+    },
+    domain::device_group::DeviceGroup,
+    infrastructure::http::axum::{
+        device_handlers::{types::DeviceResponse, utils::log_and_return_response as log_and_return_device_response},
+        device_state_handlers::{DeviceStateResponse, log_and_return_response as log_and_return_state_response},
+        error::ErrorResponse,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDeviceGroupRequest {
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDeviceGroupRequest {
+    pub name: Option<String>,
+    /// A null parent makes the group a root group.
+    #[serde(default, deserialize_with = "deserialize_parent_id")]
+    pub parent_id: Option<Option<Uuid>>,
+}
+
+fn deserialize_parent_id<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<Uuid>>, D::Error> {
+    Option::<Uuid>::deserialize(deserializer).map(Some)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceGroupsQuery {
+    pub user_id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct DeviceGroupResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<Uuid>,
+    pub device_ids: BTreeSet<Uuid>,
+}
+
+impl From<DeviceGroup> for DeviceGroupResponse {
+    fn from(group: DeviceGroup) -> Self {
+        DeviceGroupResponse {
+            id: *group.id(),
+            user_id: *group.user_id(),
+            name: group.name().to_string(),
+            parent_id: group.parent_id().cloned(),
+            device_ids: group.device_ids().clone(),
+        }
+    }
+}
+
+fn parse_id(id: &str, kind: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(id).map_err(|_| {
+        warn!(
+            result = "warn",
+            details = format!("Invalid {} id provided : {}", kind, id)
+        );
+        ErrorResponse {
+            status: 400,
+            message: format!("Invalid {} ID", kind),
+        }
+    })
+}
+
+fn log_and_return_response(err: DeviceGroupServiceError) -> Response {
+    match &err {
+        DeviceGroupServiceError::InternalError(e) => error!(result = "error", details = %e),
+        e => warn!(result = "warn", details = %e),
+    }
+    ErrorResponse::from(err).into_response()
+}
+
+#[instrument]
+pub async fn create_device_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Json(payload): Json<CreateDeviceGroupRequest>,
+) -> Result<Json<DeviceGroupResponse>, Response> {
+    let service = services.get_device_group_service();
+    let group = DeviceGroup::new(&Uuid::new_v4(), &payload.user_id, &payload.name, payload.parent_id);
+    match service.create_device_group(&group).await {
+        Ok(group) => {
+            trace!(result = "success");
+            Ok(Json(DeviceGroupResponse::from(group)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_device_groups_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Query(query): Query<DeviceGroupsQuery>,
+) -> Result<Json<Vec<DeviceGroupResponse>>, Response> {
+    let service = services.get_device_group_service();
+    match service.get_device_groups_by_user_id(query.user_id).await {
+        Ok(groups) => {
+            trace!(result = "success");
+            Ok(Json(groups.into_iter().map(DeviceGroupResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_device_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+) -> Result<Json<DeviceGroupResponse>, Response> {
+    let service = services.get_device_group_service();
+    let id = parse_id(&group_id, "device group")?;
+    match service.get_device_group(id).await {
+        Ok(Some(group)) => {
+            trace!(result = "success");
+            Ok(Json(DeviceGroupResponse::from(group)))
+        }
+        Ok(None) => Err(log_and_return_response(DeviceGroupServiceError::NotFound)),
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_device_group_children_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<DeviceGroupResponse>>, Response> {
+    let service = services.get_device_group_service();
+    let id = parse_id(&group_id, "device group")?;
+    match service.get_device_group_children(id).await {
+        Ok(groups) => {
+            trace!(result = "success");
+            Ok(Json(groups.into_iter().map(DeviceGroupResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn update_device_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+    Json(payload): Json<UpdateDeviceGroupRequest>,
+) -> Result<Json<DeviceGroupResponse>, Response> {
+    let service = services.get_device_group_service();
+    let id = parse_id(&group_id, "device group")?;
+    match service.update_device_group(id, payload.name, payload.parent_id, None).await {
+        Ok(group) => {
+            trace!(result = "success");
+            Ok(Json(DeviceGroupResponse::from(group)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn delete_device_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+) -> Result<(), Response> {
+    let service = services.get_device_group_service();
+    let id = parse_id(&group_id, "device group")?;
+    match service.delete_device_group(id).await {
+        Ok(_) => {
+            trace!(result = "success");
+            Ok(())
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn add_device_to_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path((group_id, device_id)): Path<(String, String)>,
+) -> Result<Json<DeviceGroupResponse>, Response> {
+    let id = parse_id(&group_id, "device group")?;
+    let device_id = parse_id(&device_id, "device")?;
+    // only existing devices can join a group
+    match services.get_device_service().get_device(device_id).await {
+        Ok(Some(_)) => {}
+        Ok(None) => return Err(ErrorResponse { status: 404, message: "Device not found".to_string() }.into_response()),
+        Err(err) => return Err(log_and_return_device_response(err)),
+    }
+    match services.get_device_group_service().add_device_to_group(id, device_id).await {
+        Ok(group) => {
+            trace!(result = "success");
+            Ok(Json(DeviceGroupResponse::from(group)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn remove_device_from_group_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path((group_id, device_id)): Path<(String, String)>,
+) -> Result<Json<DeviceGroupResponse>, Response> {
+    let id = parse_id(&group_id, "device group")?;
+    let device_id = parse_id(&device_id, "device")?;
+    match services.get_device_group_service().remove_device_from_group(id, device_id).await {
+        Ok(group) => {
+            trace!(result = "success");
+            Ok(Json(DeviceGroupResponse::from(group)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+/// Devices of the group and of all its sub-groups.
+#[instrument]
+pub async fn get_device_group_devices_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<DeviceResponse>>, Response> {
+    let id = parse_id(&group_id, "device group")?;
+    let device_ids = services
+        .get_device_group_service()
+        .get_device_group_device_ids(id)
+        .await
+        .map_err(log_and_return_response)?;
+    let device_service = services.get_device_service();
+    let mut devices = Vec::with_capacity(device_ids.len());
+    for device_id in device_ids {
+        match device_service.get_device(device_id).await {
+            Ok(Some(device)) => devices.push(DeviceResponse::from(device)),
+            // members may refer to devices deleted since
+            Ok(None) | Err(DeviceServiceError::NotFound) => {}
+            Err(err) => return Err(log_and_return_device_response(err)),
+        }
+    }
+    trace!(result = "success");
+    Ok(Json(devices))
+}
+
+/// Latest state of every device of the group and of its sub-groups, devices without state are skipped.
+#[instrument]
+pub async fn get_device_group_states_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(group_id): Path<String>,
+) -> Result<Json<Vec<DeviceStateResponse>>, Response> {
+    let id = parse_id(&group_id, "device group")?;
+    let device_ids = services
+        .get_device_group_service()
+        .get_device_group_device_ids(id)
+        .await
+        .map_err(log_and_return_response)?;
+    let device_state_service = services.get_device_state_service();
+    let mut states = Vec::with_capacity(device_ids.len());
+    for device_id in device_ids {
+        match device_state_service.get_device_state(device_id).await {
+            Ok(Some(state)) => states.push(DeviceStateResponse::from(state)),
+            Ok(None) => {}
+            Err(err) => return Err(log_and_return_state_response(err)),
+        }
+    }
+    trace!(result = "success");
+    Ok(Json(states))
+}
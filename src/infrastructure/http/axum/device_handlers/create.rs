@@ -41,7 +41,7 @@ pub async fn create_device_handler<AO: AppOutbound>(
     let events = into_event_emittable(payload.events)?;
     let actions = into_action_emittable(payload.actions)?;
 
-    let device = Device::new(
+    let mut device = Device::new(
         &Uuid::new_v4(),
         &payload.physical_id,
         &payload.user_id,
@@ -49,11 +49,16 @@ pub async fn create_device_handler<AO: AppOutbound>(
         events,
         actions
     );
+    device.set_descriptor_set(payload.descriptor_set);
+    device.set_model_id(payload.model_id);
+    device.set_labels(payload.labels);
+    device.set_tags(payload.tags);
+    device.set_state_merge_policy(payload.state_merge_policy);
+    device.set_heartbeat_interval(payload.heartbeat_interval);
     match service.create_device(&device).await {
         Ok(device) => {
             let events: HashMap<String, EventEmittableSerializable> = device
-                .events()
-                .clone()
+                .effective_events()
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect();
@@ -64,6 +69,13 @@ pub async fn create_device_handler<AO: AppOutbound>(
                 user_id: device.user_id().clone(),
                 name: device.name().to_string(),
                 events,
+                schema_version: device.schema_version(),
+                model_id: device.model_id().cloned(),
+                labels: device.labels().clone(),
+                tags: device.tags().clone(),
+                state_merge_policy: device.state_merge_policy(),
+                heartbeat_interval: device.heartbeat_interval(),
+                connectivity: device.connectivity(),
             }))
         }
         Err(err) => Err(log_and_return_response(err)),
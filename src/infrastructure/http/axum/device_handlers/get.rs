@@ -2,7 +2,7 @@ use std::{collections::HashMap, str::FromStr, sync::Arc};
 
 use axum::{
     Json,
-    extract::{Path, State},
+    extract::{Path, Query, State},
     response::{IntoResponse, Response},
 };
 use tracing::{instrument, trace, warn};
@@ -12,7 +12,7 @@ use crate::{
     application::ports::{app::AppOutbound, inbound::device_service::DeviceService},
     infrastructure::http::axum::{
         device_handlers::{
-            types::{DeviceResponse, EventEmittableSerializable},
+            types::{DeviceResponse, EventEmittableSerializable, parse_device_filter},
             utils::log_and_return_response,
         },
         error::ErrorResponse,
@@ -43,8 +43,7 @@ pub async fn get_device_handler<AO: AppOutbound>(
     match service.get_device(id).await {
         Ok(Some(device)) => {
             let events: HashMap<String, EventEmittableSerializable> = device
-                .events()
-                .clone()
+                .effective_events()
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect();
@@ -55,6 +54,13 @@ pub async fn get_device_handler<AO: AppOutbound>(
                 user_id: device.user_id().clone(),
                 name: device.name().to_string(),
                 events,
+                schema_version: device.schema_version(),
+                model_id: device.model_id().cloned(),
+                labels: device.labels().clone(),
+                tags: device.tags().clone(),
+                state_merge_policy: device.state_merge_policy(),
+                heartbeat_interval: device.heartbeat_interval(),
+                connectivity: device.connectivity(),
             }))
         }
         Ok(None) => {
@@ -82,8 +88,7 @@ pub async fn get_device_by_physical_id<AO: AppOutbound>(
     match service.get_device_by_physical_id(&physical_id).await {
         Ok(Some(device)) => {
             let events: HashMap<String, EventEmittableSerializable> = device
-                .events()
-                .clone()
+                .effective_events()
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect();
@@ -94,6 +99,13 @@ pub async fn get_device_by_physical_id<AO: AppOutbound>(
                 user_id: device.user_id().clone(),
                 name: device.name().to_string(),
                 events,
+                schema_version: device.schema_version(),
+                model_id: device.model_id().cloned(),
+                labels: device.labels().clone(),
+                tags: device.tags().clone(),
+                state_merge_policy: device.state_merge_policy(),
+                heartbeat_interval: device.heartbeat_interval(),
+                connectivity: device.connectivity(),
             }))
         }
         Ok(None) => {
@@ -114,16 +126,36 @@ pub async fn get_device_by_physical_id<AO: AppOutbound>(
 #[instrument]
 pub async fn get_devices_handler<AO: AppOutbound>(
     State(services): State<Arc<AO>>,
+    Query(query): Query<Vec<(String, String)>>,
 ) -> Result<Json<Vec<DeviceResponse>>, Response> {
     let service = services.get_device_service();
     let uuid = Uuid::from_str("4a78a953-99bc-4a08-932e-956ef3f7d8fc").unwrap();
-    match service.get_devices_by_user_id(uuid).await {
+    // devices of every user having a heartbeat interval, read by the connectivity monitor
+    let monitored = query.iter().any(|(key, value)| key == "monitored" && value == "true");
+    let filter = match parse_device_filter(query) {
+        Ok(filter) => filter,
+        Err(err) => {
+            warn!(result = "warn", details = %err);
+            return Err(ErrorResponse {
+                status: 400,
+                message: err,
+            }
+            .into_response());
+        }
+    };
+    let devices = if monitored {
+        service.get_monitored_devices().await
+    } else if filter.is_empty() {
+        service.get_devices_by_user_id(uuid).await
+    } else {
+        service.get_devices_by_filter(uuid, &filter).await
+    };
+    match devices {
         Ok(devices) => {
             let mut device_responses = Vec::new();
             for device in devices {
                 let events: HashMap<String, EventEmittableSerializable> = device
-                    .events()
-                    .clone()
+                    .effective_events()
                     .into_iter()
                     .map(|(k, v)| (k, v.into()))
                     .collect();
@@ -133,6 +165,13 @@ pub async fn get_devices_handler<AO: AppOutbound>(
                     user_id: device.user_id().clone(),
                     name: device.name().to_string(),
                     events,
+                    schema_version: device.schema_version(),
+                    model_id: device.model_id().cloned(),
+                    labels: device.labels().clone(),
+                    tags: device.tags().clone(),
+                    state_merge_policy: device.state_merge_policy(),
+                    heartbeat_interval: device.heartbeat_interval(),
+                    connectivity: device.connectivity(),
                 })
             }
             trace!(result = "success");
@@ -2,5 +2,6 @@ pub mod create;
 pub mod get;
 pub mod update;
 pub mod delete;
+pub mod schemas;
 pub mod utils;
 pub mod types;
\ No newline at end of file
@@ -0,0 +1,71 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::Response,
+};
+use serde::Deserialize;
+use tracing::{instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{app::AppOutbound, inbound::device_service::DeviceService},
+    domain::device_schema::SchemaDiff,
+    infrastructure::http::axum::{
+        device_handlers::{types::DeviceSchemaResponse, utils::log_and_return_response},
+        error::ErrorResponse,
+    },
+};
+
+fn parse_device_id(device_id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(device_id).map_err(|_| {
+        warn!(
+            result = "warn",
+            details = format!("Invalid device id provided : {}", device_id)
+        );
+        ErrorResponse {
+            status: 400,
+            message: "Invalid device ID".to_string(),
+        }
+    })
+}
+
+#[instrument]
+pub async fn get_device_schemas_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(device_id): Path<String>,
+) -> Result<Json<Vec<DeviceSchemaResponse>>, Response> {
+    let service = services.get_device_service();
+    let id = parse_device_id(&device_id)?;
+    match service.get_device_schemas(id).await {
+        Ok(schemas) => {
+            trace!(result = "success");
+            Ok(Json(schemas.into_iter().map(DeviceSchemaResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SchemaDiffQuery {
+    pub from: u32,
+    pub to: u32,
+}
+
+#[instrument]
+pub async fn diff_device_schemas_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(device_id): Path<String>,
+    Query(query): Query<SchemaDiffQuery>,
+) -> Result<Json<SchemaDiff>, Response> {
+    let service = services.get_device_service();
+    let id = parse_device_id(&device_id)?;
+    match service.diff_device_schemas(id, query.from, query.to).await {
+        Ok(diff) => {
+            trace!(result = "success");
+            Ok(Json(diff))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
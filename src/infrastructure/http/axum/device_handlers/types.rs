@@ -1,10 +1,12 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
+use base64::Engine;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use uuid::Uuid;
 
-use crate::domain::{action::action_emittable::ActionEmittable, event::event_emittable::EventEmittable};
+use crate::domain::{binary_layout::BinaryLayout, device::Device, device_filter::DeviceFilter, device_schema::DeviceSchema, action::action_emittable::ActionEmittable, event::{event_emittable::EventEmittable, event_timestamp::TimestampField, computed_field::Expression}, field_constraints::{FieldConstraints, UnknownKeyPolicy}, state::StateMergePolicy, connectivity::Connectivity};
 
 pub struct CreateDeviceRequest {
     pub physical_id: String,
@@ -12,6 +14,12 @@ pub struct CreateDeviceRequest {
     pub name: String,
     pub events: HashMap<String, EventEmittableSerializable>,
     pub actions: HashMap<String, ActionEmittableSerializable>,
+    pub descriptor_set: Option<Vec<u8>>,
+    pub model_id: Option<Uuid>,
+    pub labels: HashMap<String, String>,
+    pub tags: BTreeSet<String>,
+    pub state_merge_policy: StateMergePolicy,
+    pub heartbeat_interval: Option<u64>,
 }
 
 impl TryFrom<Value> for CreateDeviceRequest {
@@ -53,6 +61,11 @@ impl TryFrom<Value> for CreateDeviceRequest {
                         .iter()
                         .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
                         .collect(),
+                    binary_layout: parse_binary_layout(value, key)?,
+                    constraints: parse_constraints(value, key)?,
+                    unknown_keys: parse_unknown_keys(value, key)?,
+                    timestamp_field: parse_timestamp_field(value, key)?,
+                    computed: parse_computed(value, key)?,
                 };
                 events.insert(key.clone(), event);
             }
@@ -74,6 +87,9 @@ impl TryFrom<Value> for CreateDeviceRequest {
                         .iter()
                         .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
                         .collect(),
+                    binary_layout: parse_binary_layout(value, key)?,
+                    constraints: parse_constraints(value, key)?,
+                    unknown_keys: parse_unknown_keys(value, key)?,
                 };
                 actions.insert(key.clone(), action);
             }
@@ -83,16 +99,139 @@ impl TryFrom<Value> for CreateDeviceRequest {
             physical_id,
             name,
             events,
-            actions
+            actions,
+            descriptor_set: parse_descriptor_set(&value)?,
+            model_id: parse_model_id(&value)?.flatten(),
+            labels: parse_labels(&value)?.unwrap_or_default(),
+            tags: parse_tags(&value)?.unwrap_or_default(),
+            state_merge_policy: parse_state_merge_policy(&value)?.unwrap_or_default(),
+            heartbeat_interval: parse_heartbeat_interval(&value)?.flatten(),
         })
     }
 }
 
+/// `None` when the model ID is missing, `Some(None)` when it is null.
+fn parse_model_id(value: &Value) -> Result<Option<Option<Uuid>>, String> {
+    match value.get("model_id") {
+        Some(Value::Null) => Ok(Some(None)),
+        Some(id) => id
+            .as_str()
+            .and_then(|s| Uuid::parse_str(s).ok())
+            .map(|id| Some(Some(id)))
+            .ok_or_else(|| String::from("Invalid model_id format")),
+        None => Ok(None),
+    }
+}
+
+fn parse_labels(value: &Value) -> Result<Option<HashMap<String, String>>, String> {
+    match value.get("labels") {
+        Some(labels) => serde_json::from_value(labels.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid labels : {}", e)),
+        None => Ok(None),
+    }
+}
+
+fn parse_tags(value: &Value) -> Result<Option<BTreeSet<String>>, String> {
+    match value.get("tags") {
+        Some(tags) => serde_json::from_value(tags.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid tags : {}", e)),
+        None => Ok(None),
+    }
+}
+
+fn parse_state_merge_policy(value: &Value) -> Result<Option<StateMergePolicy>, String> {
+    match value.get("state_merge_policy") {
+        Some(policy) => policy
+            .as_str()
+            .ok_or_else(|| String::from("Invalid state_merge_policy format"))
+            .and_then(StateMergePolicy::try_from)
+            .map(Some),
+        None => Ok(None),
+    }
+}
+
+/// Seconds, `None` when the interval is missing, `Some(None)` when it is null.
+fn parse_heartbeat_interval(value: &Value) -> Result<Option<Option<u64>>, String> {
+    match value.get("heartbeat_interval") {
+        Some(Value::Null) => Ok(Some(None)),
+        Some(interval) => interval
+            .as_u64()
+            .filter(|interval| *interval > 0)
+            .map(|interval| Some(Some(interval)))
+            .ok_or_else(|| String::from("Invalid heartbeat_interval, a positive number of seconds is expected")),
+        None => Ok(None),
+    }
+}
+
+/// The descriptor set is sent base64 encoded.
+fn parse_descriptor_set(value: &Value) -> Result<Option<Vec<u8>>, String> {
+    match value.get("descriptor_set").and_then(Value::as_str) {
+        Some(descriptor_set) => base64::engine::general_purpose::STANDARD
+            .decode(descriptor_set)
+            .map(Some)
+            .map_err(|e| format!("Invalid descriptor_set : {}", e)),
+        None => Ok(None),
+    }
+}
+
+fn parse_binary_layout(value: &Value, key: &str) -> Result<Option<BinaryLayout>, String> {
+    match value.get("binary_layout") {
+        Some(layout) => serde_json::from_value(layout.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid binary layout for {} : {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+fn parse_constraints(value: &Value, key: &str) -> Result<HashMap<String, FieldConstraints>, String> {
+    match value.get("constraints") {
+        Some(constraints) => serde_json::from_value(constraints.clone())
+            .map_err(|e| format!("Invalid constraints for {} : {}", key, e)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn parse_timestamp_field(value: &Value, key: &str) -> Result<Option<TimestampField>, String> {
+    match value.get("timestamp_field") {
+        Some(field) => serde_json::from_value(field.clone())
+            .map(Some)
+            .map_err(|e| format!("Invalid timestamp_field for {} : {}", key, e)),
+        None => Ok(None),
+    }
+}
+
+/// Computed metrics are sent as expressions keyed by the name of the metric.
+fn parse_computed(value: &Value, key: &str) -> Result<HashMap<String, Expression>, String> {
+    match value.get("computed") {
+        Some(computed) => serde_json::from_value(computed.clone())
+            .map_err(|e| format!("Invalid computed for {} : {}", key, e)),
+        None => Ok(HashMap::new()),
+    }
+}
+
+fn parse_unknown_keys(value: &Value, key: &str) -> Result<UnknownKeyPolicy, String> {
+    match value.get("unknown_keys").and_then(Value::as_str) {
+        Some(policy) => UnknownKeyPolicy::try_from(policy)
+            .map_err(|e| format!("Invalid unknown_keys for {} : {}", key, e)),
+        None => Ok(UnknownKeyPolicy::default()),
+    }
+}
+
 pub struct UpdateDeviceRequest {
     pub physical_id: Option<String>,
     pub name: Option<String>,
     pub events: Option<HashMap<String, EventEmittableSerializable>>,
-    pub actions: Option<HashMap<String, ActionEmittableSerializable>>
+    pub actions: Option<HashMap<String, ActionEmittableSerializable>>,
+    pub descriptor_set: Option<Vec<u8>>,
+    /// `Some(None)` detaches the device from its model.
+    pub model_id: Option<Option<Uuid>>,
+    pub labels: Option<HashMap<String, String>>,
+    pub tags: Option<BTreeSet<String>>,
+    pub state_merge_policy: Option<StateMergePolicy>,
+    /// `Some(None)` stops monitoring the device.
+    pub heartbeat_interval: Option<Option<u64>>,
 }
 
 impl TryFrom<Value> for UpdateDeviceRequest {
@@ -125,6 +264,11 @@ impl TryFrom<Value> for UpdateDeviceRequest {
                         .iter()
                         .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
                         .collect(),
+                    binary_layout: parse_binary_layout(value, key)?,
+                    constraints: parse_constraints(value, key)?,
+                    unknown_keys: parse_unknown_keys(value, key)?,
+                    timestamp_field: parse_timestamp_field(value, key)?,
+                    computed: parse_computed(value, key)?,
                 };
                 events_to_update.insert(key.clone(), event);
             }
@@ -148,6 +292,9 @@ impl TryFrom<Value> for UpdateDeviceRequest {
                         .iter()
                         .map(|(k, v)| (k.clone(), v.as_str().unwrap_or("").to_string()))
                         .collect(),
+                    binary_layout: parse_binary_layout(value, key)?,
+                    constraints: parse_constraints(value, key)?,
+                    unknown_keys: parse_unknown_keys(value, key)?,
                 };
                 actions_to_update.insert(key.clone(), event);
             }
@@ -157,34 +304,124 @@ impl TryFrom<Value> for UpdateDeviceRequest {
             physical_id,
             name,
             events,
-            actions
+            actions,
+            descriptor_set: parse_descriptor_set(&value)?,
+            model_id: parse_model_id(&value)?,
+            labels: parse_labels(&value)?,
+            tags: parse_tags(&value)?,
+            state_merge_policy: parse_state_merge_policy(&value)?,
+            heartbeat_interval: parse_heartbeat_interval(&value)?,
         })
     }
 }
 
+/// Builds a filter from `label=<key>:<value>` and `tag=<tag>` query parameters, both repeatable.
+pub fn parse_device_filter(query: Vec<(String, String)>) -> Result<DeviceFilter, String> {
+    let mut labels = HashMap::new();
+    let mut tags = BTreeSet::new();
+    for (key, value) in query {
+        match key.as_str() {
+            "label" => {
+                let (label, label_value) = value
+                    .split_once(':')
+                    .ok_or_else(|| format!("Invalid label filter {}, expected key:value", value))?;
+                labels.insert(label.to_string(), label_value.to_string());
+            }
+            "tag" => {
+                tags.insert(value);
+            }
+            _ => {}
+        }
+    }
+    Ok(DeviceFilter::new(labels, tags))
+}
+
 #[derive(Serialize)]
 pub struct DeviceResponse {
     pub id: Uuid,
     pub physical_id: String,
     pub user_id: Uuid,
     pub name: String,
+    /// Events of the device with those of its model.
     pub events: HashMap<String, EventEmittableSerializable>,
+    pub schema_version: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<Uuid>,
+    pub labels: HashMap<String, String>,
+    pub tags: BTreeSet<String>,
+    pub state_merge_policy: StateMergePolicy,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval: Option<u64>,
+    pub connectivity: Connectivity,
+}
+
+impl From<Device> for DeviceResponse {
+    fn from(device: Device) -> Self {
+        DeviceResponse {
+            id: *device.id(),
+            physical_id: device.physical_id().to_string(),
+            user_id: *device.user_id(),
+            name: device.name().to_string(),
+            events: device.effective_events().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            schema_version: device.schema_version(),
+            model_id: device.model_id().cloned(),
+            labels: device.labels().clone(),
+            tags: device.tags().clone(),
+            state_merge_policy: device.state_merge_policy(),
+            heartbeat_interval: device.heartbeat_interval(),
+            connectivity: device.connectivity(),
+        }
+    }
+}
+
+#[derive(Serialize)]
+pub struct DeviceSchemaResponse {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub events: HashMap<String, EventEmittableSerializable>,
+    pub actions: HashMap<String, ActionEmittableSerializable>,
+}
+
+impl From<DeviceSchema> for DeviceSchemaResponse {
+    fn from(schema: DeviceSchema) -> Self {
+        DeviceSchemaResponse {
+            version: schema.version(),
+            created_at: *schema.created_at(),
+            events: schema.events().clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            actions: schema.actions().clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EventEmittableSerializable {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_field: Option<TimestampField>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub computed: HashMap<String, Expression>,
 }
 impl From<EventEmittable> for EventEmittableSerializable {
     fn from(value: EventEmittable) -> Self {
         EventEmittableSerializable {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value
                 .payload()
                 .iter()
                 .map(|(k, v)| (k.clone(), v.to_string()))
                 .collect(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
+            timestamp_field: value.timestamp_field().cloned(),
+            computed: value.computed().clone(),
         }
     }
 }
@@ -193,17 +430,26 @@ impl From<EventEmittable> for EventEmittableSerializable {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ActionEmittableSerializable {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
 }
 impl From<ActionEmittable> for ActionEmittableSerializable {
     fn from(value: ActionEmittable) -> Self {
         ActionEmittableSerializable {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value
                 .payload()
                 .iter()
                 .map(|(k, v)| (k.clone(), v.to_string()))
                 .collect(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
         }
     }
 }
\ No newline at end of file
@@ -10,7 +10,7 @@ use tracing::{error, instrument, trace, warn};
 use uuid::Uuid;
 
 use crate::{
-    application::ports::{app::AppOutbound, inbound::device_service::DeviceService},
+    application::ports::{app::AppOutbound, inbound::device_service::{DeviceService, DeviceUpdate}},
     infrastructure::http::axum::{
         device_handlers::{
             types::{DeviceResponse, EventEmittableSerializable, UpdateDeviceRequest},
@@ -55,12 +55,23 @@ pub async fn update_device_handler<AO: AppOutbound>(
     };
     let events = payload.events.map(into_event_emittable).transpose()?;
     let actions = payload.actions.map(into_action_emittable).transpose()?;
-    match service.update_device(id, payload.physical_id, payload.name, events, actions).await {
+    let update = DeviceUpdate {
+        physical_id: payload.physical_id,
+        name: payload.name,
+        events,
+        actions,
+        descriptor_set: payload.descriptor_set,
+        model_id: payload.model_id,
+        labels: payload.labels,
+        tags: payload.tags,
+        state_merge_policy: payload.state_merge_policy,
+        heartbeat_interval: payload.heartbeat_interval,
+    };
+    match service.update_device(id, update).await {
         // convert event_data to HashMap<String, String>
         Ok(device) => {
             let events: HashMap<String, EventEmittableSerializable> = device
-                .events()
-                .clone()
+                .effective_events()
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect();
@@ -71,6 +82,13 @@ pub async fn update_device_handler<AO: AppOutbound>(
                 user_id: device.user_id().clone(),
                 name: device.name().to_string(),
                 events,
+                schema_version: device.schema_version(),
+                model_id: device.model_id().cloned(),
+                labels: device.labels().clone(),
+                tags: device.tags().clone(),
+                state_merge_policy: device.state_merge_policy(),
+                heartbeat_interval: device.heartbeat_interval(),
+                connectivity: device.connectivity(),
             }))
         }
         Err(err) => Err(log_and_return_response(err)),
@@ -20,7 +20,7 @@ pub fn into_event_emittable(
 ) -> Result<HashMap<String, EventEmittable>, Response> {
     let mut events = HashMap::new();
     for (k, v) in payload.into_iter() {
-        let format = match EventFormat::try_from(v.format.as_str()) {
+        let format = match EventFormat::from_parts(&v.format, v.binary_layout) {
             Ok(format) => format,
             Err(_) => {
                 warn!(
@@ -58,7 +58,12 @@ pub fn into_event_emittable(
             };
             payload.insert(data_name, data_type);
         }
-        events.insert(k, EventEmittable::new(format, payload));
+        let mut event = EventEmittable::new(format, payload);
+        event.set_constraints(v.constraints);
+        event.set_unknown_keys(v.unknown_keys);
+        event.set_timestamp_field(v.timestamp_field);
+        event.set_computed(v.computed);
+        events.insert(k, event);
     }
     return Ok(events);
 }
@@ -69,7 +74,7 @@ pub fn into_action_emittable(
 ) -> Result<HashMap<String, ActionEmittable>, Response> {
     let mut actions = HashMap::new();
     for (k, v) in payload.into_iter() {
-        let format = match ActionFormat::try_from(v.format.as_str()) {
+        let format = match ActionFormat::from_parts(&v.format, v.binary_layout) {
             Ok(format) => format,
             Err(_) => {
                 warn!(
@@ -107,7 +112,10 @@ pub fn into_action_emittable(
             };
             payload.insert(data_name, data_type);
         }
-        actions.insert(k, ActionEmittable::new(format, payload));
+        let mut action = ActionEmittable::new(format, payload);
+        action.set_constraints(v.constraints);
+        action.set_unknown_keys(v.unknown_keys);
+        actions.insert(k, action);
     }
     return Ok(actions);
 }
@@ -0,0 +1,175 @@
+use std::{collections::HashMap, sync::Arc};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use tracing::{error, instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::device_model_service::{DeviceModelService, DeviceModelServiceError},
+    },
+    domain::device_model::DeviceModel,
+    infrastructure::http::axum::{
+        device_handlers::{
+            types::{ActionEmittableSerializable, EventEmittableSerializable},
+            utils::{into_action_emittable, into_event_emittable},
+        },
+        error::ErrorResponse,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateDeviceModelRequest {
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub events: HashMap<String, EventEmittableSerializable>,
+    #[serde(default)]
+    pub actions: HashMap<String, ActionEmittableSerializable>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateDeviceModelRequest {
+    pub name: Option<String>,
+    pub events: Option<HashMap<String, EventEmittableSerializable>>,
+    pub actions: Option<HashMap<String, ActionEmittableSerializable>>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceModelsQuery {
+    pub user_id: Uuid,
+}
+
+#[derive(Serialize)]
+pub struct DeviceModelResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub events: HashMap<String, EventEmittableSerializable>,
+    pub actions: HashMap<String, ActionEmittableSerializable>,
+}
+
+impl From<DeviceModel> for DeviceModelResponse {
+    fn from(model: DeviceModel) -> Self {
+        DeviceModelResponse {
+            id: *model.id(),
+            user_id: *model.user_id(),
+            name: model.name().to_string(),
+            events: model.events().clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            actions: model.actions().clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+        }
+    }
+}
+
+fn parse_model_id(model_id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(model_id).map_err(|_| {
+        warn!(
+            result = "warn",
+            details = format!("Invalid device model id provided : {}", model_id)
+        );
+        ErrorResponse {
+            status: 400,
+            message: "Invalid device model ID".to_string(),
+        }
+    })
+}
+
+fn log_and_return_response(err: DeviceModelServiceError) -> Response {
+    match &err {
+        DeviceModelServiceError::InternalError(e) => error!(result = "error", details = %e),
+        e => warn!(result = "warn", details = %e),
+    }
+    ErrorResponse::from(err).into_response()
+}
+
+#[instrument]
+pub async fn create_device_model_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Json(payload): Json<CreateDeviceModelRequest>,
+) -> Result<Json<DeviceModelResponse>, Response> {
+    let service = services.get_device_model_service();
+    let events = into_event_emittable(payload.events)?;
+    let actions = into_action_emittable(payload.actions)?;
+    let model = DeviceModel::new(&Uuid::new_v4(), &payload.user_id, &payload.name, events, actions);
+    match service.create_device_model(&model).await {
+        Ok(model) => {
+            trace!(result = "success");
+            Ok(Json(DeviceModelResponse::from(model)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_device_models_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Query(query): Query<DeviceModelsQuery>,
+) -> Result<Json<Vec<DeviceModelResponse>>, Response> {
+    let service = services.get_device_model_service();
+    match service.get_device_models_by_user_id(query.user_id).await {
+        Ok(models) => {
+            trace!(result = "success");
+            Ok(Json(models.into_iter().map(DeviceModelResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_device_model_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(model_id): Path<String>,
+) -> Result<Json<DeviceModelResponse>, Response> {
+    let service = services.get_device_model_service();
+    let id = parse_model_id(&model_id)?;
+    match service.get_device_model(id).await {
+        Ok(Some(model)) => {
+            trace!(result = "success");
+            Ok(Json(DeviceModelResponse::from(model)))
+        }
+        Ok(None) => Err(log_and_return_response(DeviceModelServiceError::NotFound)),
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+/// Changes are seen by every device using the model.
+#[instrument]
+pub async fn update_device_model_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(model_id): Path<String>,
+    Json(payload): Json<UpdateDeviceModelRequest>,
+) -> Result<Json<DeviceModelResponse>, Response> {
+    let service = services.get_device_model_service();
+    let id = parse_model_id(&model_id)?;
+    let events = payload.events.map(into_event_emittable).transpose()?;
+    let actions = payload.actions.map(into_action_emittable).transpose()?;
+    match service.update_device_model(id, payload.name, events, actions).await {
+        Ok(model) => {
+            trace!(result = "success");
+            Ok(Json(DeviceModelResponse::from(model)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn delete_device_model_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(model_id): Path<String>,
+) -> Result<(), Response> {
+    let service = services.get_device_model_service();
+    let id = parse_model_id(&model_id)?;
+    match service.delete_device_model(id).await {
+        Ok(_) => {
+            trace!(result = "success");
+            Ok(())
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
@@ -9,8 +9,8 @@ use uuid::Uuid;
 
 use crate::{
     application::ports::{
-        app::AppOutbound, inbound::device_state_service::{DeviceStateService, DeviceStateServiceError}
-    }, domain::state::DeviceState, infrastructure::{http::axum::error::ErrorResponse, utils::log_device_state_service_error}
+        app::AppOutbound, inbound::{action_service::ActionService, device_service::DeviceService, device_state_service::{DeviceStateService, DeviceStateServiceError}}
+    }, domain::{event::event_data_value::EventDataValue, state::{DeviceState, ValueSource}}, infrastructure::{http::axum::{action_handlers::types::{self as action_types, ActionResponse}, error::ErrorResponse}, utils::log_device_state_service_error}
 };
 
 #[instrument]
@@ -19,13 +19,7 @@ pub async fn get_device_state_handler<AO: AppOutbound>(
     Path(device_id) : Path<String>,
 ) -> Result<Json<DeviceStateResponse>, Response> {
     let service = app_state.get_device_state_service();
-    let id = match Uuid::parse_str(&device_id) {
-        Ok(id) => id,
-        Err(_) => {
-            warn!(result = "warn", details = format!("Invalid device ID"));
-            return Err(ErrorResponse { status: 400, message: "Invalid device ID".to_string() }.into_response());
-        },
-    };
+    let id = parse_device_id(&device_id)?;
 
     match service.get_device_state(id).await {
         Ok(Some(device_state)) => {
@@ -42,6 +36,82 @@ pub async fn get_device_state_handler<AO: AppOutbound>(
     }
 }
 
+#[instrument]
+pub async fn get_device_shadow_handler<AO: AppOutbound>(
+    State(app_state): State<Arc<AO>>,
+    Path(device_id) : Path<String>,
+) -> Result<Json<ShadowResponse>, Response> {
+    let service = app_state.get_device_state_service();
+    let id = parse_device_id(&device_id)?;
+    match service.get_device_state(id).await {
+        Ok(Some(device_state)) => {
+            trace!(result = "success");
+            Ok(Json(ShadowResponse::from(device_state)))
+        },
+        Ok(None) => {
+            warn!(result = "warn", details = format!("Device state with ID {} not found", device_id));
+            Err(ErrorResponse { status: 404, message: "Device state not found".to_string() }.into_response())
+        },
+        Err(err) => {
+            Err(log_and_return_response(err))
+        },
+    }
+}
+
+/// Replaces the desired state of the device and sends the actions for it to converge.
+#[instrument]
+pub async fn set_desired_state_handler<AO: AppOutbound>(
+    State(app_state): State<Arc<AO>>,
+    Path(device_id) : Path<String>,
+    Json(payload): Json<HashMap<String, Value>>,
+) -> Result<Json<SetDesiredStateResponse>, Response> {
+    let id = parse_device_id(&device_id)?;
+    let mut desired = HashMap::new();
+    for (k, v) in payload {
+        match EventDataValue::try_from(v) {
+            Ok(value) => desired.insert(k, value),
+            Err(_) => {
+                warn!(result = "warn", details = format!("Invalid desired value for key {}", k));
+                return Err(ErrorResponse { status: 400, message: format!("Invalid desired value for key {}", k) }.into_response());
+            }
+        };
+    }
+    let device = match app_state.get_device_service().get_device(id).await {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            warn!(result = "warn", details = format!("Device with ID {} not found", device_id));
+            return Err(ErrorResponse { status: 404, message: "Device not found".to_string() }.into_response());
+        },
+        Err(err) => return Err(ErrorResponse::from(err).into_response()),
+    };
+    let (device_state, actions) = match app_state.get_device_state_service().set_desired_state(&device, desired).await {
+        Ok(res) => res,
+        Err(err) => return Err(log_and_return_response(err)),
+    };
+    let action_service = app_state.get_action_service();
+    for action in &actions {
+        let format = match device.action(&action.action_name) {
+            Some(action_concerned) => action_concerned.format(),
+            None => return Err(ErrorResponse::internal_error().into_response()),
+        };
+        if let Err(err) = action_service.send_action(action.clone(), format).await {
+            return Err(action_types::log_and_return_response(err));
+        }
+    }
+    trace!(result = "success");
+    Ok(Json(SetDesiredStateResponse {
+        shadow: ShadowResponse::from(device_state),
+        actions: actions.into_iter().map(ActionResponse::from).collect(),
+    }))
+}
+
+fn parse_device_id(device_id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(device_id).map_err(|_| {
+        warn!(result = "warn", details = format!("Invalid device ID"));
+        ErrorResponse { status: 400, message: "Invalid device ID".to_string() }
+    })
+}
+
 pub(crate) fn log_and_return_response(err: DeviceStateServiceError) -> Response {
     log_device_state_service_error(&err);
     ErrorResponse::from(err).into_response()
@@ -52,15 +122,62 @@ pub struct DeviceStateResponse {
     pub device_id: Uuid,
     pub last_update: DateTime<Utc>,
     pub values: HashMap<String, Value>,
+    /// Timestamp and event of each value.
+    pub sources: HashMap<String, ValueSource>,
+    pub desired: HashMap<String, Value>,
+    pub desired_update: Option<DateTime<Utc>>,
+    pub converged_at: Option<DateTime<Utc>>,
 }
 
 impl From<DeviceState> for DeviceStateResponse {
     fn from(state: DeviceState) -> Self {
-        let values = state.values.into_iter().map(|(k, v)| (k, v.into())).collect();
+        let values = state.raw_values().into_iter().map(|(k, v)| (k, v.into())).collect();
         DeviceStateResponse {
             device_id: state.device_id,
             last_update: state.last_update,
             values,
+            sources: state.sources(),
+            desired: to_json(&state.desired),
+            desired_update: state.desired_update,
+            converged_at: state.converged_at,
+        }
+    }
+}
+
+/// Desired and reported values of a device, with the desired values not reported yet.
+#[derive(Serialize)]
+pub struct ShadowResponse {
+    pub device_id: Uuid,
+    pub desired: HashMap<String, Value>,
+    pub reported: HashMap<String, Value>,
+    pub delta: HashMap<String, Value>,
+    pub desired_update: Option<DateTime<Utc>>,
+    pub converged_at: Option<DateTime<Utc>>,
+    pub converged: bool,
+}
+
+impl From<DeviceState> for ShadowResponse {
+    fn from(state: DeviceState) -> Self {
+        ShadowResponse {
+            device_id: state.device_id,
+            desired: to_json(&state.desired),
+            reported: to_json(&state.raw_values()),
+            delta: to_json(&state.delta()),
+            desired_update: state.desired_update,
+            converged_at: state.converged_at,
+            converged: state.converged_at.is_some(),
         }
     }
+}
+
+#[derive(Serialize)]
+pub struct SetDesiredStateResponse {
+    #[serde(flatten)]
+    pub shadow: ShadowResponse,
+    /// Actions sent for the device to converge.
+    pub actions: Vec<ActionResponse>,
+}
+
+fn to_json(values: &HashMap<String, EventDataValue>) -> HashMap<String, Value> {
+    values.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect()
 }
\ No newline at end of file
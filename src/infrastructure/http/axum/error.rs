@@ -1,7 +1,7 @@
 use axum::response::{IntoResponse, Response};
 use serde::Serialize;
 
-use crate::{application::ports::inbound::{action_service::ActionServiceError, device_service::DeviceServiceError, device_state_service::DeviceStateServiceError, event_service::EventServiceError}, domain::{action::action_format::ActionFormatError, event::event_format::EventFormatError}};
+use crate::{application::ports::inbound::{action_service::ActionServiceError, device_group_service::DeviceGroupServiceError, device_model_service::DeviceModelServiceError, device_service::DeviceServiceError, device_state_service::DeviceStateServiceError, event_service::EventServiceError, rule_service::RuleServiceError}, domain::{action::action_format::ActionFormatError, event::event_format::EventFormatError}};
 
 #[derive(Serialize)]
 pub struct ErrorResponse {
@@ -43,9 +43,9 @@ impl From<DeviceServiceError> for ErrorResponse {
                 status: 409,
                 message: "Device already exists".to_string(),
             },
-            DeviceServiceError::InvalidInput => ErrorResponse {
+            DeviceServiceError::InvalidInput(err) => ErrorResponse {
                 status: 400,
-                message: "Invalid input".to_string(),
+                message: format!("Invalid input: {}", err),
             },
             DeviceServiceError::InternalError(_) => ErrorResponse {
                 status: 500,
@@ -55,6 +55,79 @@ impl From<DeviceServiceError> for ErrorResponse {
     }
 }
 
+impl From<DeviceModelServiceError> for ErrorResponse {
+    fn from(err: DeviceModelServiceError) -> Self {
+        match err {
+            DeviceModelServiceError::NotFound => ErrorResponse {
+                status: 404,
+                message: "Device model not found".to_string(),
+            },
+            DeviceModelServiceError::AlreadyExists => ErrorResponse {
+                status: 409,
+                message: "Device model already exists".to_string(),
+            },
+            DeviceModelServiceError::InUse => ErrorResponse {
+                status: 409,
+                message: "Device model is used by devices".to_string(),
+            },
+            DeviceModelServiceError::InvalidInput(err) => ErrorResponse {
+                status: 400,
+                message: format!("Invalid input: {}", err),
+            },
+            DeviceModelServiceError::InternalError(_) => ErrorResponse {
+                status: 500,
+                message: "Internal server error".to_string(),
+            },
+        }
+    }
+}
+
+impl From<DeviceGroupServiceError> for ErrorResponse {
+    fn from(err: DeviceGroupServiceError) -> Self {
+        match err {
+            DeviceGroupServiceError::NotFound => ErrorResponse {
+                status: 404,
+                message: "Device group not found".to_string(),
+            },
+            DeviceGroupServiceError::AlreadyExists => ErrorResponse {
+                status: 409,
+                message: "Device group already exists".to_string(),
+            },
+            DeviceGroupServiceError::InvalidInput(err) => ErrorResponse {
+                status: 400,
+                message: format!("Invalid input: {}", err),
+            },
+            DeviceGroupServiceError::InternalError(_) => ErrorResponse {
+                status: 500,
+                message: "Internal server error".to_string(),
+            },
+        }
+    }
+}
+
+impl From<RuleServiceError> for ErrorResponse {
+    fn from(err: RuleServiceError) -> Self {
+        match err {
+            RuleServiceError::NotFound => ErrorResponse {
+                status: 404,
+                message: "Rule not found".to_string(),
+            },
+            RuleServiceError::AlreadyExists => ErrorResponse {
+                status: 409,
+                message: "Rule already exists".to_string(),
+            },
+            RuleServiceError::InvalidInput(err) => ErrorResponse {
+                status: 400,
+                message: format!("Invalid input: {}", err),
+            },
+            RuleServiceError::InternalError(_) => ErrorResponse {
+                status: 500,
+                message: "Internal server error".to_string(),
+            },
+        }
+    }
+}
+
 impl From<DeviceStateServiceError> for ErrorResponse {
     fn from(err: DeviceStateServiceError) -> Self {
         match err {
@@ -74,9 +147,9 @@ impl From<DeviceStateServiceError> for ErrorResponse {
                 status: 500,
                 message: "Internal server error".to_string(),
             },
-            DeviceStateServiceError::InvalidInput => ErrorResponse {
+            DeviceStateServiceError::InvalidInput(err) => ErrorResponse {
                 status: 400,
-                message: "Invalid input".to_string(),
+                message: format!("Invalid input: {}", err),
             },
         }
     }
@@ -100,6 +173,10 @@ impl From<EventServiceError> for ErrorResponse {
 impl From<ActionServiceError> for ErrorResponse {
     fn from(err: ActionServiceError) -> Self {
         match err {
+            ActionServiceError::NotFound => ErrorResponse {
+                status: 404,
+                message: "Action not found".to_string(),
+            },
             ActionServiceError::InternalError(val) => ErrorResponse {
                 status: 404,
                 message: format!("Action not found: {}", val.to_string()),
@@ -119,6 +196,10 @@ impl From<EventFormatError> for ErrorResponse {
                 status: 400,
                 message: format!("Invalid event format: {}", e),
             },
+            EventFormatError::ConstraintViolation(e) => ErrorResponse {
+                status: 400,
+                message: format!("Constraint violation: {}", e),
+            },
         }
     }
 }
@@ -130,6 +211,10 @@ impl From<ActionFormatError> for ErrorResponse {
                 status: 400,
                 message: format!("Invalid event format: {}", e),
             },
+            ActionFormatError::ConstraintViolation(e) => ErrorResponse {
+                status: 400,
+                message: format!("Constraint violation: {}", e),
+            },
         }
     }
 }
\ No newline at end of file
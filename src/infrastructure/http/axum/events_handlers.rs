@@ -1,13 +1,13 @@
 use std::{collections::HashMap, sync::Arc, usize};
 
-use axum::{ body, extract::{Path, Request, State}, response::{IntoResponse, Response}, Json};
+use axum::{ body, extract::{Path, Query, Request, State}, http::header, response::{IntoResponse, Response}, Json};
 use chrono::{DateTime, Utc};
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{error, instrument, trace, warn};
 use uuid::Uuid;
 
-use crate::{application::ports::{app::AppOutbound, inbound::{device_service::DeviceService, device_state_service::DeviceStateService, event_service::{EventService, EventServiceError}}}, domain::event::event::Event, infrastructure::http::axum::{device_state_handlers, error::ErrorResponse}};
+use crate::{application::ports::{app::AppOutbound, inbound::{device_service::DeviceService, device_state_service::DeviceStateService, event_service::{EventService, EventServiceError}}}, domain::{device::Device, event::{event::Event, senml::{self, SenmlRecord}}}, infrastructure::{connectivity, http::axum::{device_state_handlers, error::ErrorResponse}, rules}};
 
 #[instrument]
 pub async fn create_event_handler<AO: AppOutbound>(
@@ -47,7 +47,7 @@ pub async fn create_event_handler<AO: AppOutbound>(
             return Err(ErrorResponse::from(err).into_response())
         },
     };
-    let event_concerned = device.events().get(&event_name).expect("Check done before");
+    let event_concerned = device.event(&event_name).expect("Check done before");
     let res = match event_service.handle_event(event.clone(), &event_concerned.format()).await {
         Ok(_) => {
             Json(EventResponse::from(event.clone()))
@@ -57,23 +57,33 @@ pub async fn create_event_handler<AO: AppOutbound>(
         },
     };
     // Update the device state with the event payload
-    match device_state_service.update_device_state(device.id().clone(), event.payload).await {
-        Ok(_) => {
-            trace!(result = "success");
-            Ok(res)
-        },
-        Err(err) => {
-            return Err(device_state_handlers::log_and_return_response(err))
-        },
+    let device_state = match device_state_service.update_device_state(*device.id(), event.state_values(), device.state_merge_policy()).await {
+        Ok(device_state) => device_state,
+        Err(err) => return Err(device_state_handlers::log_and_return_response(err))
+    };
+    if let Err(e) = connectivity::record_activity(services.as_ref(), &device).await {
+        warn!(result = "warn", details = format!("Failed to record activity: {}", e));
+    }
+    if let Err(e) = rules::run_rules(services.as_ref(), &device, &event, Some(&device_state)).await {
+        warn!(result = "warn", details = format!("Failed to run rules: {}", e));
     }
+    trace!(result = "success");
+    Ok(res)
+
+}
 
+#[derive(Debug, Deserialize)]
+pub struct GetEventsQuery {
+    /// `senml` to export events as a SenML pack, events are listed as JSON otherwise.
+    pub format: Option<String>,
 }
 
 #[instrument]
 pub async fn get_event_handler<AO: AppOutbound>(
     State(services): State<Arc<AO>>,
     Path(device_id): Path<String>,
-) -> Result<Json<Vec<EventResponse>>, Response> {
+    Query(query): Query<GetEventsQuery>,
+) -> Result<Response, Response> {
     let event_service = services.get_event_service();
     let device_service = services.get_device_service();
     let device_id = match Uuid::parse_str(&device_id) {
@@ -91,11 +101,24 @@ pub async fn get_event_handler<AO: AppOutbound>(
         },
         Err(err) => return Err(ErrorResponse::from(err).into_response()),
     };
+    let export_senml = match query.format.as_deref() {
+        None | Some("json") => false,
+        Some("senml") => true,
+        Some(format) => {
+            warn!(result = "warn", details = format!("Unsupported export format {}", format));
+            return Err(ErrorResponse { status: 400, message: format!("Unsupported export format {}", format) }.into_response())
+        },
+    };
     match event_service.get_events(&device.physical_id()).await {
+        Ok(events) if export_senml => {
+            let pack = events_to_pack(&device, events);
+            trace!(result = "success");
+            Ok(([(header::CONTENT_TYPE, "application/senml+json")], Json(pack)).into_response())
+        },
         Ok(events) => {
             let response: Vec<EventResponse> = events.into_iter().map(EventResponse::from).collect();
             trace!(result = "success");
-            Ok(Json(response))
+            Ok(Json(response).into_response())
         },
         Err(err) => {
             Err(log_and_return_response(err))
@@ -103,6 +126,30 @@ pub async fn get_event_handler<AO: AppOutbound>(
     }
 }
 
+/// Exports events of a device as a single pack, each event starts with its own base name and time.
+fn events_to_pack(device: &Device, events: Vec<Event>) -> Vec<SenmlRecord> {
+    let mut pack = Vec::new();
+    for event in events {
+        let units: HashMap<String, String> = device
+            .event(&event.event_name)
+            .map(|e| {
+                e.constraints()
+                    .iter()
+                    .filter_map(|(k, c)| c.unit.clone().map(|u| (k.clone(), u)))
+                    .collect()
+            })
+            .unwrap_or_default();
+        let payload = event.payload.into_iter().map(|(k, v)| (k, v.into())).collect();
+        let mut records = senml::to_records(payload, &units);
+        if let Some(first) = records.first_mut() {
+            first.bn = Some(format!("{}:{}:", device.physical_id(), event.event_name));
+            first.bt = Some(event.timestamp.timestamp_millis() as f64 / 1000.);
+        }
+        pack.extend(records);
+    }
+    pack
+}
+
 pub fn log_and_return_response(err: EventServiceError) -> Response {
     match &err {
         EventServiceError::InvalidInput(err) => warn!(result = "warn", details = format!("invalid input: {}", err)),
@@ -117,6 +164,8 @@ pub struct EventResponse {
     pub device_physical_id: String,
     pub timestamp: DateTime<Utc>,
     pub payload: HashMap<String, Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schema_version: Option<u32>,
 }
 
 impl From<Event> for EventResponse {
@@ -127,6 +176,7 @@ impl From<Event> for EventResponse {
             device_physical_id: event.device_physical_id,
             timestamp: event.timestamp,
             payload,
+            schema_version: event.schema_version,
         }
     }
 }
\ No newline at end of file
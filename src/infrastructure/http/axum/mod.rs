@@ -1,5 +1,8 @@
 pub mod action_handlers;
+pub mod device_group_handlers;
 pub mod device_handlers;
+pub mod device_model_handlers;
 pub mod device_state_handlers;
 pub mod error;
-pub mod events_handlers;
\ No newline at end of file
+pub mod events_handlers;
+pub mod rule_handlers;
\ No newline at end of file
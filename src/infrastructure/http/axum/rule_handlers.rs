@@ -0,0 +1,253 @@
+use std::sync::Arc;
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+    response::{IntoResponse, Response},
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Deserializer, Serialize};
+use tracing::{error, instrument, trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{
+            device_service::DeviceService,
+            rule_service::{RuleService, RuleServiceError, RuleUpdate},
+        },
+    },
+    domain::rule::{Rule, RuleAction, RuleCondition},
+    infrastructure::http::axum::{
+        device_handlers::utils::log_and_return_response as log_and_return_device_response,
+        error::ErrorResponse,
+    },
+};
+
+#[derive(Debug, Deserialize)]
+pub struct CreateRuleRequest {
+    pub user_id: Uuid,
+    pub name: String,
+    pub device_id: Uuid,
+    #[serde(default)]
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub debounce: u64,
+    #[serde(default)]
+    pub cooldown: u64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateRuleRequest {
+    pub name: Option<String>,
+    /// A null event name evaluates every event of the device.
+    #[serde(default, deserialize_with = "deserialize_event_name")]
+    pub event_name: Option<Option<String>>,
+    pub conditions: Option<Vec<RuleCondition>>,
+    pub action: Option<RuleAction>,
+    pub debounce: Option<u64>,
+    pub cooldown: Option<u64>,
+    pub enabled: Option<bool>,
+}
+
+fn deserialize_event_name<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<Option<String>>, D::Error> {
+    Option::<String>::deserialize(deserializer).map(Some)
+}
+
+/// Rules of a user or evaluated on the events of a device, one of them is required.
+#[derive(Debug, Deserialize)]
+pub struct RulesQuery {
+    pub user_id: Option<Uuid>,
+    pub device_id: Option<Uuid>,
+}
+
+#[derive(Serialize)]
+pub struct RuleResponse {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub device_id: Uuid,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    pub debounce: u64,
+    pub cooldown: u64,
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub matching_since: Option<DateTime<Utc>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_triggered_at: Option<DateTime<Utc>>,
+}
+
+impl From<Rule> for RuleResponse {
+    fn from(rule: Rule) -> Self {
+        RuleResponse {
+            id: rule.id,
+            user_id: rule.user_id,
+            name: rule.name,
+            device_id: rule.device_id,
+            event_name: rule.event_name,
+            conditions: rule.conditions,
+            action: rule.action,
+            debounce: rule.debounce,
+            cooldown: rule.cooldown,
+            enabled: rule.enabled,
+            matching_since: rule.matching_since,
+            last_triggered_at: rule.last_triggered_at,
+        }
+    }
+}
+
+fn parse_id(id: &str) -> Result<Uuid, ErrorResponse> {
+    Uuid::parse_str(id).map_err(|_| {
+        warn!(
+            result = "warn",
+            details = format!("Invalid rule id provided : {}", id)
+        );
+        ErrorResponse {
+            status: 400,
+            message: "Invalid rule ID".to_string(),
+        }
+    })
+}
+
+fn log_and_return_response(err: RuleServiceError) -> Response {
+    match &err {
+        RuleServiceError::InternalError(e) => error!(result = "error", details = %e),
+        e => warn!(result = "warn", details = %e),
+    }
+    ErrorResponse::from(err).into_response()
+}
+
+/// Rules only refer to existing devices, the one watched and the one receiving the action.
+async fn check_device_exists<AO: AppOutbound>(services: &AO, device_id: Uuid) -> Result<(), Response> {
+    match services.get_device_service().get_device(device_id).await {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err(ErrorResponse {
+            status: 404,
+            message: format!("Device {} not found", device_id),
+        }
+        .into_response()),
+        Err(err) => Err(log_and_return_device_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn create_rule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Json(payload): Json<CreateRuleRequest>,
+) -> Result<Json<RuleResponse>, Response> {
+    check_device_exists(services.as_ref(), payload.device_id).await?;
+    check_device_exists(services.as_ref(), payload.action.device_id).await?;
+    let mut rule = Rule::new(
+        &payload.user_id,
+        &payload.name,
+        &payload.device_id,
+        payload.event_name,
+        payload.conditions,
+        payload.action,
+    );
+    rule.debounce = payload.debounce;
+    rule.cooldown = payload.cooldown;
+    match services.get_rule_service().create_rule(&rule).await {
+        Ok(rule) => {
+            trace!(result = "success");
+            Ok(Json(RuleResponse::from(rule)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_rules_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Query(query): Query<RulesQuery>,
+) -> Result<Json<Vec<RuleResponse>>, Response> {
+    let service = services.get_rule_service();
+    let rules = match (query.user_id, query.device_id) {
+        (Some(user_id), device_id) => service.get_rules_by_user_id(user_id).await.map(|rules| {
+            rules
+                .into_iter()
+                .filter(|rule| device_id.is_none_or(|device_id| rule.device_id == device_id))
+                .collect()
+        }),
+        (None, Some(device_id)) => service.get_rules_by_device_id(device_id).await,
+        (None, None) => Err(RuleServiceError::InvalidInput("a user or device ID is required".to_string())),
+    };
+    match rules {
+        Ok(rules) => {
+            trace!(result = "success");
+            Ok(Json(rules.into_iter().map(RuleResponse::from).collect()))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn get_rule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(rule_id): Path<String>,
+) -> Result<Json<RuleResponse>, Response> {
+    let id = parse_id(&rule_id)?;
+    match services.get_rule_service().get_rule(id).await {
+        Ok(Some(rule)) => {
+            trace!(result = "success");
+            Ok(Json(RuleResponse::from(rule)))
+        }
+        Ok(None) => Err(log_and_return_response(RuleServiceError::NotFound)),
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn update_rule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(rule_id): Path<String>,
+    Json(payload): Json<UpdateRuleRequest>,
+) -> Result<Json<RuleResponse>, Response> {
+    let id = parse_id(&rule_id)?;
+    if let Some(action) = &payload.action {
+        check_device_exists(services.as_ref(), action.device_id).await?;
+    }
+    match services
+        .get_rule_service()
+        .update_rule(
+            id,
+            RuleUpdate {
+                name: payload.name,
+                event_name: payload.event_name,
+                conditions: payload.conditions,
+                action: payload.action,
+                debounce: payload.debounce,
+                cooldown: payload.cooldown,
+                enabled: payload.enabled,
+            },
+        )
+        .await
+    {
+        Ok(rule) => {
+            trace!(result = "success");
+            Ok(Json(RuleResponse::from(rule)))
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
+
+#[instrument]
+pub async fn delete_rule_handler<AO: AppOutbound>(
+    State(services): State<Arc<AO>>,
+    Path(rule_id): Path<String>,
+) -> Result<(), Response> {
+    let id = parse_id(&rule_id)?;
+    match services.get_rule_service().delete_rule(id).await {
+        Ok(_) => {
+            trace!(result = "success");
+            Ok(())
+        }
+        Err(err) => Err(log_and_return_response(err)),
+    }
+}
@@ -0,0 +1,83 @@
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_group_repository::{
+        DeviceGroupRepositoryError, GetDeviceGroupRepository,
+    },
+    domain::device_group::DeviceGroup,
+    infrastructure::http::reqwest::types::DeviceGroupToReceive,
+};
+
+#[derive(Debug)]
+pub struct ReqwestDeviceGroupRepository {
+    base_url: String,
+    get_path: String,
+}
+
+impl ReqwestDeviceGroupRepository {
+    pub fn new(base_url: &str, get_path: &str) -> Self {
+        ReqwestDeviceGroupRepository {
+            base_url: base_url.to_string(),
+            get_path: get_path.to_string(),
+        }
+    }
+
+    async fn get_groups(&self, url: &str, query: &[(&str, String)]) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let client = reqwest::Client::new();
+        let res = client
+            .get(url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let groups: Vec<DeviceGroupToReceive> = res
+                .json()
+                .await
+                .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+            Ok(groups.into_iter().map(DeviceGroup::from).collect())
+        } else {
+            Err(DeviceGroupRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+}
+
+impl GetDeviceGroupRepository for ReqwestDeviceGroupRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceGroup>, DeviceGroupRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}/{}", self.base_url, self.get_path, id);
+        let res = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if res.status().is_success() {
+            let group: DeviceGroupToReceive = res
+                .json()
+                .await
+                .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+            Ok(Some(DeviceGroup::from(group)))
+        } else {
+            Err(DeviceGroupRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let url = format!("{}{}", self.base_url, self.get_path);
+        self.get_groups(&url, &[("user_id", user_id.to_string())]).await
+    }
+
+    async fn get_children(&self, parent_id: Uuid) -> Result<Vec<DeviceGroup>, DeviceGroupRepositoryError> {
+        let url = format!("{}{}/{}/children", self.base_url, self.get_path, parent_id);
+        self.get_groups(&url, &[]).await
+    }
+}
@@ -0,0 +1,77 @@
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_model_repository::{
+        DeviceModelRepositoryError, GetDeviceModelRepository,
+    },
+    domain::device_model::DeviceModel,
+    infrastructure::http::reqwest::types::DeviceModelToReceive,
+};
+
+#[derive(Debug)]
+pub struct ReqwestDeviceModelRepository {
+    base_url: String,
+    get_path: String,
+}
+
+impl ReqwestDeviceModelRepository {
+    pub fn new(base_url: &str, get_path: &str) -> Self {
+        ReqwestDeviceModelRepository {
+            base_url: base_url.to_string(),
+            get_path: get_path.to_string(),
+        }
+    }
+}
+
+impl GetDeviceModelRepository for ReqwestDeviceModelRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<DeviceModel>, DeviceModelRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}/{}", self.base_url, self.get_path, id);
+        let res = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if res.status().is_success() {
+            let model: DeviceModelToReceive = res
+                .json()
+                .await
+                .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+            Ok(Some(DeviceModel::try_from(model)?))
+        } else {
+            Err(DeviceModelRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+
+    async fn get_by_user_id(
+        &self,
+        user_id: Uuid,
+    ) -> Result<Vec<DeviceModel>, DeviceModelRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}", self.base_url, self.get_path);
+        let res = client
+            .get(&url)
+            .query(&[("user_id", user_id.to_string())])
+            .send()
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let models: Vec<DeviceModelToReceive> = res
+                .json()
+                .await
+                .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+            models.into_iter().map(DeviceModel::try_from).collect()
+        } else {
+            Err(DeviceModelRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+}
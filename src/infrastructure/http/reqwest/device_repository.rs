@@ -5,8 +5,8 @@ use crate::{
         CreateDeviceRepository, DeleteDeviceRepository, DeviceRepositoryError, GetDeviceRepository,
         UpdateDeviceRepository,
     },
-    domain::device::Device,
-    infrastructure::http::reqwest::types::DeviceToSend,
+    domain::{connectivity::Connectivity, device::Device, device_filter::DeviceFilter, device_schema::DeviceSchema},
+    infrastructure::http::reqwest::types::{DeviceSchemaToReceive, DeviceToSend},
 };
 
 #[derive(Debug)]
@@ -141,6 +141,123 @@ impl GetDeviceRepository for ReqwestDeviceRepository {
             ))
         }
     }
+
+    async fn get_by_filter(
+        &self,
+        _user_id: Uuid,
+        filter: &DeviceFilter,
+    ) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}", self.base_url, self.get_path);
+        let mut query: Vec<(&str, String)> = filter
+            .labels()
+            .iter()
+            .map(|(k, v)| ("label", format!("{}:{}", k, v)))
+            .collect();
+        query.extend(filter.tags().iter().map(|t| ("tag", t.clone())));
+        let res = client
+            .get(&url)
+            .query(&query)
+            .send()
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let devices_to_send: Vec<DeviceToSend> = res
+                .json()
+                .await
+                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+            let mut devices = Vec::new();
+            for device_to_send in devices_to_send {
+                devices.push(Device::try_from(device_to_send)?);
+            }
+            Ok(devices)
+        } else {
+            Err(DeviceRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+
+    async fn get_monitored(&self) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}", self.base_url, self.get_path);
+        let res = client
+            .get(&url)
+            .query(&[("monitored", "true")])
+            .send()
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let devices_to_send: Vec<DeviceToSend> = res
+                .json()
+                .await
+                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+            let mut devices = Vec::new();
+            for device_to_send in devices_to_send {
+                devices.push(Device::try_from(device_to_send)?);
+            }
+            Ok(devices)
+        } else {
+            Err(DeviceRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+
+    async fn get_by_model_id(&self, model_id: Uuid) -> Result<Vec<Device>, DeviceRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}", self.base_url, self.get_path);
+        let res = client
+            .get(&url)
+            .query(&[("model_id", model_id.to_string())])
+            .send()
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let devices_to_send: Vec<DeviceToSend> = res
+                .json()
+                .await
+                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+            let mut devices = Vec::new();
+            for device_to_send in devices_to_send {
+                devices.push(Device::try_from(device_to_send)?);
+            }
+            Ok(devices)
+        } else {
+            Err(DeviceRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
+
+    async fn get_schemas(&self, device_id: Uuid) -> Result<Vec<DeviceSchema>, DeviceRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}/{}/schemas", self.base_url, self.get_path, device_id);
+        let res = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let schemas_received: Vec<DeviceSchemaToReceive> = res
+                .json()
+                .await
+                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+            let mut schemas = Vec::new();
+            for schema_received in schemas_received {
+                schemas.push(DeviceSchema::try_from(schema_received)?);
+            }
+            Ok(schemas)
+        } else {
+            Err(DeviceRepositoryError::InternalError(
+                res.status().to_string(),
+            ))
+        }
+    }
 }
 
 impl UpdateDeviceRepository for ReqwestDeviceRepository {
@@ -169,6 +286,13 @@ impl UpdateDeviceRepository for ReqwestDeviceRepository {
             ))
         }
     }
+
+    async fn update_connectivity(&self, id: Uuid, connectivity: Connectivity) -> Result<(), DeviceRepositoryError> {
+        // the HTTP API only replaces whole devices
+        let mut device = self.get_by_id(id).await?.ok_or(DeviceRepositoryError::NotFound)?;
+        device.set_connectivity(connectivity);
+        self.update(&device).await
+    }
 }
 
 impl DeleteDeviceRepository for ReqwestDeviceRepository {
@@ -1,9 +1,11 @@
+use std::collections::HashMap;
+
 use crate::{
     application::ports::outbound::device_state_repository::{
         CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError,
         GetDeviceStateRepository, UpdateDeviceStateRepository,
     },
-    domain::state::DeviceState,
+    domain::state::{DeviceState, StateMergePolicy},
     infrastructure::{
         http::reqwest::types::DeviceStateToSend,
     },
@@ -59,8 +61,12 @@ impl CreateDeviceStateRepository for ReqwestDeviceStateRepository {
 }
 
 impl UpdateDeviceStateRepository for ReqwestDeviceStateRepository {
-    async fn update(&self, device: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
-        let device_state_to_send = DeviceStateToSend::from(device.clone());
+    async fn set_desired(&self, device_state: &DeviceState) -> Result<DeviceState, DeviceStateRepositoryError> {
+        // reported values are not sent, the server keeps its own
+        let mut desired = DeviceState::from_parts(device_state.device_id, device_state.last_update, HashMap::new(), HashMap::new());
+        desired.desired = device_state.desired.clone();
+        desired.desired_update = device_state.desired_update;
+        let device_state_to_send = DeviceStateToSend::from(desired);
         let url = format!("{}{}", self.base_url, self.update_path);
         let client = reqwest::Client::new();
         let response = client
@@ -71,7 +77,30 @@ impl UpdateDeviceStateRepository for ReqwestDeviceStateRepository {
             .map_err(|e| DeviceStateRepositoryError::InternalError(e.to_string()))?;
 
         if response.status().is_success() {
-            Ok(())
+            Ok(device_state.clone())
+        } else {
+            Err(DeviceStateRepositoryError::InternalError(
+                response.status().to_string(),
+            ))
+        }
+    }
+
+    async fn merge_values(&self, device_state: &DeviceState, keys: &[String], _policy: StateMergePolicy) -> Result<DeviceState, DeviceStateRepositoryError> {
+        // only the merged keys are sent, the server merges them into its own state
+        let mut merged = DeviceState::from_parts(device_state.device_id, device_state.last_update, HashMap::new(), HashMap::new());
+        merged.values = device_state.values_of(keys);
+        let device_state_to_send = DeviceStateToSend::from(merged);
+        let url = format!("{}{}", self.base_url, self.update_path);
+        let client = reqwest::Client::new();
+        let response = client
+            .put(&url)
+            .json(&device_state_to_send)
+            .send()
+            .await
+            .map_err(|e| DeviceStateRepositoryError::InternalError(e.to_string()))?;
+
+        if response.status().is_success() {
+            Ok(device_state.clone())
         } else {
             Err(DeviceStateRepositoryError::InternalError(
                 response.status().to_string(),
@@ -1,7 +1,9 @@
+use chrono::{DateTime, Utc};
+
 use crate::{
     application::ports::outbound::event_repository::{
         CreateEventRepository, EventRepositoryError, GetEventRepository,
-    }, domain::event::{event::Event, event_format::{EventFormat, EventFormatError}}, infrastructure::http::reqwest::types::{EventToReceive, EventToSend}
+    }, domain::event::{event::Event, event_format::{EventFormat, EventFormatError}}, infrastructure::{http::reqwest::types::{EventToReceive, EventToSend}, utils}
 };
 
 #[derive(Debug)]
@@ -34,7 +36,12 @@ impl CreateEventRepository for ReqwestEventRepository {
                 EventFormatError::UnsupportedFormat(e) => {
                     EventRepositoryError::ValidationError(format!("invalid format, {}", e))
                 }
+                EventFormatError::ConstraintViolation(e) => {
+                    EventRepositoryError::ValidationError(format!("constraint violation, {}", e))
+                }
             })?;
+        let data = utils::payload_to_text(data, event_format.is_binary())
+            .map_err(|e| EventRepositoryError::ValidationError(format!("invalid format, {}", e)))?;
 
         let event_to_send = EventToSend {
             id: event_id,
@@ -94,4 +101,13 @@ impl GetEventRepository for ReqwestEventRepository {
             ))
         }
     }
+
+    async fn get_last_event_time(
+        &self,
+        device_physical_id: &str,
+    ) -> Result<Option<DateTime<Utc>>, EventRepositoryError> {
+        // the HTTP API only serves the whole history of a device
+        let events = self.get_events(device_physical_id).await?;
+        Ok(events.into_iter().map(|event| event.timestamp).max())
+    }
 }
@@ -0,0 +1,76 @@
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::rule_repository::{GetRuleRepository, RuleRepositoryError},
+    domain::rule::Rule,
+    infrastructure::http::reqwest::types::RuleToReceive,
+};
+
+#[derive(Debug)]
+pub struct ReqwestRuleRepository {
+    base_url: String,
+    get_path: String,
+}
+
+impl ReqwestRuleRepository {
+    pub fn new(base_url: &str, get_path: &str) -> Self {
+        ReqwestRuleRepository {
+            base_url: base_url.to_string(),
+            get_path: get_path.to_string(),
+        }
+    }
+
+    async fn get_rules(&self, query: &[(&str, String)]) -> Result<Vec<Rule>, RuleRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}", self.base_url, self.get_path);
+        let res = client
+            .get(&url)
+            .query(query)
+            .send()
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status().is_success() {
+            let rules: Vec<RuleToReceive> = res
+                .json()
+                .await
+                .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+            Ok(rules.into_iter().map(Rule::from).collect())
+        } else {
+            Err(RuleRepositoryError::InternalError(res.status().to_string()))
+        }
+    }
+}
+
+impl GetRuleRepository for ReqwestRuleRepository {
+    async fn get_by_id(&self, id: Uuid) -> Result<Option<Rule>, RuleRepositoryError> {
+        let client = reqwest::Client::new();
+        let url = format!("{}{}/{}", self.base_url, self.get_path, id);
+        let res = client
+            .get(&url)
+            .send()
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+
+        if res.status() == reqwest::StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        if res.status().is_success() {
+            let rule: RuleToReceive = res
+                .json()
+                .await
+                .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+            Ok(Some(Rule::from(rule)))
+        } else {
+            Err(RuleRepositoryError::InternalError(res.status().to_string()))
+        }
+    }
+
+    async fn get_by_user_id(&self, user_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        self.get_rules(&[("user_id", user_id.to_string())]).await
+    }
+
+    async fn get_by_device_id(&self, device_id: Uuid) -> Result<Vec<Rule>, RuleRepositoryError> {
+        self.get_rules(&[("device_id", device_id.to_string())]).await
+    }
+}
@@ -1,4 +1,4 @@
-use std::{collections::HashMap, str::FromStr};
+use std::{collections::{BTreeSet, HashMap}, str::FromStr};
 
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
@@ -6,22 +6,31 @@ use uuid::Uuid;
 
 use crate::{
     application::ports::outbound::{
+        device_model_repository::DeviceModelRepositoryError,
         device_repository::DeviceRepositoryError,
         device_state_repository::DeviceStateRepositoryError,
         event_repository::EventRepositoryError,
     },
     domain::{
+        binary_layout::BinaryLayout,
         action::{
             action_data_type::ActionDataType,
             action_emittable::ActionEmittable, action_format::ActionFormat,
         },
         device::Device,
+        device_group::DeviceGroup,
+        device_model::DeviceModel,
+        device_schema::DeviceSchema,
         event::{
             event::Event, event_data_type::EventDataType, event_data_value::EventDataValue,
-            event_emittable::EventEmittable, event_format::EventFormat,
+            event_emittable::EventEmittable, event_format::EventFormat, event_timestamp::TimestampField, computed_field::Expression,
         },
-        state::DeviceState,
+        field_constraints::{FieldConstraints, UnknownKeyPolicy},
+        rule::{Rule, RuleAction, RuleCondition},
+        state::{DeviceState, StateMergePolicy, ValueSource},
+        connectivity::Connectivity,
     },
+    infrastructure::utils,
 };
 
 #[derive(Serialize, Deserialize)]
@@ -32,6 +41,27 @@ pub struct DeviceToSend {
     pub name: String,
     pub events: HashMap<String, EventEmittableToSend>,
     pub actions: HashMap<String, ActionEmittableToSend>,
+    /// Base64 encoded protobuf descriptor set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor_set: Option<String>,
+    #[serde(default = "first_schema_version")]
+    pub schema_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub state_merge_policy: StateMergePolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval: Option<u64>,
+    #[serde(default)]
+    pub connectivity: Connectivity,
+}
+
+fn first_schema_version() -> u32 {
+    1
 }
 
 impl From<Device> for DeviceToSend {
@@ -56,6 +86,16 @@ impl From<Device> for DeviceToSend {
             name,
             events,
             actions,
+            descriptor_set: device
+                .descriptor_set()
+                .and_then(|d| utils::payload_to_text(d.clone(), true).ok()),
+            schema_version: device.schema_version(),
+            model_id: device.model_id().map(|id| id.to_string()),
+            labels: device.labels().clone(),
+            tags: device.tags().clone(),
+            state_merge_policy: device.state_merge_policy(),
+            heartbeat_interval: device.heartbeat_interval(),
+            connectivity: device.connectivity(),
         }
     }
 }
@@ -69,46 +109,180 @@ impl TryFrom<DeviceToSend> for Device {
         let user_id = Uuid::from_str(&device_to_send.user_id)
             .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
         let name = device_to_send.name.to_string();
-        let mut events = HashMap::new();
-        for (key, val) in device_to_send.events.iter() {
-            let event_format = EventFormat::try_from(val.format.as_str())
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            let mut event_payload = HashMap::new();
-            for (event_key, event_value) in val.payload.clone() {
-                let value = EventDataType::from_str(&event_value)
-                    .map_err(|e| DeviceRepositoryError::InternalError(e))?;
-                event_payload.insert(event_key, value);
-            }
-
-            events.insert(
-                key.clone(),
-                EventEmittable::new(event_format, event_payload),
-            );
-        }
-        let mut actions = HashMap::new();
-        for (key, val) in device_to_send.actions.iter() {
-            let action_format = ActionFormat::try_from(val.format.as_str())
-                .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
-            let mut action_payload = HashMap::new();
-            for (action_key, action_value) in val.payload.clone() {
-                let value = ActionDataType::from_str(&action_value)
-                    .map_err(|e| DeviceRepositoryError::InternalError(e))?;
-                action_payload.insert(action_key, value);
-            }
-
-            actions.insert(
-                key.clone(),
-                ActionEmittable::new(action_format, action_payload),
-            );
-        }
-        Ok(Device::new(
+        let (events, actions) = emittables_from_send(&device_to_send.events, &device_to_send.actions)?;
+        let descriptor_set = device_to_send
+            .descriptor_set
+            .as_deref()
+            .map(|d| utils::payload_from_text(d, true))
+            .transpose()
+            .map_err(DeviceRepositoryError::InternalError)?;
+        let mut device = Device::new(
             &device_id,
             &device_to_send.physical_id,
             &user_id,
             &name,
             events,
             actions,
-        ))
+        );
+        device.set_descriptor_set(descriptor_set);
+        device.set_schema_version(device_to_send.schema_version);
+        let model_id = device_to_send
+            .model_id
+            .as_deref()
+            .map(Uuid::from_str)
+            .transpose()
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+        device.set_model_id(model_id);
+        device.set_labels(device_to_send.labels);
+        device.set_tags(device_to_send.tags);
+        device.set_state_merge_policy(device_to_send.state_merge_policy);
+        device.set_heartbeat_interval(device_to_send.heartbeat_interval);
+        device.set_connectivity(device_to_send.connectivity);
+        Ok(device)
+    }
+}
+
+/// Events and actions of a device, keyed by their name.
+type Emittables = (HashMap<String, EventEmittable>, HashMap<String, ActionEmittable>);
+
+fn emittables_from_send(
+    events_to_send: &HashMap<String, EventEmittableToSend>,
+    actions_to_send: &HashMap<String, ActionEmittableToSend>,
+) -> Result<Emittables, DeviceRepositoryError> {
+    let mut events = HashMap::new();
+    for (key, val) in events_to_send.iter() {
+        let event_format = EventFormat::from_parts(&val.format, val.binary_layout.clone())
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+        let mut event_payload = HashMap::new();
+        for (event_key, event_value) in val.payload.clone() {
+            let value = EventDataType::from_str(&event_value)
+                .map_err(DeviceRepositoryError::InternalError)?;
+            event_payload.insert(event_key, value);
+        }
+
+        let mut event = EventEmittable::new(event_format, event_payload);
+        event.set_constraints(val.constraints.clone());
+        event.set_unknown_keys(val.unknown_keys);
+        event.set_timestamp_field(val.timestamp_field.clone());
+        event.set_computed(val.computed.clone());
+        events.insert(key.clone(), event);
+    }
+    let mut actions = HashMap::new();
+    for (key, val) in actions_to_send.iter() {
+        let action_format = ActionFormat::from_parts(&val.format, val.binary_layout.clone())
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?;
+        let mut action_payload = HashMap::new();
+        for (action_key, action_value) in val.payload.clone() {
+            let value = ActionDataType::from_str(&action_value)
+                .map_err(DeviceRepositoryError::InternalError)?;
+            action_payload.insert(action_key, value);
+        }
+
+        let mut action = ActionEmittable::new(action_format, action_payload);
+        action.set_constraints(val.constraints.clone());
+        action.set_unknown_keys(val.unknown_keys);
+        actions.insert(key.clone(), action);
+    }
+    Ok((events, actions))
+}
+
+#[derive(Deserialize)]
+pub struct DeviceSchemaToReceive {
+    pub version: u32,
+    pub created_at: String,
+    pub events: HashMap<String, EventEmittableToSend>,
+    pub actions: HashMap<String, ActionEmittableToSend>,
+}
+
+impl TryFrom<DeviceSchemaToReceive> for DeviceSchema {
+    type Error = DeviceRepositoryError;
+
+    fn try_from(schema: DeviceSchemaToReceive) -> Result<Self, Self::Error> {
+        let created_at = chrono::DateTime::parse_from_rfc3339(&schema.created_at)
+            .map_err(|e| DeviceRepositoryError::InternalError(e.to_string()))?
+            .with_timezone(&chrono::Utc);
+        let (events, actions) = emittables_from_send(&schema.events, &schema.actions)?;
+        Ok(DeviceSchema::new(schema.version, &created_at, events, actions))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeviceModelToReceive {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub events: HashMap<String, EventEmittableToSend>,
+    pub actions: HashMap<String, ActionEmittableToSend>,
+}
+
+impl TryFrom<DeviceModelToReceive> for DeviceModel {
+    type Error = DeviceModelRepositoryError;
+
+    fn try_from(model: DeviceModelToReceive) -> Result<Self, Self::Error> {
+        let id = Uuid::from_str(&model.id)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        let user_id = Uuid::from_str(&model.user_id)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        let (events, actions) = emittables_from_send(&model.events, &model.actions)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(format!("{:?}", e)))?;
+        Ok(DeviceModel::new(&id, &user_id, &model.name, events, actions))
+    }
+}
+
+#[derive(Deserialize)]
+pub struct DeviceGroupToReceive {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    #[serde(default)]
+    pub parent_id: Option<Uuid>,
+    #[serde(default)]
+    pub device_ids: BTreeSet<Uuid>,
+}
+
+impl From<DeviceGroupToReceive> for DeviceGroup {
+    fn from(group: DeviceGroupToReceive) -> Self {
+        let mut device_group = DeviceGroup::new(&group.id, &group.user_id, &group.name, group.parent_id);
+        device_group.set_device_ids(group.device_ids);
+        device_group
+    }
+}
+
+#[derive(Deserialize)]
+pub struct RuleToReceive {
+    pub id: Uuid,
+    pub user_id: Uuid,
+    pub name: String,
+    pub device_id: Uuid,
+    #[serde(default)]
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    pub debounce: u64,
+    pub cooldown: u64,
+    pub enabled: bool,
+    #[serde(default)]
+    pub matching_since: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub last_triggered_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<RuleToReceive> for Rule {
+    fn from(rule: RuleToReceive) -> Self {
+        Rule {
+            id: rule.id,
+            user_id: rule.user_id,
+            name: rule.name,
+            device_id: rule.device_id,
+            event_name: rule.event_name,
+            conditions: rule.conditions,
+            action: rule.action,
+            debounce: rule.debounce,
+            cooldown: rule.cooldown,
+            enabled: rule.enabled,
+            matching_since: rule.matching_since,
+            last_triggered_at: rule.last_triggered_at,
+        }
     }
 }
 
@@ -117,6 +291,14 @@ pub struct DeviceStateToSend {
     pub device_id: String,
     pub last_update: String,
     pub values: HashMap<String, Value>,
+    #[serde(default)]
+    pub sources: HashMap<String, ValueSource>,
+    #[serde(default)]
+    pub desired: HashMap<String, Value>,
+    #[serde(default)]
+    pub desired_update: Option<chrono::DateTime<chrono::Utc>>,
+    #[serde(default)]
+    pub converged_at: Option<chrono::DateTime<chrono::Utc>>,
 }
 
 impl From<DeviceState> for DeviceStateToSend {
@@ -125,10 +307,18 @@ impl From<DeviceState> for DeviceStateToSend {
             device_id: device_state.device_id.to_string(),
             last_update: device_state.last_update.to_rfc3339(),
             values: device_state
-                .values
+                .raw_values()
                 .into_iter()
                 .map(|(k, v)| (k, v.into()))
                 .collect(),
+            sources: device_state.sources(),
+            desired: device_state
+                .desired
+                .into_iter()
+                .map(|(k, v)| (k, v.into()))
+                .collect(),
+            desired_update: device_state.desired_update,
+            converged_at: device_state.converged_at,
         }
     }
 }
@@ -150,11 +340,16 @@ impl TryFrom<DeviceStateToSend> for DeviceState {
             })?;
             values.insert(key, val);
         }
-        Ok(DeviceState {
-            device_id,
-            last_update,
-            values,
-        })
+        let mut device_state = DeviceState::from_parts(device_id, last_update, values, device_state_to_send.sources);
+        for (key, val) in device_state_to_send.desired.into_iter() {
+            let val = EventDataValue::try_from(val).map_err(|_| {
+                DeviceStateRepositoryError::InternalError(format!("Unknown type given in desired {}", &key))
+            })?;
+            device_state.desired.insert(key, val);
+        }
+        device_state.desired_update = device_state_to_send.desired_update;
+        device_state.converged_at = device_state_to_send.converged_at;
+        Ok(device_state)
     }
 }
 
@@ -173,6 +368,8 @@ pub struct EventToReceive {
     pub event_name: String,
     pub timestamp: String,
     pub payload: HashMap<String, Value>,
+    #[serde(default)]
+    pub schema_version: Option<u32>,
 }
 
 impl TryFrom<EventToReceive> for Event {
@@ -199,6 +396,7 @@ impl TryFrom<EventToReceive> for Event {
             event_name: event_to_send.event_name,
             timestamp,
             payload,
+            schema_version: event_to_send.schema_version,
         })
     }
 }
@@ -206,18 +404,33 @@ impl TryFrom<EventToReceive> for Event {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct EventEmittableToSend {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timestamp_field: Option<TimestampField>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub computed: HashMap<String, Expression>,
 }
 
 impl From<&EventEmittable> for EventEmittableToSend {
     fn from(value: &EventEmittable) -> Self {
         EventEmittableToSend {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value
                 .payload()
                 .iter()
                 .map(|(k, v)| (k.clone(), v.to_string()))
                 .collect(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
+            timestamp_field: value.timestamp_field().cloned(),
+            computed: value.computed().clone(),
         }
     }
 }
@@ -225,18 +438,27 @@ impl From<&EventEmittable> for EventEmittableToSend {
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ActionEmittableToSend {
     pub format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub binary_layout: Option<BinaryLayout>,
     pub payload: HashMap<String, String>,
+    #[serde(default)]
+    pub constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    pub unknown_keys: UnknownKeyPolicy,
 }
 
 impl From<&ActionEmittable> for ActionEmittableToSend {
     fn from(value: &ActionEmittable) -> Self {
         ActionEmittableToSend {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value
                 .payload()
                 .iter()
                 .map(|(k, v)| (k.clone(), v.to_string()))
                 .collect(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
         }
     }
 }
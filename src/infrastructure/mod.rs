@@ -5,4 +5,9 @@ pub mod app_outbound;
 pub mod app_inbound;
 pub mod ui;
 pub mod serial;
+pub mod connectivity;
+pub mod action_scheduling;
+#[cfg(feature = "axum")]
+pub mod action_broadcast;
+pub mod rules;
 mod utils;
\ No newline at end of file
@@ -0,0 +1,144 @@
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use rumqttc::Publish;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::DeviceService},
+    },
+    domain::action::{action::Action, action_format::ActionFormat, action_status::ActionStatus},
+    infrastructure::{
+        mqtt::{
+            inbound::error::HandlerError,
+            mqtt_messages::{
+                AcknowledgeActionPayload, CreateActionPayload, MqttActionType, MqttMessage,
+                UpdateActionStatusPayload,
+            },
+        },
+        utils,
+    },
+};
+
+pub async fn handle_action<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+    match data.action_type {
+        MqttActionType::Create => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_create_action(payload, state).await
+        }
+        MqttActionType::Update => {
+            let payload: UpdateActionStatusPayload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            let action_id = parse_uuid(&payload.action_id)?;
+            let timestamp = parse_timestamp(&payload.timestamp)?;
+            state
+                .get_action_service()
+                .update_action_status(&payload.device_id, action_id, payload.status, &timestamp)
+                .await?;
+            Ok(())
+        }
+        MqttActionType::Delete => Err(HandlerError::ParsingError(
+            "Invalid payload, no delete on actions".to_string(),
+        )),
+    }
+}
+
+/// Acknowledgements published by the devices.
+pub async fn handle_action_ack<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+    let MqttActionType::Create = data.action_type else {
+        return Err(HandlerError::ParsingError(
+            "Invalid payload, acknowledgements are only created".to_string(),
+        ));
+    };
+    let payload: AcknowledgeActionPayload = serde_json::from_value(data.payload).map_err(|e| {
+        HandlerError::ParsingError(format!("Invalid payload: {}", e))
+    })?;
+    if !payload.status.is_acknowledgement() {
+        return Err(HandlerError::ClientError(format!(
+            "Devices cannot report the {} status",
+            payload.status
+        )));
+    }
+    let action_id = parse_uuid(&payload.action_id)?;
+    let timestamp = match payload.timestamp {
+        Some(timestamp) => parse_timestamp(&timestamp)?,
+        None => Utc::now(),
+    };
+    let device = state
+        .get_device_service()
+        .get_device_by_physical_id(&payload.device_physical_id)
+        .await?
+        .ok_or_else(|| HandlerError::ClientError("Device not found".to_string()))?;
+    state
+        .get_action_service()
+        .update_action_status(&device.id().to_string(), action_id, payload.status, &timestamp)
+        .await?;
+    Ok(())
+}
+
+// actions published on the topic reached the devices, they are stored as sent
+async fn handle_create_action<AO: AppOutbound + 'static>(
+    payload: CreateActionPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let timestamp = parse_timestamp(&payload.timestamp)?;
+    let device = state
+        .get_device_service()
+        .get_device(parse_uuid(&payload.device_id)?)
+        .await?
+        .ok_or_else(|| HandlerError::ClientError("Device not found".to_string()))?;
+    let action_format = ActionFormat::from_parts(
+        payload.action_format.as_deref().unwrap_or("json"),
+        payload.action_binary_layout.clone(),
+    )
+    .map_err(HandlerError::ParsingError)?;
+    let action_data = utils::payload_from_text(&payload.action_data, action_format.is_binary())
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid action data: {}", e)))?;
+    let values = action_format
+        .decode_action(&action_data)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid action data: {}", e)))?;
+    let mut action = Action::from_values(&device, &timestamp, &payload.device_action_name, values)
+        .map_err(|e| HandlerError::ClientError(format!("Invalid action: {}", e)))?;
+    if let Some(action_id) = &payload.action_id {
+        action.id = parse_uuid(action_id)?;
+    }
+    if let Some(expires_at) = &payload.expires_at {
+        action.expires_at = Some(parse_timestamp(expires_at)?);
+    }
+    // scheduled actions expiring before being due are only stored
+    let now = Utc::now();
+    let status = if action.is_expired(&now) { ActionStatus::Expired } else { ActionStatus::Sent };
+    action
+        .transition(status, &now)
+        .map_err(HandlerError::InternalError)?;
+    state
+        .get_action_service()
+        .send_action(action, &action_format)
+        .await?;
+    Ok(())
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, HandlerError> {
+    Uuid::from_str(id).map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))
+}
+
+fn parse_timestamp(timestamp: &str) -> Result<DateTime<Utc>, HandlerError> {
+    DateTime::from_str(timestamp)
+        .map_err(|_| HandlerError::ParsingError("invalid timestamp format".to_string()))
+}
@@ -0,0 +1,42 @@
+use rumqttc::{AsyncClient, QoS};
+use tracing::warn;
+
+use crate::{
+    domain::connectivity::ConnectivityChange,
+    infrastructure::mqtt::mqtt_messages::{self, ConnectivityPayload, MqttActionType},
+};
+
+/// Publishes the connectivity changes of devices on the connectivity topic.
+#[derive(Debug, Clone)]
+pub struct ConnectivityPublisher {
+    mqtt_client: AsyncClient,
+    connectivity_topic: String,
+}
+
+impl ConnectivityPublisher {
+    pub fn new(mqtt_client: AsyncClient, connectivity_topic: &str) -> Self {
+        Self {
+            mqtt_client,
+            connectivity_topic: connectivity_topic.to_string(),
+        }
+    }
+
+    pub fn publish(&self, change: &ConnectivityChange) {
+        let payload = ConnectivityPayload {
+            device_id: change.device_id.to_string(),
+            device_physical_id: change.device_physical_id.clone(),
+            connectivity: change.connectivity,
+            timestamp: change.timestamp.to_rfc3339(),
+        };
+        let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create) {
+            Ok(message) => message,
+            Err(e) => {
+                warn!(result = "warn", details = format!("Invalid connectivity change: {}", e));
+                return;
+            }
+        };
+        if let Err(e) = self.mqtt_client.try_publish(&self.connectivity_topic, QoS::AtLeastOnce, false, message) {
+            warn!(result = "warn", details = format!("Failed to publish connectivity change: {}", e));
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use std::{collections::BTreeSet, str::FromStr};
+
+use rumqttc::Publish;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{app::AppOutbound, inbound::device_group_service::DeviceGroupService},
+    domain::device_group::DeviceGroup,
+    infrastructure::mqtt::{
+        inbound::error::HandlerError,
+        mqtt_messages::{
+            CreateDeviceGroupPayload, DeleteDeviceGroupPayload, MqttActionType, MqttMessage,
+            UpdateDeviceGroupPayload,
+        },
+    },
+};
+
+#[tracing::instrument]
+pub async fn handle_device_group<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+    match data.action_type {
+        MqttActionType::Create => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_create_device_group(payload, state).await
+        }
+        MqttActionType::Delete => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_delete_device_group(payload, state).await
+        }
+        MqttActionType::Update => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_update_device_group(payload, state).await
+        }
+    }
+}
+
+pub async fn handle_create_device_group<AO: AppOutbound + 'static>(
+    group: CreateDeviceGroupPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_group_service = state.get_device_group_service();
+    let mut device_group = DeviceGroup::new(
+        &parse_uuid(&group.id)?,
+        &parse_uuid(&group.user_id)?,
+        &group.name,
+        group.parent_id.as_deref().map(parse_uuid).transpose()?,
+    );
+    device_group.set_device_ids(parse_device_ids(&group.device_ids)?);
+    device_group_service.create_device_group(&device_group).await?;
+    Ok(())
+}
+
+pub async fn handle_delete_device_group<AO: AppOutbound + 'static>(
+    group: DeleteDeviceGroupPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_group_service = state.get_device_group_service();
+    device_group_service.delete_device_group(parse_uuid(&group.id)?).await?;
+    Ok(())
+}
+
+pub async fn handle_update_device_group<AO: AppOutbound + 'static>(
+    group: UpdateDeviceGroupPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_group_service = state.get_device_group_service();
+    // the payload carries the whole group, a missing parent makes it a root group
+    let parent_id = group.parent_id.as_deref().map(parse_uuid).transpose()?;
+    device_group_service
+        .update_device_group(
+            parse_uuid(&group.id)?,
+            Some(group.name),
+            Some(parent_id),
+            Some(parse_device_ids(&group.device_ids)?),
+        )
+        .await?;
+    Ok(())
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, HandlerError> {
+    Uuid::from_str(id).map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))
+}
+
+fn parse_device_ids(device_ids: &[String]) -> Result<BTreeSet<Uuid>, HandlerError> {
+    device_ids.iter().map(|id| parse_uuid(id)).collect()
+}
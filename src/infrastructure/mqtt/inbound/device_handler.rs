@@ -5,14 +5,17 @@ use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{
-    application::ports::{app::AppOutbound, inbound::device_service::DeviceService},
+    application::ports::{app::AppOutbound, inbound::device_service::{DeviceService, DeviceUpdate}},
     domain::device::Device,
-    infrastructure::mqtt::{
-        inbound::error::HandlerError,
-        mqtt_messages::{
-            CreateDevicePayload, DeleteDevicePayload, MqttActionType, MqttMessage,
-            UpdateDevicePayload, deserialize_actions, deserialize_events,
+    infrastructure::{
+        mqtt::{
+            inbound::error::HandlerError,
+            mqtt_messages::{
+                CreateDevicePayload, DeleteDevicePayload, MqttActionType, MqttMessage,
+                UpdateDevicePayload, deserialize_actions, deserialize_events,
+            },
         },
+        utils,
     },
 };
 
@@ -52,7 +55,10 @@ pub async fn handle_create_device<AO: AppOutbound + 'static>(
     let device_service = state.get_device_service();
     let events = deserialize_events(&device.events)?;
     let actions = deserialize_actions(&device.actions)?;
-    let device = Device::new(
+    let descriptor_set = decode_descriptor_set(device.descriptor_set.as_deref())?;
+    let device_model_id = device.model_id;
+    let (labels, tags, state_merge_policy, heartbeat_interval) = (device.labels, device.tags, device.state_merge_policy, device.heartbeat_interval);
+    let mut device = Device::new(
         &Uuid::from_str(&device.id)
             .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?,
         &device.physical_id,
@@ -62,6 +68,12 @@ pub async fn handle_create_device<AO: AppOutbound + 'static>(
         events,
         actions,
     );
+    device.set_descriptor_set(descriptor_set);
+    device.set_model_id(parse_model_id(device_model_id.as_deref())?);
+    device.set_labels(labels);
+    device.set_tags(tags);
+    device.set_state_merge_policy(state_merge_policy);
+    device.set_heartbeat_interval(heartbeat_interval);
     device_service.create_device(&device).await?;
     Ok(())
 }
@@ -86,14 +98,39 @@ pub async fn handle_update_device<AO: AppOutbound + 'static>(
         .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
     let events = deserialize_events(&device.events)?;
     let actions = deserialize_actions(&device.actions)?;
+    let descriptor_set = decode_descriptor_set(device.descriptor_set.as_deref())?;
+    // the payload carries the whole device, a missing model ID detaches it
+    let model_id = parse_model_id(device.model_id.as_deref())?;
     device_service
         .update_device(
             device_id,
-            Some(device.physical_id),
-            Some(device.name),
-            Some(events),
-            Some(actions),
+            DeviceUpdate {
+                physical_id: Some(device.physical_id),
+                name: Some(device.name),
+                events: Some(events),
+                actions: Some(actions),
+                descriptor_set,
+                model_id: Some(model_id),
+                labels: Some(device.labels),
+                tags: Some(device.tags),
+                state_merge_policy: Some(device.state_merge_policy),
+                heartbeat_interval: Some(device.heartbeat_interval),
+            },
         )
         .await?;
     Ok(())
 }
+
+fn decode_descriptor_set(descriptor_set: Option<&str>) -> Result<Option<Vec<u8>>, HandlerError> {
+    descriptor_set
+        .map(|d| utils::payload_from_text(d, true))
+        .transpose()
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid descriptor set: {}", e)))
+}
+
+fn parse_model_id(model_id: Option<&str>) -> Result<Option<Uuid>, HandlerError> {
+    model_id
+        .map(Uuid::from_str)
+        .transpose()
+        .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))
+}
@@ -0,0 +1,92 @@
+use std::str::FromStr;
+
+use rumqttc::Publish;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{app::AppOutbound, inbound::device_model_service::DeviceModelService},
+    domain::device_model::DeviceModel,
+    infrastructure::mqtt::{
+        inbound::error::HandlerError,
+        mqtt_messages::{
+            CreateDeviceModelPayload, DeleteDeviceModelPayload, MqttActionType, MqttMessage,
+            UpdateDeviceModelPayload, deserialize_actions, deserialize_events,
+        },
+    },
+};
+
+#[tracing::instrument]
+pub async fn handle_device_model<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+    match data.action_type {
+        MqttActionType::Create => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_create_device_model(payload, state).await
+        }
+        MqttActionType::Delete => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_delete_device_model(payload, state).await
+        }
+        MqttActionType::Update => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_update_device_model(payload, state).await
+        }
+    }
+}
+
+pub async fn handle_create_device_model<AO: AppOutbound + 'static>(
+    model: CreateDeviceModelPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_model_service = state.get_device_model_service();
+    let events = deserialize_events(&model.events)?;
+    let actions = deserialize_actions(&model.actions)?;
+    let model = DeviceModel::new(
+        &Uuid::from_str(&model.id)
+            .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?,
+        &Uuid::from_str(&model.user_id)
+            .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?,
+        &model.name,
+        events,
+        actions,
+    );
+    device_model_service.create_device_model(&model).await?;
+    Ok(())
+}
+
+pub async fn handle_delete_device_model<AO: AppOutbound + 'static>(
+    model: DeleteDeviceModelPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_model_service = state.get_device_model_service();
+    let model_id = Uuid::from_str(&model.id)
+        .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
+    device_model_service.delete_device_model(model_id).await?;
+    Ok(())
+}
+
+pub async fn handle_update_device_model<AO: AppOutbound + 'static>(
+    model: UpdateDeviceModelPayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_model_service = state.get_device_model_service();
+    let model_id = Uuid::from_str(&model.id)
+        .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
+    let events = deserialize_events(&model.events)?;
+    let actions = deserialize_actions(&model.actions)?;
+    device_model_service
+        .update_device_model(model_id, Some(model.name), Some(events), Some(actions))
+        .await?;
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use std::{collections::HashMap, str::FromStr};
+
+use rumqttc::Publish;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{
+            action_service::ActionService, device_service::DeviceService,
+            device_state_service::DeviceStateService,
+        },
+    },
+    domain::event::event_data_value::EventDataValue,
+    infrastructure::mqtt::{
+        inbound::error::HandlerError,
+        mqtt_messages::{ClearDesiredStatePayload, MqttActionType, MqttMessage, SetDesiredStatePayload},
+    },
+};
+
+pub async fn handle_device_shadow<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+
+    match data.action_type {
+        MqttActionType::Create | MqttActionType::Update => {
+            let payload: SetDesiredStatePayload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            let mut desired = HashMap::new();
+            for (k, v) in payload.desired {
+                let val = EventDataValue::try_from(v).map_err(|_| {
+                    HandlerError::ParsingError(format!("Invalid desired data received for key {}", k))
+                })?;
+                desired.insert(k, val);
+            }
+            handle_set_desired_state(&payload.device_id, desired, state).await
+        }
+        MqttActionType::Delete => {
+            let payload: ClearDesiredStatePayload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_set_desired_state(&payload.device_id, HashMap::new(), state).await
+        }
+    }
+}
+
+async fn handle_set_desired_state<AO: AppOutbound + 'static>(
+    device_id: &str,
+    desired: HashMap<String, EventDataValue>,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device_id = Uuid::from_str(device_id)
+        .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
+    let device = state
+        .get_device_service()
+        .get_device(device_id)
+        .await?
+        .ok_or_else(|| HandlerError::ClientError("Device not found".to_string()))?;
+    let (_, actions) = state
+        .get_device_state_service()
+        .set_desired_state(&device, desired)
+        .await?;
+    let action_service = state.get_action_service();
+    for action in actions {
+        let format = device
+            .action(&action.action_name)
+            .ok_or_else(|| HandlerError::InternalError(format!("Action '{}' not found in device actions", action.action_name)))?
+            .format();
+        action_service.send_action(action, format).await?;
+    }
+    Ok(())
+}
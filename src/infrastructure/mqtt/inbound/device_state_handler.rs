@@ -1,11 +1,12 @@
 use std::{collections::HashMap, str::FromStr};
 
+use chrono::{DateTime, Utc};
 use rumqttc::Publish;
 use serde_json::Value;
 use uuid::Uuid;
 
 use crate::{
-    application::ports::{app::AppOutbound, inbound::device_state_service::DeviceStateService}, domain::event::event_data_value::EventDataValue, infrastructure::mqtt::{
+    application::ports::{app::AppOutbound, inbound::{device_service::DeviceService, device_state_service::DeviceStateService}}, domain::{event::event_data_value::EventDataValue, state::{DeviceState, StateMergePolicy, StateValue}}, infrastructure::mqtt::{
         inbound::error::HandlerError,
         mqtt_messages::{
             CreateDeviceStatePayload, DeleteDeviceStatePayload, MqttActionType, MqttMessage,
@@ -49,6 +50,22 @@ async fn handle_create_device_state<AO: AppOutbound + 'static>(
     let device_state_service = state.get_device_state_service();
     let device_id = Uuid::from_str(&device_state.device_id)
         .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
+    let desired = device_state.desired_update.is_some().then(|| device_state.desired.clone());
+    let values = parse_state_values(device_id, device_state)?;
+    device_state_service
+        .create_device_state(device_id, values)
+        .await?;
+    if let Some(desired) = desired {
+        apply_desired(device_id, desired, state).await?;
+    }
+    Ok(())
+}
+
+/// Values with their source, as published by the MQTT device state repository.
+fn parse_state_values(device_id: Uuid, device_state: CreateDeviceStatePayload) -> Result<HashMap<String, StateValue>, HandlerError> {
+    let last_update = DateTime::parse_from_rfc3339(&device_state.last_update)
+        .map_err(|_| HandlerError::ParsingError("invalid last_update format".to_string()))?
+        .with_timezone(&Utc);
     let mut values = HashMap::new();
     for (k, v) in device_state.values {
         let val = EventDataValue::try_from(v).map_err(|_| {
@@ -56,10 +73,7 @@ async fn handle_create_device_state<AO: AppOutbound + 'static>(
         })?;
         values.insert(k, val);
     }
-    device_state_service
-        .create_device_state(device_id, values)
-        .await?;
-    Ok(())
+    Ok(DeviceState::from_parts(device_id, last_update, values, device_state.sources).values)
 }
 
 async fn handle_delete_device_state<AO: AppOutbound + 'static>(
@@ -81,15 +95,41 @@ async fn handle_update_device_state<AO: AppOutbound + 'static>(
     let device_state_service = state.get_device_state_service();
     let device_id = Uuid::from_str(&device_state.device_id)
         .map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))?;
+    let desired_update = device_state.desired_update.clone();
+    let desired = device_state.desired.clone();
+    // states published without a policy are already merged, they are applied as is
+    let policy = device_state.merge_policy.unwrap_or(StateMergePolicy::ArrivalTime);
+    let values = parse_state_values(device_id, device_state)?;
+    let updated = device_state_service
+        .update_device_state(device_id, values, policy)
+        .await?;
+    if desired_update.is_some() && desired_update != updated.desired_update.map(|d| d.to_rfc3339()) {
+        apply_desired(device_id, desired, state).await?;
+    }
+    Ok(())
+}
+
+/// Stores desired values set by the publisher, which already sent the converging actions.
+async fn apply_desired<AO: AppOutbound + 'static>(
+    device_id: Uuid,
+    desired: HashMap<String, Value>,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let device = state
+        .get_device_service()
+        .get_device(device_id)
+        .await?
+        .ok_or_else(|| HandlerError::ClientError("Device not found".to_string()))?;
     let mut values = HashMap::new();
-    for (k, v) in device_state.values {
+    for (k, v) in desired {
         let val = EventDataValue::try_from(v).map_err(|_| {
-            HandlerError::ParsingError(format!("Invalid data received for key {}", k))
+            HandlerError::ParsingError(format!("Invalid desired data received for key {}", k))
         })?;
         values.insert(k, val);
     }
-    device_state_service
-        .update_device_state(device_id, values)
+    state
+        .get_device_state_service()
+        .set_desired_state(&device, values)
         .await?;
     Ok(())
 }
@@ -1,7 +1,9 @@
 use crate::{
     application::ports::inbound::{
-        device_service::DeviceServiceError, device_state_service::DeviceStateServiceError,
+        action_service::ActionServiceError, device_group_service::DeviceGroupServiceError, device_model_service::DeviceModelServiceError, device_service::DeviceServiceError,
+        device_state_service::DeviceStateServiceError,
         event_service::EventServiceError,
+        rule_service::RuleServiceError,
     }, domain::event::event_format::EventFormatError,
 };
 
@@ -17,6 +19,9 @@ impl From<EventFormatError> for HandlerError {
             EventFormatError::UnsupportedFormat(e) => {
                 HandlerError::ParsingError(format!("Invalid event format : {}", e))
             }
+            EventFormatError::ConstraintViolation(e) => {
+                HandlerError::ClientError(format!("Constraint violation : {}", e))
+            }
         }
     }
 }
@@ -30,8 +35,8 @@ impl From<DeviceServiceError> for HandlerError {
             DeviceServiceError::AlreadyExists => {
                 HandlerError::ClientError("Device already exists".to_string())
             }
-            DeviceServiceError::InvalidInput => {
-                HandlerError::ClientError("Invalid input provided".to_string())
+            DeviceServiceError::InvalidInput(err) => {
+                HandlerError::ClientError(format!("Invalid input provided : {}", err))
             }
             DeviceServiceError::InternalError(err) => {
                 HandlerError::InternalError(format!("on device service : {}", err))
@@ -40,6 +45,66 @@ impl From<DeviceServiceError> for HandlerError {
     }
 }
 
+impl From<DeviceGroupServiceError> for HandlerError {
+    fn from(value: DeviceGroupServiceError) -> Self {
+        match value {
+            DeviceGroupServiceError::NotFound => {
+                HandlerError::ClientError("Device group not found".to_string())
+            }
+            DeviceGroupServiceError::AlreadyExists => {
+                HandlerError::ClientError("Device group already exists".to_string())
+            }
+            DeviceGroupServiceError::InvalidInput(err) => {
+                HandlerError::ClientError(format!("Invalid input provided : {}", err))
+            }
+            DeviceGroupServiceError::InternalError(err) => {
+                HandlerError::InternalError(format!("on device group service : {}", err))
+            }
+        }
+    }
+}
+
+impl From<RuleServiceError> for HandlerError {
+    fn from(value: RuleServiceError) -> Self {
+        match value {
+            RuleServiceError::NotFound => {
+                HandlerError::ClientError("Rule not found".to_string())
+            }
+            RuleServiceError::AlreadyExists => {
+                HandlerError::ClientError("Rule already exists".to_string())
+            }
+            RuleServiceError::InvalidInput(err) => {
+                HandlerError::ClientError(format!("Invalid input provided : {}", err))
+            }
+            RuleServiceError::InternalError(err) => {
+                HandlerError::InternalError(format!("on rule service : {}", err))
+            }
+        }
+    }
+}
+
+impl From<DeviceModelServiceError> for HandlerError {
+    fn from(value: DeviceModelServiceError) -> Self {
+        match value {
+            DeviceModelServiceError::NotFound => {
+                HandlerError::ClientError("Device model not found".to_string())
+            }
+            DeviceModelServiceError::AlreadyExists => {
+                HandlerError::ClientError("Device model already exists".to_string())
+            }
+            DeviceModelServiceError::InUse => {
+                HandlerError::ClientError("Device model is used by devices".to_string())
+            }
+            DeviceModelServiceError::InvalidInput(err) => {
+                HandlerError::ClientError(format!("Invalid input provided : {}", err))
+            }
+            DeviceModelServiceError::InternalError(err) => {
+                HandlerError::InternalError(format!("on device model service : {}", err))
+            }
+        }
+    }
+}
+
 impl From<DeviceStateServiceError> for HandlerError {
     fn from(value: DeviceStateServiceError) -> Self {
         match value {
@@ -52,8 +117,8 @@ impl From<DeviceStateServiceError> for HandlerError {
             DeviceStateServiceError::AlreadyExists => {
                 HandlerError::ClientError("Device already exists".to_string())
             }
-            DeviceStateServiceError::InvalidInput => {
-                HandlerError::ClientError("Invalid input provided".to_string())
+            DeviceStateServiceError::InvalidInput(err) => {
+                HandlerError::ClientError(format!("Invalid input provided: {}", err))
             }
             DeviceStateServiceError::InternalError(err) => {
                 HandlerError::InternalError(format!("on device state service, {}", err))
@@ -62,6 +127,22 @@ impl From<DeviceStateServiceError> for HandlerError {
     }
 }
 
+impl From<ActionServiceError> for HandlerError {
+    fn from(value: ActionServiceError) -> Self {
+        match value {
+            ActionServiceError::NotFound => {
+                HandlerError::ClientError("Action not found".to_string())
+            }
+            ActionServiceError::InvalidInput(s) => {
+                HandlerError::ClientError(format!("invalid input, {}", s))
+            }
+            ActionServiceError::InternalError(err) => {
+                HandlerError::InternalError(format!("on action service, {}", err))
+            }
+        }
+    }
+}
+
 impl From<EventServiceError> for HandlerError {
     fn from(value: EventServiceError) -> Self {
         match value {
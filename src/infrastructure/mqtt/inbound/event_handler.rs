@@ -3,6 +3,7 @@ use std::str::FromStr;
 use chrono::DateTime;
 use rumqttc::Publish;
 use serde_json::Value;
+use tracing::warn;
 
 use crate::{
     application::ports::{
@@ -13,15 +14,20 @@ use crate::{
         },
     },
     domain::event::event::Event,
-    infrastructure::mqtt::{
-        inbound::error::HandlerError,
-        mqtt_messages::{CreateEventPayload, MqttActionType, MqttMessage},
+    infrastructure::{
+        connectivity,
+        mqtt::{
+            inbound::{connectivity_publisher::ConnectivityPublisher, error::HandlerError},
+            mqtt_messages::{CreateEventPayload, MqttActionType, MqttMessage},
+        },
+        rules, utils,
     },
 };
 
 pub async fn handle_event<AO: AppOutbound + 'static>(
     received: &Publish,
     state: &AO,
+    publisher: &ConnectivityPublisher,
 ) -> Result<(), HandlerError> {
     let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
         .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e.to_string())))?;
@@ -30,7 +36,7 @@ pub async fn handle_event<AO: AppOutbound + 'static>(
             let payload = serde_json::from_value(data.payload).map_err(|e| {
                 HandlerError::ParsingError(format!("Invalid payload: {}", e.to_string()))
             })?;
-            handle_create_event(payload, state).await
+            handle_create_event(payload, state, publisher).await
         }
         MqttActionType::Delete => {
             return Err(HandlerError::ParsingError(
@@ -48,6 +54,7 @@ pub async fn handle_event<AO: AppOutbound + 'static>(
 async fn handle_create_event<AO: AppOutbound + 'static>(
     event: CreateEventPayload,
     state: &AO,
+    publisher: &ConnectivityPublisher,
 ) -> Result<(), HandlerError> {
     let timestamp = DateTime::from_str(&event.timestamp)
         .map_err(|_| HandlerError::ParsingError("invalid timestamp format".to_string()))?;
@@ -62,20 +69,29 @@ async fn handle_create_event<AO: AppOutbound + 'static>(
         None => return Err(HandlerError::ParsingError("Device not found".to_string())), // Skip if device not found
     };
     let event_concerned = device
-        .events()
-        .get(&event.device_event_name)
-        .expect("Check done before");
+        .event(&event.device_event_name)
+        .ok_or_else(|| HandlerError::ClientError(format!("Event '{}' not found in device events", event.device_event_name)))?;
+    let event_data = utils::payload_from_text(&event.event_data, event_concerned.format().is_binary())
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid event data: {}", e)))?;
     let event = Event::new_checked(
         &device,
         &timestamp,
         &event.device_event_name,
-        &event.event_data.as_bytes(),
+        &event_data,
     )?;
     event_service
         .handle_event(event.clone(), &event_concerned.format())
         .await?;
-    device_state_service
-        .update_device_state(device.id().clone(), event.payload)
+    let device_state = device_state_service
+        .update_device_state(*device.id(), event.state_values(), device.state_merge_policy())
         .await?;
+    match connectivity::record_activity(state, &device).await {
+        Ok(Some(change)) => publisher.publish(&change),
+        Ok(None) => {}
+        Err(e) => warn!(result = "warn", details = format!("Failed to record activity: {}", e)),
+    }
+    if let Err(e) = rules::run_rules(state, &device, &event, Some(&device_state)).await {
+        warn!(result = "warn", details = format!("Failed to run rules: {}", e));
+    }
     Ok(())
 }
@@ -1,4 +1,10 @@
+pub mod action_handler;
 pub mod event_handler;
 pub mod device_handler;
+pub mod device_group_handler;
+pub mod device_model_handler;
 pub mod device_state_handler;
+pub mod device_shadow_handler;
+pub mod rule_handler;
+pub mod connectivity_publisher;
 pub mod error;
\ No newline at end of file
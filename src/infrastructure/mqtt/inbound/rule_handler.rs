@@ -0,0 +1,102 @@
+use std::str::FromStr;
+
+use rumqttc::Publish;
+use serde_json::Value;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{app::AppOutbound, inbound::rule_service::{RuleService, RuleUpdate}},
+    domain::rule::Rule,
+    infrastructure::mqtt::{
+        inbound::error::HandlerError,
+        mqtt_messages::{
+            CreateRulePayload, DeleteRulePayload, MqttActionType, MqttMessage, UpdateRulePayload,
+        },
+    },
+};
+
+#[tracing::instrument]
+pub async fn handle_rule<AO: AppOutbound + 'static>(
+    received: &Publish,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let data: MqttMessage<Value> = serde_json::from_slice(&received.payload)
+        .map_err(|e| HandlerError::ParsingError(format!("Invalid payload: {}", e)))?;
+    match data.action_type {
+        MqttActionType::Create => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_create_rule(payload, state).await
+        }
+        MqttActionType::Delete => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_delete_rule(payload, state).await
+        }
+        MqttActionType::Update => {
+            let payload = serde_json::from_value(data.payload).map_err(|e| {
+                HandlerError::ParsingError(format!("Invalid payload: {}", e))
+            })?;
+            handle_update_rule(payload, state).await
+        }
+    }
+}
+
+pub async fn handle_create_rule<AO: AppOutbound + 'static>(
+    payload: CreateRulePayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let rule_service = state.get_rule_service();
+    let mut rule = Rule::new(
+        &parse_uuid(&payload.user_id)?,
+        &payload.name,
+        &parse_uuid(&payload.device_id)?,
+        payload.event_name,
+        payload.conditions,
+        payload.action,
+    );
+    rule.id = parse_uuid(&payload.id)?;
+    rule.debounce = payload.debounce;
+    rule.cooldown = payload.cooldown;
+    rule.enabled = payload.enabled;
+    rule_service.create_rule(&rule).await?;
+    Ok(())
+}
+
+pub async fn handle_delete_rule<AO: AppOutbound + 'static>(
+    payload: DeleteRulePayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let rule_service = state.get_rule_service();
+    rule_service.delete_rule(parse_uuid(&payload.id)?).await?;
+    Ok(())
+}
+
+pub async fn handle_update_rule<AO: AppOutbound + 'static>(
+    payload: UpdateRulePayload,
+    state: &AO,
+) -> Result<(), HandlerError> {
+    let rule_service = state.get_rule_service();
+    // the payload carries the whole rule, a missing event name evaluates every event
+    rule_service
+        .update_rule(
+            parse_uuid(&payload.id)?,
+            RuleUpdate {
+                name: Some(payload.name),
+                event_name: Some(payload.event_name),
+                conditions: Some(payload.conditions),
+                action: Some(payload.action),
+                debounce: Some(payload.debounce),
+                cooldown: Some(payload.cooldown),
+                enabled: Some(payload.enabled),
+            },
+        )
+        .await?;
+    Ok(())
+}
+
+fn parse_uuid(id: &str) -> Result<Uuid, HandlerError> {
+    Uuid::from_str(id).map_err(|_| HandlerError::ParsingError("invalid Uuid format".to_string()))
+}
@@ -2,4 +2,5 @@
 pub mod inbound;
 #[cfg(feature = "mqtt_outbound")]
 pub mod outbound;
+#[cfg(feature = "mqtt")]
 pub mod mqtt_messages;
\ No newline at end of file
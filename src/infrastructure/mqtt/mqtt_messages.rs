@@ -1,16 +1,20 @@
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
-use crate::domain::{
+use crate::domain::{binary_layout::BinaryLayout, 
     action::{
         action_data_type::ActionDataType, action_emittable::ActionEmittable,
-        action_format::ActionFormat,
+        action_format::ActionFormat, action_status::ActionStatus,
     },
     event::{
-        event_data_type::EventDataType, event_emittable::EventEmittable, event_format::EventFormat,
+        event_data_type::EventDataType, event_emittable::EventEmittable, event_format::EventFormat, event_timestamp::TimestampField, computed_field::Expression,
     },
+    field_constraints::{FieldConstraints, UnknownKeyPolicy},
+    rule::{RuleAction, RuleCondition},
+    state::{StateMergePolicy, ValueSource},
 };
 #[cfg(feature = "mqtt_inbound")]
 use crate::{
+    domain::connectivity::Connectivity,
     infrastructure::mqtt::inbound::error::HandlerError,
 };
 use serde::{Deserialize, Serialize};
@@ -39,6 +43,19 @@ pub struct CreateDevicePayload {
     pub name: String,
     pub events: String,
     pub actions: String,
+    /// Base64 encoded protobuf descriptor set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor_set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub state_merge_policy: StateMergePolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -49,6 +66,19 @@ pub struct UpdateDevicePayload {
     pub name: String,
     pub events: String,
     pub actions: String,
+    /// Base64 encoded protobuf descriptor set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub descriptor_set: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_id: Option<String>,
+    #[serde(default)]
+    pub labels: HashMap<String, String>,
+    #[serde(default)]
+    pub tags: BTreeSet<String>,
+    #[serde(default)]
+    pub state_merge_policy: StateMergePolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub heartbeat_interval: Option<u64>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -56,20 +86,147 @@ pub struct DeleteDevicePayload {
     pub id: String,
 }
 
+#[derive(Serialize, Deserialize)]
+pub struct CreateDeviceModelPayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub events: String,
+    pub actions: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateDeviceModelPayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub events: String,
+    pub actions: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteDeviceModelPayload {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateDeviceGroupPayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub device_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateDeviceGroupPayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub parent_id: Option<String>,
+    #[serde(default)]
+    pub device_ids: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteDeviceGroupPayload {
+    pub id: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct CreateRulePayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub device_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub debounce: u64,
+    #[serde(default)]
+    pub cooldown: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct UpdateRulePayload {
+    pub id: String,
+    pub user_id: String,
+    pub name: String,
+    pub device_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_name: Option<String>,
+    pub conditions: Vec<RuleCondition>,
+    pub action: RuleAction,
+    #[serde(default)]
+    pub debounce: u64,
+    #[serde(default)]
+    pub cooldown: u64,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct DeleteRulePayload {
+    pub id: String,
+}
+
 #[derive(Serialize, Deserialize)]
 pub struct CreateEventPayload {
     pub device_physical_id: String,
     pub device_event_name: String,
     pub timestamp: String,
+    /// Encoded with the event format, binary formats are base64 encoded.
     pub event_data: String,
 }
 
 #[derive(Serialize, Deserialize)]
 pub struct CreateActionPayload {
+    /// ID devices reference when acknowledging the action.
+    #[serde(default)]
+    pub action_id: Option<String>,
     pub device_id: String,
     pub device_action_name: String,
     pub timestamp: String,
+    /// Format `action_data` is encoded with, json when missing. Binary formats are base64 encoded.
+    #[serde(default)]
+    pub action_format: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub action_binary_layout: Option<BinaryLayout>,
     pub action_data: String,
+    /// Deadline after which devices must not run the action.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+}
+
+/// Status an action reached, `timestamp` dates the transition.
+#[derive(Serialize, Deserialize)]
+pub struct UpdateActionStatusPayload {
+    pub action_id: String,
+    pub device_id: String,
+    pub status: ActionStatus,
+    pub timestamp: String,
+}
+
+/// Acknowledgement published by a device, the reception time is used without `timestamp`.
+#[cfg(feature = "mqtt_inbound")]
+#[derive(Deserialize)]
+pub struct AcknowledgeActionPayload {
+    pub action_id: String,
+    pub device_physical_id: String,
+    pub status: ActionStatus,
+    #[serde(default)]
+    pub timestamp: Option<String>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -77,6 +234,16 @@ pub struct CreateDeviceStatePayload {
     pub device_id: String,
     pub last_update: String,
     pub values: HashMap<String, Value>,
+    /// Timestamp and event of each value, values without one date from `last_update`.
+    #[serde(default)]
+    pub sources: HashMap<String, ValueSource>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub desired: HashMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desired_update: Option<String>,
+    /// How the values are merged into the stored state, they replace it when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_policy: Option<StateMergePolicy>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -84,6 +251,16 @@ pub struct UpdateDeviceStatePayload {
     pub device_id: String,
     pub last_update: String,
     pub values: HashMap<String, Value>,
+    /// Timestamp and event of each value, values without one date from `last_update`.
+    #[serde(default)]
+    pub sources: HashMap<String, ValueSource>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub desired: HashMap<String, Value>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub desired_update: Option<String>,
+    /// How the values are merged into the stored state, they replace it when missing.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub merge_policy: Option<StateMergePolicy>,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -91,7 +268,30 @@ pub struct DeleteDeviceStatePayload {
     pub device_id: String,
 }
 
-#[cfg(feature = "mqtt_outbound")]
+/// Desired values of a device, actions are sent for the device to converge.
+#[cfg(feature = "mqtt_inbound")]
+#[derive(Deserialize)]
+pub struct SetDesiredStatePayload {
+    pub device_id: String,
+    pub desired: HashMap<String, Value>,
+}
+
+#[cfg(feature = "mqtt_inbound")]
+#[derive(Deserialize)]
+pub struct ClearDesiredStatePayload {
+    pub device_id: String,
+}
+
+/// Device going online or offline, published for other systems.
+#[cfg(feature = "mqtt_inbound")]
+#[derive(Serialize)]
+pub struct ConnectivityPayload {
+    pub device_id: String,
+    pub device_physical_id: String,
+    pub connectivity: Connectivity,
+    pub timestamp: String,
+}
+
 pub fn payload_to_mqtt_message<S: Serialize>(
     payload: S,
     action_type: MqttActionType,
@@ -105,15 +305,30 @@ pub fn payload_to_mqtt_message<S: Serialize>(
 #[derive(Serialize, Deserialize)]
 pub struct MqttEventEmittable {
     format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    binary_layout: Option<BinaryLayout>,
     payload: HashMap<String, EventDataType>,
+    #[serde(default)]
+    constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    unknown_keys: UnknownKeyPolicy,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    timestamp_field: Option<TimestampField>,
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    computed: HashMap<String, Expression>,
 }
 
 impl TryFrom<MqttEventEmittable> for EventEmittable {
     type Error = String;
 
     fn try_from(value: MqttEventEmittable) -> Result<Self, Self::Error> {
-        let format = EventFormat::try_from(value.format.as_str())?;
-        Ok(EventEmittable::new(format, value.payload))
+        let format = EventFormat::from_parts(&value.format, value.binary_layout)?;
+        let mut emittable = EventEmittable::new(format, value.payload);
+        emittable.set_constraints(value.constraints);
+        emittable.set_unknown_keys(value.unknown_keys);
+        emittable.set_timestamp_field(value.timestamp_field);
+        emittable.set_computed(value.computed);
+        Ok(emittable)
     }
 }
 
@@ -121,7 +336,12 @@ impl From<&EventEmittable> for MqttEventEmittable {
     fn from(value: &EventEmittable) -> Self {
         MqttEventEmittable {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value.payload().clone(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
+            timestamp_field: value.timestamp_field().cloned(),
+            computed: value.computed().clone(),
         }
     }
 }
@@ -129,15 +349,24 @@ impl From<&EventEmittable> for MqttEventEmittable {
 #[derive(Serialize, Deserialize)]
 pub struct MqttActionEmittable {
     format: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    binary_layout: Option<BinaryLayout>,
     payload: HashMap<String, ActionDataType>,
+    #[serde(default)]
+    constraints: HashMap<String, FieldConstraints>,
+    #[serde(default)]
+    unknown_keys: UnknownKeyPolicy,
 }
 
 impl TryFrom<MqttActionEmittable> for ActionEmittable {
     type Error = String;
 
     fn try_from(value: MqttActionEmittable) -> Result<Self, Self::Error> {
-        let format = ActionFormat::try_from(value.format.as_str())?;
-        Ok(ActionEmittable::new(format, value.payload))
+        let format = ActionFormat::from_parts(&value.format, value.binary_layout)?;
+        let mut emittable = ActionEmittable::new(format, value.payload);
+        emittable.set_constraints(value.constraints);
+        emittable.set_unknown_keys(value.unknown_keys);
+        Ok(emittable)
     }
 }
 
@@ -145,7 +374,10 @@ impl From<&ActionEmittable> for MqttActionEmittable {
     fn from(value: &ActionEmittable) -> Self {
         MqttActionEmittable {
             format: value.format().to_string(),
+            binary_layout: value.format().binary_layout().cloned(),
             payload: value.payload().clone(),
+            constraints: value.constraints().clone(),
+            unknown_keys: *value.unknown_keys(),
         }
     }
 }
@@ -2,10 +2,10 @@ use rumqttc::AsyncClient;
 
 use crate::{
     application::ports::outbound::action_repository::{
-        ActionRepositoryError, CreateActionRepository,
+        ActionRepositoryError, CreateActionRepository, UpdateActionRepository,
     },
     domain::action::{action::Action, action_format::ActionFormat},
-    infrastructure::mqtt::mqtt_messages::{self, MqttActionType},
+    infrastructure::{mqtt::mqtt_messages::{self, MqttActionType}, utils},
 };
 
 #[derive(Debug)]
@@ -30,10 +30,18 @@ impl CreateActionRepository for MqttActionRepository {
         action_format: &ActionFormat,
     ) -> Result<(), ActionRepositoryError> {
         let payload = mqtt_messages::CreateActionPayload {
+            action_id: Some(action.id.to_string()),
             device_id: action.device_id.to_string(),
             device_action_name: action.action_name.to_string(),
             timestamp: action.timestamp.to_rfc3339(),
-            action_data: ActionFormat::encode_event(action_format, action.payload)?,
+            action_format: Some(action_format.to_string()),
+            action_binary_layout: action_format.binary_layout().cloned(),
+            action_data: utils::payload_to_text(
+                ActionFormat::encode_event(action_format, action.payload)?,
+                action_format.is_binary(),
+            )
+            .map_err(ActionRepositoryError::RepositoryError)?,
+            expires_at: action.expires_at.map(|expires_at| expires_at.to_rfc3339()),
         };
         let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create)
         {
@@ -60,3 +68,33 @@ impl CreateActionRepository for MqttActionRepository {
         Ok(())
     }
 }
+
+impl UpdateActionRepository for MqttActionRepository {
+    async fn update_action_status(&self, action: &Action) -> Result<(), ActionRepositoryError> {
+        let timestamp = action
+            .transitions
+            .last()
+            .map(|transition| transition.timestamp)
+            .unwrap_or(action.timestamp);
+        let payload = mqtt_messages::UpdateActionStatusPayload {
+            action_id: action.id.to_string(),
+            device_id: action.device_id.to_string(),
+            status: action.status,
+            timestamp: timestamp.to_rfc3339(),
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update)
+            .map_err(|e| ActionRepositoryError::RepositoryError(format!("Invalid data in action: {}", e)))?;
+        self.mqtt_client
+            .publish(
+                &self.action_topic,
+                rumqttc::QoS::AtLeastOnce,
+                false,
+                message,
+            )
+            .await
+            .map_err(|_| {
+                ActionRepositoryError::RepositoryError("Failed to publish action status".to_string())
+            })?;
+        Ok(())
+    }
+}
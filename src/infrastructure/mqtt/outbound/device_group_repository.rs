@@ -0,0 +1,72 @@
+use rumqttc::AsyncClient;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_group_repository::{
+        CreateDeviceGroupRepository, DeleteDeviceGroupRepository, DeviceGroupRepositoryError,
+        UpdateDeviceGroupRepository,
+    },
+    domain::device_group::DeviceGroup,
+    infrastructure::mqtt::mqtt_messages::{self, MqttActionType},
+};
+
+#[derive(Debug)]
+pub struct MqttDeviceGroupRepository {
+    mqtt_client: AsyncClient,
+    device_group_topic: String,
+}
+
+impl MqttDeviceGroupRepository {
+    pub fn new(mqtt_client: AsyncClient, device_group_topic: &str) -> Self {
+        MqttDeviceGroupRepository {
+            mqtt_client,
+            device_group_topic: device_group_topic.to_string(),
+        }
+    }
+
+    async fn publish(&self, message: Vec<u8>) -> Result<(), DeviceGroupRepositoryError> {
+        self.mqtt_client
+            .publish(&self.device_group_topic, rumqttc::QoS::AtLeastOnce, true, message)
+            .await
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))
+    }
+}
+
+impl CreateDeviceGroupRepository for MqttDeviceGroupRepository {
+    async fn create(&self, group: &DeviceGroup) -> Result<(), DeviceGroupRepositoryError> {
+        let payload = mqtt_messages::CreateDeviceGroupPayload {
+            id: group.id().to_string(),
+            user_id: group.user_id().to_string(),
+            name: group.name().to_string(),
+            parent_id: group.parent_id().map(|id| id.to_string()),
+            device_ids: group.device_ids().iter().map(|id| id.to_string()).collect(),
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create)
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl UpdateDeviceGroupRepository for MqttDeviceGroupRepository {
+    async fn update(&self, group: &DeviceGroup) -> Result<(), DeviceGroupRepositoryError> {
+        let payload = mqtt_messages::UpdateDeviceGroupPayload {
+            id: group.id().to_string(),
+            user_id: group.user_id().to_string(),
+            name: group.name().to_string(),
+            parent_id: group.parent_id().map(|id| id.to_string()),
+            device_ids: group.device_ids().iter().map(|id| id.to_string()).collect(),
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update)
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl DeleteDeviceGroupRepository for MqttDeviceGroupRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceGroupRepositoryError> {
+        let payload = mqtt_messages::DeleteDeviceGroupPayload { id: id.to_string() };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Delete)
+            .map_err(|e| DeviceGroupRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
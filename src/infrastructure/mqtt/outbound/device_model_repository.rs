@@ -0,0 +1,96 @@
+use std::collections::HashMap;
+
+use rumqttc::AsyncClient;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::device_model_repository::{
+        CreateDeviceModelRepository, DeleteDeviceModelRepository, DeviceModelRepositoryError,
+        UpdateDeviceModelRepository,
+    },
+    domain::device_model::DeviceModel,
+    infrastructure::mqtt::mqtt_messages::{
+        self, MqttActionEmittable, MqttActionType, MqttEventEmittable,
+    },
+};
+
+#[derive(Debug)]
+pub struct MqttDeviceModelRepository {
+    mqtt_client: AsyncClient,
+    device_model_topic: String,
+}
+
+impl MqttDeviceModelRepository {
+    pub fn new(mqtt_client: AsyncClient, device_model_topic: &str) -> Self {
+        MqttDeviceModelRepository {
+            mqtt_client,
+            device_model_topic: device_model_topic.to_string(),
+        }
+    }
+
+    fn serialize_emittables(model: &DeviceModel) -> Result<(String, String), DeviceModelRepositoryError> {
+        let events_serialized: HashMap<String, MqttEventEmittable> = model
+            .events()
+            .iter()
+            .map(|(k, v)| (k.to_string(), MqttEventEmittable::from(v)))
+            .collect();
+        let events = serde_json::to_string(&events_serialized)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        let actions_serialized: HashMap<String, MqttActionEmittable> = model
+            .actions()
+            .iter()
+            .map(|(k, v)| (k.to_string(), MqttActionEmittable::from(v)))
+            .collect();
+        let actions = serde_json::to_string(&actions_serialized)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        Ok((events, actions))
+    }
+
+    async fn publish(&self, message: Vec<u8>) -> Result<(), DeviceModelRepositoryError> {
+        self.mqtt_client
+            .publish(&self.device_model_topic, rumqttc::QoS::AtLeastOnce, true, message)
+            .await
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))
+    }
+}
+
+impl CreateDeviceModelRepository for MqttDeviceModelRepository {
+    async fn create(&self, model: &DeviceModel) -> Result<(), DeviceModelRepositoryError> {
+        let (events, actions) = Self::serialize_emittables(model)?;
+        let payload = mqtt_messages::CreateDeviceModelPayload {
+            id: model.id().to_string(),
+            user_id: model.user_id().to_string(),
+            name: model.name().to_string(),
+            events,
+            actions,
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl UpdateDeviceModelRepository for MqttDeviceModelRepository {
+    async fn update(&self, model: &DeviceModel) -> Result<(), DeviceModelRepositoryError> {
+        let (events, actions) = Self::serialize_emittables(model)?;
+        let payload = mqtt_messages::UpdateDeviceModelPayload {
+            id: model.id().to_string(),
+            user_id: model.user_id().to_string(),
+            name: model.name().to_string(),
+            events,
+            actions,
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl DeleteDeviceModelRepository for MqttDeviceModelRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), DeviceModelRepositoryError> {
+        let payload = mqtt_messages::DeleteDeviceModelPayload { id: id.to_string() };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Delete)
+            .map_err(|e| DeviceModelRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
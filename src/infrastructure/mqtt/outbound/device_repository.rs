@@ -8,9 +8,10 @@ use crate::{
         CreateDeviceRepository, DeleteDeviceRepository, DeviceRepositoryError,
         UpdateDeviceRepository,
     },
-    domain::device::Device,
-    infrastructure::mqtt::mqtt_messages::{
-        self, MqttActionEmittable, MqttActionType, MqttEventEmittable,
+    domain::{connectivity::Connectivity, device::Device},
+    infrastructure::{
+        mqtt::mqtt_messages::{self, MqttActionEmittable, MqttActionType, MqttEventEmittable},
+        utils,
     },
 };
 
@@ -56,6 +57,16 @@ impl CreateDeviceRepository for MqttDeviceRepository {
             name: device.name().to_string(),
             events,
             actions,
+            descriptor_set: device
+                .descriptor_set()
+                .map(|d| utils::payload_to_text(d.clone(), true))
+                .transpose()
+                .map_err(DeviceRepositoryError::InternalError)?,
+            model_id: device.model_id().map(|id| id.to_string()),
+            labels: device.labels().clone(),
+            tags: device.tags().clone(),
+            state_merge_policy: device.state_merge_policy(),
+            heartbeat_interval: device.heartbeat_interval(),
         };
 
         let message =
@@ -104,6 +115,16 @@ impl UpdateDeviceRepository for MqttDeviceRepository {
             name: device.name().to_string(),
             events,
             actions,
+            descriptor_set: device
+                .descriptor_set()
+                .map(|d| utils::payload_to_text(d.clone(), true))
+                .transpose()
+                .map_err(DeviceRepositoryError::InternalError)?,
+            model_id: device.model_id().map(|id| id.to_string()),
+            labels: device.labels().clone(),
+            tags: device.tags().clone(),
+            state_merge_policy: device.state_merge_policy(),
+            heartbeat_interval: device.heartbeat_interval(),
         };
 
         let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update)
@@ -120,6 +141,11 @@ impl UpdateDeviceRepository for MqttDeviceRepository {
 
         return Ok(());
     }
+
+    async fn update_connectivity(&self, _id: Uuid, _connectivity: Connectivity) -> Result<(), DeviceRepositoryError> {
+        // the MQTT inbound receiving the events tracks the connectivity of the devices
+        Ok(())
+    }
 }
 
 impl DeleteDeviceRepository for MqttDeviceRepository {
@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use rumqttc::AsyncClient;
 use uuid::Uuid;
 
@@ -6,7 +8,7 @@ use crate::{
         CreateDeviceStateRepository, DeleteDeviceStateRepository, DeviceStateRepositoryError,
         UpdateDeviceStateRepository,
     },
-    domain::state::DeviceState,
+    domain::state::{DeviceState, StateMergePolicy},
     infrastructure::mqtt::mqtt_messages::{self, MqttActionType},
 };
 
@@ -30,7 +32,11 @@ impl CreateDeviceStateRepository for MqttDeviceStateRepository {
         let payload = mqtt_messages::CreateDeviceStatePayload {
             device_id: device_state.device_id.to_string(),
             last_update: device_state.last_update.to_rfc3339(),
-            values: device_state.values.clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            values: device_state.raw_values().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            sources: device_state.sources(),
+            desired: device_state.desired.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect(),
+            desired_update: device_state.desired_update.map(|d| d.to_rfc3339()),
+            merge_policy: None,
         };
 
         let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create){
@@ -73,11 +79,16 @@ impl DeleteDeviceStateRepository for MqttDeviceStateRepository {
 }
 
 impl UpdateDeviceStateRepository for MqttDeviceStateRepository {
-    async fn update(&self, device_state: &DeviceState) -> Result<(), DeviceStateRepositoryError> {
+    async fn set_desired(&self, device_state: &DeviceState) -> Result<DeviceState, DeviceStateRepositoryError> {
+        // reported values are not published, the server keeps its own
         let payload = mqtt_messages::UpdateDeviceStatePayload {
             device_id: device_state.device_id.to_string(),
             last_update: device_state.last_update.to_rfc3339(),
-            values: device_state.values.clone().into_iter().map(|(k, v)| (k, v.into())).collect(),
+            values: HashMap::new(),
+            sources: HashMap::new(),
+            desired: device_state.desired.iter().map(|(k, v)| (k.clone(), v.clone().into())).collect(),
+            desired_update: device_state.desired_update.map(|d| d.to_rfc3339()),
+            merge_policy: None,
         };
 
         let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update){
@@ -94,6 +105,36 @@ impl UpdateDeviceStateRepository for MqttDeviceStateRepository {
             )
             .await
             .map_err(|e| DeviceStateRepositoryError::InternalError(e.to_string()))?;
-        Ok(())
+        Ok(device_state.clone())
+    }
+
+    async fn merge_values(&self, device_state: &DeviceState, keys: &[String], policy: StateMergePolicy) -> Result<DeviceState, DeviceStateRepositoryError> {
+        // only the merged keys are published, the server merges them into its own state
+        let values = device_state.values_of(keys);
+        let payload = mqtt_messages::UpdateDeviceStatePayload {
+            device_id: device_state.device_id.to_string(),
+            last_update: device_state.last_update.to_rfc3339(),
+            values: values.iter().map(|(k, v)| (k.clone(), v.value.clone().into())).collect(),
+            sources: values.into_iter().map(|(k, v)| (k, v.source)).collect(),
+            desired: HashMap::new(),
+            desired_update: None,
+            merge_policy: Some(policy),
+        };
+
+        let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update){
+            Ok(r) => r,
+            Err(e) => return Err(DeviceStateRepositoryError::InternalError(e.to_string())),
+        };
+
+        self.mqtt_client
+            .publish(
+                &self.device_state_topic,
+                rumqttc::QoS::AtLeastOnce,
+                false,
+                message,
+            )
+            .await
+            .map_err(|e| DeviceStateRepositoryError::InternalError(e.to_string()))?;
+        Ok(device_state.clone())
     }
 }
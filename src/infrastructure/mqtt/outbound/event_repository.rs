@@ -1,7 +1,7 @@
 use rumqttc::AsyncClient;
 
 use crate::{
-    application::ports::outbound::event_repository::{CreateEventRepository, EventRepositoryError}, domain::event::{event::Event, event_format::EventFormat}, infrastructure::mqtt::mqtt_messages::{self, MqttActionType}
+    application::ports::outbound::event_repository::{CreateEventRepository, EventRepositoryError}, domain::event::{event::Event, event_format::EventFormat}, infrastructure::{mqtt::mqtt_messages::{self, MqttActionType}, utils}
 };
 
 #[derive(Debug)]
@@ -29,7 +29,11 @@ impl CreateEventRepository for MqttEventRepository {
             device_physical_id: event.device_physical_id.to_string(),
             device_event_name: event.event_name.to_string(),
             timestamp: event.timestamp.to_rfc3339(),
-            event_data: EventFormat::encode_event(event_format, event.payload)?,
+            event_data: utils::payload_to_text(
+                EventFormat::encode_event(event_format, event.payload)?,
+                event_format.is_binary(),
+            )
+            .map_err(EventRepositoryError::RepositoryError)?,
         };
         let message = match mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create)
         {
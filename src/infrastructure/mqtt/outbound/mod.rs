@@ -1,5 +1,8 @@
 
 pub mod device_repository;
+pub mod device_group_repository;
+pub mod device_model_repository;
 pub mod device_state_repository;
 pub mod event_repository;
-pub mod action_repository;
\ No newline at end of file
+pub mod action_repository;
+pub mod rule_repository;
\ No newline at end of file
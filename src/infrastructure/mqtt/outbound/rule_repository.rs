@@ -0,0 +1,81 @@
+use rumqttc::AsyncClient;
+use uuid::Uuid;
+
+use crate::{
+    application::ports::outbound::rule_repository::{
+        CreateRuleRepository, DeleteRuleRepository, RuleRepositoryError, UpdateRuleRepository,
+    },
+    domain::rule::Rule,
+    infrastructure::mqtt::mqtt_messages::{self, MqttActionType},
+};
+
+#[derive(Debug)]
+pub struct MqttRuleRepository {
+    mqtt_client: AsyncClient,
+    rule_topic: String,
+}
+
+impl MqttRuleRepository {
+    pub fn new(mqtt_client: AsyncClient, rule_topic: &str) -> Self {
+        MqttRuleRepository {
+            mqtt_client,
+            rule_topic: rule_topic.to_string(),
+        }
+    }
+
+    async fn publish(&self, message: Vec<u8>) -> Result<(), RuleRepositoryError> {
+        self.mqtt_client
+            .publish(&self.rule_topic, rumqttc::QoS::AtLeastOnce, true, message)
+            .await
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))
+    }
+}
+
+impl CreateRuleRepository for MqttRuleRepository {
+    async fn create(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let payload = mqtt_messages::CreateRulePayload {
+            id: rule.id.to_string(),
+            user_id: rule.user_id.to_string(),
+            name: rule.name.clone(),
+            device_id: rule.device_id.to_string(),
+            event_name: rule.event_name.clone(),
+            conditions: rule.conditions.clone(),
+            action: rule.action.clone(),
+            debounce: rule.debounce,
+            cooldown: rule.cooldown,
+            enabled: rule.enabled,
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Create)
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl UpdateRuleRepository for MqttRuleRepository {
+    async fn update(&self, rule: &Rule) -> Result<(), RuleRepositoryError> {
+        let payload = mqtt_messages::UpdateRulePayload {
+            id: rule.id.to_string(),
+            user_id: rule.user_id.to_string(),
+            name: rule.name.clone(),
+            device_id: rule.device_id.to_string(),
+            event_name: rule.event_name.clone(),
+            conditions: rule.conditions.clone(),
+            action: rule.action.clone(),
+            debounce: rule.debounce,
+            cooldown: rule.cooldown,
+            enabled: rule.enabled,
+        };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Update)
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
+
+impl DeleteRuleRepository for MqttRuleRepository {
+    async fn delete_by_id(&self, id: Uuid) -> Result<(), RuleRepositoryError> {
+        let payload = mqtt_messages::DeleteRulePayload { id: id.to_string() };
+        let message = mqtt_messages::payload_to_mqtt_message(payload, MqttActionType::Delete)
+            .map_err(|e| RuleRepositoryError::InternalError(e.to_string()))?;
+        self.publish(message).await
+    }
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use tracing::warn;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::DeviceService, rule_service::RuleService},
+    },
+    domain::{
+        action::{action::Action, action_data_value::ActionDataValue},
+        device::Device,
+        event::event::Event,
+        rule::Rule,
+        state::DeviceState,
+    },
+};
+
+// with an MQTT outbound, events are published and the MQTT inbound receiving them evaluates the rules
+const EVALUATES_RULES: bool = !cfg!(feature = "mqtt_outbound");
+
+/// Evaluates the rules of the device on one of its events and the state it led to, sends the
+/// action of every rule firing. Returns the actions sent, a rule whose action can't be sent
+/// does not prevent the others from being sent.
+pub async fn run_rules<AO: AppOutbound>(
+    outbound: &AO,
+    device: &Device,
+    event: &Event,
+    state: Option<&DeviceState>,
+) -> Result<Vec<Action>, String> {
+    if !EVALUATES_RULES {
+        return Ok(Vec::new());
+    }
+    let fired = outbound
+        .get_rule_service()
+        .evaluate_rules(*device.id(), event, state, &Utc::now())
+        .await
+        .map_err(|e| e.to_string())?;
+    let mut actions = Vec::with_capacity(fired.len());
+    for rule in fired {
+        match send_rule_action(outbound, &rule).await {
+            Ok(action) => actions.push(action),
+            Err(e) => warn!(
+                result = "warn",
+                details = format!("Failed to send the action of rule {}: {}", rule.id, e)
+            ),
+        }
+    }
+    Ok(actions)
+}
+
+async fn send_rule_action<AO: AppOutbound>(outbound: &AO, rule: &Rule) -> Result<Action, String> {
+    let device = outbound
+        .get_device_service()
+        .get_device(rule.action.device_id)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Device {} not found", rule.action.device_id))?;
+    let payload = rule
+        .action
+        .payload
+        .iter()
+        .map(|(key, value)| {
+            ActionDataValue::try_from(value.clone())
+                .map(|value| (key.clone(), value))
+                .map_err(|_| format!("Invalid value for key {}", key))
+        })
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    outbound
+        .get_action_service()
+        .send_device_action(&device, &rule.action.action_name, payload, &Utc::now())
+        .await
+        .map_err(|e| e.to_string())
+}
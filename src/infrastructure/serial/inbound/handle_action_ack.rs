@@ -0,0 +1,65 @@
+use chrono::{DateTime, Utc};
+use serde::Deserialize;
+use tracing::{trace, warn};
+use uuid::Uuid;
+
+use crate::{
+    application::ports::{
+        app::AppOutbound,
+        inbound::{action_service::ActionService, device_service::DeviceService},
+    },
+    domain::action::action_status::ActionStatus,
+};
+
+/// Name devices send instead of an event name to acknowledge an action,
+/// e.g. `device-1;action_ack;{"action_id": "...", "status": "succeeded"}`.
+pub const ACTION_ACK_NAME: &str = "action_ack";
+
+#[derive(Deserialize)]
+struct AcknowledgementPayload {
+    action_id: Uuid,
+    status: ActionStatus,
+    #[serde(default)]
+    timestamp: Option<DateTime<Utc>>,
+}
+
+pub async fn handle_action_ack<AO: AppOutbound + 'static>(
+    app_outbound: AO,
+    device_id: &str,
+    payload: &str,
+) {
+    let payload: AcknowledgementPayload = match serde_json::from_str(payload) {
+        Ok(payload) => payload,
+        Err(e) => {
+            warn!("Invalid acknowledgement for device ID: {}, error: {}", device_id, e);
+            return;
+        }
+    };
+    if !payload.status.is_acknowledgement() {
+        warn!("Device ID: {} cannot report the {} status", device_id, payload.status);
+        return;
+    }
+    let device = match app_outbound.get_device_service().get_device_by_physical_id(device_id).await {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            warn!("Received acknowledgement for unknown device ID: {}", device_id);
+            return;
+        }
+        Err(e) => {
+            warn!("Failed to get device ID: {}, error: {}", device_id, e);
+            return;
+        }
+    };
+    let timestamp = payload.timestamp.unwrap_or_else(Utc::now);
+    match app_outbound
+        .get_action_service()
+        .update_action_status(&device.id().to_string(), payload.action_id, payload.status, &timestamp)
+        .await
+    {
+        Ok(_) => trace!("Action {} of device ID: {} is {}", payload.action_id, device_id, payload.status),
+        Err(e) => warn!(
+            "Failed to acknowledge action {} for device ID: {}, error: {}",
+            payload.action_id, device_id, e
+        ),
+    }
+}
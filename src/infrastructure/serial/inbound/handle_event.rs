@@ -1,11 +1,16 @@
-use std::collections::HashMap;
-
 use chrono::Utc;
 use tracing::{trace, warn};
 
 use crate::{
-    application::ports::{app::AppOutbound, inbound::event_service::EventService},
-    domain::event::{event::Event, event_data_value::EventDataValue, event_format::EventFormat},
+    application::ports::{
+        app::AppOutbound,
+        inbound::{
+            device_service::DeviceService, device_state_service::DeviceStateService,
+            event_service::EventService,
+        },
+    },
+    domain::event::event::Event,
+    infrastructure::{connectivity, rules},
 };
 
 pub async fn handle_event<AO: AppOutbound + 'static>(
@@ -15,37 +20,66 @@ pub async fn handle_event<AO: AppOutbound + 'static>(
     payload: &str,
 ) {
     let timestamp = Utc::now();
-    let event_data: Vec<&str> = payload.split(',').collect();
-
-    if event_data.is_empty() {
+    if payload.is_empty() {
         warn!("Received empty payload for device ID: {}", device_id);
         return;
     }
 
-    let mut values = HashMap::new();
-    for data in event_data {
-        let parts: Vec<&str> = data.split('=').collect();
-        if parts.len() == 2 {
-            let value = match EventDataValue::try_from(parts[1].to_string().as_str()) {
-                Ok(v) => v,
-                Err(e) => {
-                    warn!("Invalid data format in payload: {}, error: {:?}", data, e);
-                    continue;
-                }
-            };
-            values.insert(parts[0].to_string(), value);
-        } else {
-            warn!("Invalid data format in payload: {}", data);
+    let device_service = app_outbound.get_device_service();
+    let device = match device_service.get_device_by_physical_id(device_id).await {
+        Ok(Some(device)) => device,
+        Ok(None) => {
+            warn!("Received event for unknown device ID: {}", device_id);
+            return;
         }
-    }
-
-    let event = Event::new(device_id.to_string(), event_name, &timestamp, values);
+        Err(e) => {
+            warn!("Failed to get device ID: {}, error: {}", device_id, e);
+            return;
+        }
+    };
+    let event = match Event::new_checked(&device, &timestamp, event_name, payload.as_bytes()) {
+        Ok(event) => event,
+        Err(e) => {
+            warn!("Invalid event {} for device ID: {}, error: {}", event_name, device_id, e);
+            return;
+        }
+    };
+    let event_format = device
+        .event(event_name)
+        .expect("Check done before")
+        .format();
     let event_service = app_outbound.get_event_service();
-    match event_service.handle_event(event, &EventFormat::Json).await {
+    match event_service.handle_event(event.clone(), event_format).await {
         Ok(_) => trace!("Event saved successfully for device ID: {}", device_id),
-        Err(e) => warn!(
-            "Failed to save event for device ID: {}, error: {:?}",
-            device_id, e
-        ),
+        Err(e) => {
+            warn!(
+                "Failed to save event for device ID: {}, error: {:?}",
+                device_id, e
+            );
+            return;
+        }
+    }
+    let device_state_service = app_outbound.get_device_state_service();
+    let device_state = match device_state_service
+        .update_device_state(*device.id(), event.state_values(), device.state_merge_policy())
+        .await
+    {
+        Ok(device_state) => {
+            trace!("Device state updated for device ID: {}", device_id);
+            Some(device_state)
+        }
+        Err(e) => {
+            warn!(
+                "Failed to update device state for device ID: {}, error: {}",
+                device_id, e
+            );
+            None
+        }
+    };
+    if let Err(e) = connectivity::record_activity(&app_outbound, &device).await {
+        warn!("Failed to record activity for device ID: {}, error: {}", device_id, e);
+    }
+    if let Err(e) = rules::run_rules(&app_outbound, &device, &event, device_state.as_ref()).await {
+        warn!("Failed to run rules for device ID: {}, error: {}", device_id, e);
     }
 }
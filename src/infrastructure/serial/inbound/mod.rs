@@ -1 +1,2 @@
+pub mod handle_action_ack;
 pub mod handle_event;
\ No newline at end of file
@@ -1,14 +1,17 @@
-use std::{str::FromStr, sync::{Arc, Mutex}};
+use std::{collections::{BTreeSet, HashMap}, str::FromStr, sync::{Arc, Mutex}};
 
 use tracing::trace;
 use uuid::Uuid;
 
-use crate::{application::ports::{app::AppOutbound, inbound::device_service::{DeviceService}}, domain::device::Device, infrastructure::{ui::inbound::{egui_app::try_lock_until_success, LoadingStatus}, utils::log_device_service_error}};
+use crate::{application::ports::{app::AppOutbound, inbound::{device_group_service::DeviceGroupService, device_service::DeviceService}}, domain::{device::Device, device_filter::DeviceFilter, device_group::DeviceGroup}, infrastructure::{ui::inbound::{egui_app::try_lock_until_success, LoadingStatus}, utils::{log_device_group_service_error, log_device_service_error}}};
 
 #[derive(Debug)]
 pub struct DeviceManager{
     user_id: Uuid,
     device_list: Arc<Mutex<LoadingStatus<Vec<Device>>>>,
+    group_list: Arc<Mutex<LoadingStatus<Vec<DeviceGroup>>>>,
+    /// Space separated `key=value` labels and tags the listed devices must have.
+    filter_input: String,
 }
 
 impl DeviceManager {
@@ -17,6 +20,8 @@ impl DeviceManager {
         DeviceManager {
             user_id,
             device_list: Arc::new(Mutex::new(LoadingStatus::NotStarted)),
+            group_list: Arc::new(Mutex::new(LoadingStatus::NotStarted)),
+            filter_input: String::new(),
         }
     }
 
@@ -24,6 +29,7 @@ impl DeviceManager {
     pub fn load_devices<'a>(&mut self, app_outbound: impl AppOutbound + 'static) {
         let device_list = self.device_list.clone();
         let user_id = self.user_id;
+        let filter = self.filter();
         // Lock the device list to set the status to InProgress
          let mut device_list_locked = try_lock_until_success(&device_list); 
         if let LoadingStatus::Success(old_val) = device_list_locked.clone() {
@@ -32,7 +38,12 @@ impl DeviceManager {
         drop(device_list_locked); // Release the lock before spawning the task
         tokio::spawn(async move {
             let device_service = app_outbound.get_device_service();
-            match device_service.get_devices_by_user_id(user_id).await {
+            let devices = if filter.is_empty() {
+                device_service.get_devices_by_user_id(user_id).await
+            } else {
+                device_service.get_devices_by_filter(user_id, &filter).await
+            };
+            match devices {
                 Ok(devices) => {
                     trace!(result = "success");
                     let mut device_list_locked = try_lock_until_success(&device_list);  
@@ -48,7 +59,57 @@ impl DeviceManager {
         });
     }
 
+    #[tracing::instrument]
+    pub fn load_groups(&mut self, app_outbound: impl AppOutbound + 'static) {
+        let group_list = self.group_list.clone();
+        let user_id = self.user_id;
+        let mut group_list_locked = try_lock_until_success(&group_list);
+        if let LoadingStatus::Success(old_val) = group_list_locked.clone() {
+            *group_list_locked = LoadingStatus::InProgress(Some(old_val))
+        }
+        drop(group_list_locked);
+        tokio::spawn(async move {
+            let group_service = app_outbound.get_device_group_service();
+            match group_service.get_device_groups_by_user_id(user_id).await {
+                Ok(groups) => {
+                    trace!(result = "success");
+                    let mut group_list_locked = try_lock_until_success(&group_list);
+                    *group_list_locked = LoadingStatus::Success(groups);
+                }
+                Err(e) => {
+                    log_device_group_service_error(&e);
+                    let mut group_list_locked = try_lock_until_success(&group_list);
+                    *group_list_locked = LoadingStatus::Failed("Failed to load device groups".to_string());
+                }
+            }
+        });
+    }
+
+    pub fn filter_input_mut(&mut self) -> &mut String {
+        &mut self.filter_input
+    }
+
+    fn filter(&self) -> DeviceFilter {
+        let mut labels = HashMap::new();
+        let mut tags = BTreeSet::new();
+        for token in self.filter_input.split_whitespace() {
+            match token.split_once('=') {
+                Some((key, value)) => {
+                    labels.insert(key.to_string(), value.to_string());
+                }
+                None => {
+                    tags.insert(token.trim_start_matches('#').to_string());
+                }
+            }
+        }
+        DeviceFilter::new(labels, tags)
+    }
+
     pub fn get_device_list(&self) -> Arc<Mutex<LoadingStatus<Vec<Device>>>> {
         self.device_list.clone()
     }
+
+    pub fn get_group_list(&self) -> Arc<Mutex<LoadingStatus<Vec<DeviceGroup>>>> {
+        self.group_list.clone()
+    }
 }
\ No newline at end of file
@@ -10,7 +10,7 @@ use crate::{
     application::ports::{
         app::AppOutbound,
         inbound::{device_service::DeviceService, device_state_service::DeviceStateService},
-    }, domain::event::event_data_value::EventDataValue, infrastructure::ui::inbound::{egui_app::try_lock_until_success, LoadingStatus}
+    }, domain::{connectivity::Connectivity, event::event_data_value::EventDataValue, state::StateValue}, infrastructure::ui::inbound::{egui_app::try_lock_until_success, LoadingStatus}
 };
 
 pub struct DeviceStateManager {
@@ -80,12 +80,16 @@ impl DeviceStateManager {
                         device_name: device.name().to_string(),
                         values: Some(state.values.clone()),
                         last_update: Some(state.last_update),
+                        delta: state.delta(),
+                        connectivity: device.connectivity(),
                     },
                     None => DisplayableDeviceState {
                         device_id: *device.id(),
                         device_name: device.name().to_string(),
                         values: None,
                         last_update: None,
+                        delta: HashMap::new(),
+                        connectivity: device.connectivity(),
                     }, // Skip devices with no state
                 };
                 device_states_list.insert(*device.id(), state_to_insert);
@@ -101,6 +105,9 @@ impl DeviceStateManager {
 pub struct DisplayableDeviceState {
     pub device_id: Uuid,
     pub device_name: String,
-    pub values: Option<HashMap<String, EventDataValue>>,
+    pub values: Option<HashMap<String, StateValue>>,
     pub last_update: Option<DateTime<Utc>>,
+    /// Desired values the device does not report yet.
+    pub delta: HashMap<String, EventDataValue>,
+    pub connectivity: Connectivity,
 }
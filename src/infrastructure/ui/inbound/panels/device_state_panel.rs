@@ -5,7 +5,7 @@ use uuid::Uuid;
 
 use crate::{
     application::ports::app::AppOutbound, domain::event::event_data_value::EventDataValue, infrastructure::ui::inbound::{
-        egui_app::try_lock_until_success, managers::device_state_manager::{DeviceStateManager, DisplayableDeviceState}, widgets::gauge::gauge, LoadingStatus
+        egui_app::try_lock_until_success, managers::device_state_manager::{DeviceStateManager, DisplayableDeviceState}, widgets::{connectivity_badge::connectivity_badge, gauge::gauge}, LoadingStatus
     }
 };
 
@@ -66,6 +66,7 @@ fn display_cards(ui: &mut egui::Ui, device_states: HashMap<Uuid, DisplayableDevi
                             .show(ui, |ui| {
                                 ui.vertical(|ui| {
                                     ui.heading(device_state.device_name);
+                                    connectivity_badge(ui, device_state.connectivity);
                                     ui.label(
                                         RichText::new(format!("ID : {}", device_state.device_id))
                                             .italics()
@@ -77,10 +78,37 @@ fn display_cards(ui: &mut egui::Ui, device_states: HashMap<Uuid, DisplayableDevi
                                         && device_state.values.is_some()
                                     {
                                         let device_state_values = device_state.values.unwrap();
-                                        for (key, value) in device_state_values {
+                                        for (key, state_value) in device_state_values {
                                             ui.label(format!("{}:", key.to_uppercase()));
-                                            if let EventDataValue::Number(num) = value {
-                                                gauge(ui, num, 0, 50, Vec2::new(250.0, 50.0));
+                                            let source = &state_value.source;
+                                            let origin = match &source.event_name {
+                                                Some(event_name) => format!(" ({})", event_name),
+                                                None => String::new(),
+                                            };
+                                            ui.label(
+                                                RichText::new(format!(
+                                                    "🕘 {}{}",
+                                                    source.timestamp.format("%d/%m/%Y %H:%M:%S"),
+                                                    origin
+                                                ))
+                                                .italics()
+                                                .size(9.),
+                                            );
+                                            if let Some(desired) = device_state.delta.get(&key) {
+                                                ui.label(
+                                                    RichText::new(format!("🎯 Desired: {}", serde_json::Value::from(desired.clone())))
+                                                        .italics()
+                                                        .size(9.),
+                                                );
+                                            }
+                                            match state_value.value {
+                                                EventDataValue::Integer(num) => {
+                                                    gauge(ui, num as f64, 0., 50., Vec2::new(250.0, 50.0));
+                                                }
+                                                EventDataValue::Float(num) => {
+                                                    gauge(ui, num, 0., 50., Vec2::new(250.0, 50.0));
+                                                }
+                                                _ => {}
                                             }
                                         }
                                         ui.label(format!(
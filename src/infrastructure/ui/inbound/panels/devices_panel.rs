@@ -1,8 +1,13 @@
+use std::collections::HashMap;
+
+use uuid::Uuid;
+
 use crate::{
     application::ports::app::AppOutbound,
-    domain::device::Device,
+    domain::{device::Device, device_group::DeviceGroup},
     infrastructure::ui::inbound::{
         LoadingStatus, egui_app::try_lock_until_success, managers::device_manager::DeviceManager,
+        widgets::connectivity_badge::connectivity_badge,
     },
 };
 
@@ -13,14 +18,29 @@ pub fn display_device_panel<AO: AppOutbound + 'static>(
     must_refresh: bool,
 ) {
     ui.label("List of devices :");
+    let mut apply_filter = false;
+    ui.horizontal(|ui| {
+        ui.label("Filter :");
+        let response = ui.add(
+            egui::TextEdit::singleline(device_manager.filter_input_mut())
+                .hint_text("site=plant-2 #outdoor"),
+        );
+        apply_filter = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+        if ui.button("Apply").clicked() {
+            apply_filter = true;
+        }
+    });
     let device_list = device_manager.get_device_list();
     let mut should_load = false;
     let device_list_lock = try_lock_until_success(&device_list);
+    let mut loaded_devices = Vec::new();
     match device_list_lock.clone() {
         LoadingStatus::Success(devices) => {
+            loaded_devices = devices.clone();
             display_cards(ui, devices);
         }
         LoadingStatus::InProgress(Some(devices)) => {
+            loaded_devices = devices.clone();
             display_cards(ui, devices);
         }
         LoadingStatus::InProgress(None) => {
@@ -34,11 +54,76 @@ pub fn display_device_panel<AO: AppOutbound + 'static>(
         }
     };
     drop(device_list_lock);
-    if must_refresh || should_load {
+
+    ui.separator();
+    ui.label("Groups :");
+    let group_list = device_manager.get_group_list();
+    let mut should_load_groups = false;
+    let group_list_lock = try_lock_until_success(&group_list);
+    match group_list_lock.clone() {
+        LoadingStatus::Success(groups) | LoadingStatus::InProgress(Some(groups)) => {
+            display_group_tree(ui, &groups, &loaded_devices);
+        }
+        LoadingStatus::InProgress(None) => {
+            ui.label("Loading groups...");
+        }
+        LoadingStatus::NotStarted => {
+            should_load_groups = true;
+        }
+        LoadingStatus::Failed(msg) => {
+            ui.label(format!("Failed to load groups: {}", msg));
+        }
+    };
+    drop(group_list_lock);
+    if must_refresh || should_load_groups {
+        device_manager.load_groups(outbound.clone());
+    }
+    if must_refresh || should_load || apply_filter {
         device_manager.load_devices(outbound);
     }
 }
 
+fn display_group_tree(ui: &mut egui::Ui, groups: &[DeviceGroup], devices: &[Device]) {
+    if groups.is_empty() {
+        ui.label("No groups");
+        return;
+    }
+    let mut children: HashMap<Option<Uuid>, Vec<&DeviceGroup>> = HashMap::new();
+    for group in groups {
+        // a parent outside of the list is shown as a root
+        let parent = group
+            .parent_id()
+            .filter(|parent_id| groups.iter().any(|g| g.id() == *parent_id))
+            .cloned();
+        children.entry(parent).or_default().push(group);
+    }
+    let device_names: HashMap<&Uuid, &str> = devices.iter().map(|d| (d.id(), d.name())).collect();
+    for root in children.get(&None).into_iter().flatten() {
+        display_group(ui, root, &children, &device_names);
+    }
+}
+
+fn display_group(
+    ui: &mut egui::Ui,
+    group: &DeviceGroup,
+    children: &HashMap<Option<Uuid>, Vec<&DeviceGroup>>,
+    device_names: &HashMap<&Uuid, &str>,
+) {
+    egui::CollapsingHeader::new(format!("📁 {}", group.name()))
+        .id_salt(group.id())
+        .show(ui, |ui| {
+            for child in children.get(&Some(*group.id())).into_iter().flatten() {
+                display_group(ui, child, children, device_names);
+            }
+            for device_id in group.device_ids() {
+                match device_names.get(device_id) {
+                    Some(name) => ui.label(format!("📟 {name}")),
+                    None => ui.label(format!("📟 {device_id}")),
+                };
+            }
+        });
+}
+
 fn display_cards(ui: &mut egui::Ui, devices: Vec<Device>) {
     egui::ScrollArea::horizontal().show(ui, |ui| {
         ui.horizontal_top(|ui| {
@@ -62,8 +147,27 @@ fn display_cards(ui: &mut egui::Ui, devices: Vec<Device>) {
                                 ui.vertical(|ui| {
                                     ui.heading(device.name());
                                     ui.label(format!("🔢 ID : {}", device.id()));
+                                    if let Some(interval) = device.heartbeat_interval() {
+                                        ui.horizontal(|ui| {
+                                            connectivity_badge(ui, device.connectivity());
+                                            ui.label(format!("(💓 {interval}s)"));
+                                        });
+                                    }
+                                    let mut labels: Vec<String> = device
+                                        .labels()
+                                        .iter()
+                                        .map(|(k, v)| format!("{k}={v}"))
+                                        .collect();
+                                    labels.sort();
+                                    if !labels.is_empty() {
+                                        ui.label(format!("🏷 Labels : {}", labels.join(", ")));
+                                    }
+                                    if !device.tags().is_empty() {
+                                        let tags: Vec<&str> = device.tags().iter().map(String::as_str).collect();
+                                        ui.label(format!("🔖 Tags : {}", tags.join(", ")));
+                                    }
                                     ui.separator();
-                                    for (key, value) in device.events() {
+                                    for (key, value) in device.effective_events() {
                                         ui.label(format!("Event: {key}"));
                                         ui.label(format!("- 📦 Données : {:?}", value.payload()));
                                         ui.label(format!("- 🧾 Format : {:?}", value.format()));
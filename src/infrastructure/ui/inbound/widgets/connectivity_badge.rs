@@ -0,0 +1,12 @@
+use eframe::egui::{Color32, Response, RichText, Ui};
+
+use crate::domain::connectivity::Connectivity;
+
+pub fn connectivity_badge(ui: &mut Ui, connectivity: Connectivity) -> Response {
+    let color = match connectivity {
+        Connectivity::Online => Color32::GREEN,
+        Connectivity::Offline => Color32::RED,
+        Connectivity::Unknown => Color32::GRAY,
+    };
+    ui.label(RichText::new(format!("● {}", connectivity)).color(color))
+}
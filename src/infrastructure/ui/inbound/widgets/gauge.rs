@@ -1,6 +1,6 @@
 use eframe::egui::{self, Color32, Pos2, Rect, Response, Sense, Ui, Vec2};
 
-pub fn gauge(ui: &mut Ui, value: u64, min: u64, max: u64, size: Vec2) -> Response {
+pub fn gauge(ui: &mut Ui, value: f64, min: f64, max: f64, size: Vec2) -> Response {
     // Reserve l'espace
     let (rect, response) = ui.allocate_exact_size(size, Sense::hover());
     let painter = ui.painter_at(rect);
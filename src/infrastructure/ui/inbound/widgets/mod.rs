@@ -1 +1,2 @@
-pub mod gauge;
\ No newline at end of file
+pub mod gauge;
+pub mod connectivity_badge;
\ No newline at end of file
@@ -1,10 +1,14 @@
 use std::{env::VarError, sync::{Arc, Mutex, MutexGuard}};
 
+#[cfg(any(feature = "mqtt", feature = "reqwest"))]
+use base64::Engine;
 #[cfg(feature = "mqtt_outbound")]
 use rumqttc::{AsyncClient, EventLoop, MqttOptions};
 #[cfg(not(feature = "mqtt_inbound"))]
 use tracing::{error, warn};
 
+#[cfg(all(feature = "egui_inbound", not(feature = "mqtt_inbound")))]
+use crate::application::ports::inbound::device_group_service::DeviceGroupServiceError;
 #[cfg(not(feature = "mqtt_inbound"))]
 use crate::application::ports::inbound::{device_service::DeviceServiceError, device_state_service::DeviceStateServiceError, event_service::EventServiceError};
 
@@ -51,9 +55,18 @@ pub struct MQTTConfig {
     pub mqtt_url: String,
     pub mqtt_port: u16,
     pub device_topic: String,
+    pub device_model_topic: String,
+    pub device_group_topic: String,
     pub device_state_topic: String,
+    #[cfg(feature = "mqtt_inbound")]
+    pub device_shadow_topic: String,
+    #[cfg(feature = "mqtt_inbound")]
+    pub connectivity_topic: String,
     pub event_topic: String,
     pub action_topic: String,
+    #[cfg(feature = "mqtt_inbound")]
+    pub action_ack_topic: String,
+    pub rule_topic: String,
 }
 
 #[cfg(feature = "mqtt")]
@@ -62,16 +75,34 @@ pub fn load_mqtt_config_from_env() -> Result<MQTTConfig, VarError> {
     let mqtt_url = std::env::var(format!("MQTT_URL"))?;
     let mqtt_port = std::env::var(format!("MQTT_PORT"))?;
     let device_topic = std::env::var(format!("MQTT_DEVICE_TOPIC"))?;
+    let device_model_topic = std::env::var("MQTT_DEVICE_MODEL_TOPIC")?;
+    let device_group_topic = std::env::var("MQTT_DEVICE_GROUP_TOPIC")?;
     let device_state_topic = std::env::var(format!("MQTT_DEVICE_STATE_TOPIC"))?;
+    #[cfg(feature = "mqtt_inbound")]
+    let device_shadow_topic = std::env::var("MQTT_DEVICE_SHADOW_TOPIC")?;
+    #[cfg(feature = "mqtt_inbound")]
+    let connectivity_topic = std::env::var("MQTT_CONNECTIVITY_TOPIC")?;
     let event_topic = std::env::var(format!("MQTT_EVENT_TOPIC"))?;
     let action_topic = std::env::var(format!("MQTT_ACTION_TOPIC"))?;
+    #[cfg(feature = "mqtt_inbound")]
+    let action_ack_topic = std::env::var("MQTT_ACTION_ACK_TOPIC")?;
+    let rule_topic = std::env::var("MQTT_RULE_TOPIC")?;
     Ok(MQTTConfig {
         mqtt_url,
         mqtt_port: mqtt_port.parse().map_err(|_| VarError::NotPresent)?,
         device_topic,
+        device_model_topic,
+        device_group_topic,
         device_state_topic,
+        #[cfg(feature = "mqtt_inbound")]
+        device_shadow_topic,
+        #[cfg(feature = "mqtt_inbound")]
+        connectivity_topic,
         event_topic,
-        action_topic
+        action_topic,
+        #[cfg(feature = "mqtt_inbound")]
+        action_ack_topic,
+        rule_topic,
     })
 }
 
@@ -90,6 +121,9 @@ pub struct HttpConfig {
     pub device_get_path: Option<String>,
     pub device_get_by_physical_id_path: Option<String>,
     pub device_delete_path: Option<String>,
+    pub device_model_get_path: Option<String>,
+    pub device_group_get_path: Option<String>,
+    pub rule_get_path: Option<String>,
     pub device_state_create_path: Option<String>,
     pub device_state_update_path: Option<String>,
     pub device_state_get_path: Option<String>,
@@ -107,6 +141,9 @@ pub fn load_http_config_from_env() -> Result<HttpConfig, VarError> {
     let device_get_path = std::env::var("HTTP_DEVICE_GET_PATH").ok();
     let device_get_by_physical_id_path = std::env::var("HTTP_DEVICE_GET_BY_PHYSICAL_PATH").ok();
     let device_delete_path = std::env::var("HTTP_DEVICE_DELETE_PATH").ok();
+    let device_model_get_path = std::env::var("HTTP_DEVICE_MODEL_GET_PATH").ok();
+    let device_group_get_path = std::env::var("HTTP_DEVICE_GROUP_GET_PATH").ok();
+    let rule_get_path = std::env::var("HTTP_RULE_GET_PATH").ok();
     let device_state_create_path = std::env::var("HTTP_DEVICE_STATE_CREATE_PATH").ok();
     let device_state_update_path = std::env::var("HTTP_DEVICE_STATE_UPDATE_PATH").ok();
     let device_state_get_path = std::env::var("HTTP_DEVICE_STATE_GET_PATH").ok();
@@ -121,6 +158,9 @@ pub fn load_http_config_from_env() -> Result<HttpConfig, VarError> {
         device_get_path,
         device_get_by_physical_id_path,
         device_delete_path,
+        device_model_get_path,
+        device_group_get_path,
+        rule_get_path,
         device_state_create_path,
         device_state_update_path,
         device_state_get_path,
@@ -130,6 +170,26 @@ pub fn load_http_config_from_env() -> Result<HttpConfig, VarError> {
     })
 }
 
+/// Puts an encoded payload in a text envelope, binary formats are base64 encoded.
+#[cfg(any(feature = "mqtt_outbound", feature = "reqwest"))]
+pub fn payload_to_text(data: Vec<u8>, is_binary: bool) -> Result<String, String> {
+    if is_binary {
+        return Ok(base64::engine::general_purpose::STANDARD.encode(data));
+    }
+    String::from_utf8(data).map_err(|e| e.to_string())
+}
+
+/// Extracts an encoded payload from a text envelope, see `payload_to_text`.
+#[cfg(any(feature = "mqtt_inbound", feature = "mqtt_client_outbound"))]
+pub fn payload_from_text(data: &str, is_binary: bool) -> Result<Vec<u8>, String> {
+    if is_binary {
+        return base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|e| e.to_string());
+    }
+    Ok(data.as_bytes().to_vec())
+}
+
 #[cfg(not(feature = "mqtt_inbound"))]
 pub fn log_device_service_error(err: &DeviceServiceError) {
     match err {
@@ -139,14 +199,25 @@ pub fn log_device_service_error(err: &DeviceServiceError) {
         DeviceServiceError::NotFound => {
             warn!(result = "warn", details = "device not found");
         }
-        DeviceServiceError::InvalidInput => {
-            warn!(result = "warn", details = "invalid input");
+        DeviceServiceError::InvalidInput(err) => {
+            warn!(result = "warn", details = format!("invalid input: {}", err));
         }
         DeviceServiceError::InternalError(err) => {
             error!(result = "error", details = %err);
         }
     }
 }
+#[cfg(all(feature = "egui_inbound", not(feature = "mqtt_inbound")))]
+pub fn log_device_group_service_error(err: &DeviceGroupServiceError) {
+    match err {
+        DeviceGroupServiceError::InternalError(err) => {
+            error!(result = "error", details = %err);
+        }
+        err => {
+            warn!(result = "warn", details = %err);
+        }
+    }
+}
 #[cfg(not(feature = "mqtt_inbound"))]
 pub fn log_device_state_service_error(err: &DeviceStateServiceError) {
     match err {
@@ -159,8 +230,8 @@ pub fn log_device_state_service_error(err: &DeviceStateServiceError) {
         DeviceStateServiceError::AlreadyExists => {
             warn!(result = "warn", details = "device state already exists");
         }
-        DeviceStateServiceError::InvalidInput => {
-            warn!(result = "warn", details = "invalid input provided");
+        DeviceStateServiceError::InvalidInput(err) => {
+            warn!(result = "warn", details = format!("invalid input: {}", err));
         }
         DeviceStateServiceError::InternalError(err) => {
             error!(result = "error", details = %err);
@@ -5,8 +5,10 @@ mod infrastructure;
 
 use crate::{
     application::ports::app::AppInbound,
-    infrastructure::{app_inbound::get_app_inbound, app_outbound::get_app_outbound},
+    infrastructure::{action_scheduling, app_inbound::get_app_inbound, app_outbound::get_app_outbound},
 };
+#[cfg(not(feature = "mqtt_inbound"))]
+use crate::infrastructure::connectivity;
 
 #[tokio::main]
 async fn main() {
@@ -16,6 +18,14 @@ async fn main() {
 
     let app_inbound = get_app_inbound();
 
+    // the MQTT inbound runs its own monitor to publish the changes
+    #[cfg(not(feature = "mqtt_inbound"))]
+    tokio::spawn(connectivity::run_heartbeat_monitor(app_outbound.clone(), |_| {}));
+
+    if action_scheduling::RUNS_SCHEDULER {
+        tokio::spawn(action_scheduling::run_action_scheduler(app_outbound.clone()));
+    }
+
     match app_inbound.start_with_outbound(app_outbound).await {
         Ok(_) => println!("Application stopped successfully"),
         Err(e) => eprintln!("Failed to run application: {}", e),